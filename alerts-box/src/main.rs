@@ -0,0 +1,63 @@
+use twitch_sources_rework::{front_common::alerts::{components::AlertsQueue, AlertEvent, AlertsSourceOptions}, common_data::envelope::Envelope};
+use twitch_sources_rework::front_common::use_source_socket;
+use yew::{function_component, html, use_effect_with_deps, UseStateHandle, use_state, use_state_eq};
+
+#[function_component(App)]
+pub fn app() -> Html {
+    // gather data
+    let window = web_sys::window().expect("Gotta have a window object");
+    let location = window.location();
+
+    let query_string = location.search()
+        .unwrap_or_default()
+        .trim_start_matches('?')
+        .to_string();
+
+    let query_data = serde_urlencoded::de::from_str::<AlertsSourceOptions>(&query_string)
+        .unwrap_or_default();
+
+    let ws = use_source_socket("alerts");
+
+    // state setup
+    let error_state: UseStateHandle<Option<String>> = use_state_eq(|| None);
+    let last_message: UseStateHandle<Option<AlertEvent>> = use_state(|| None);
+
+    {
+        let ws = ws.clone();
+        let error_state = error_state.clone();
+        let last_message = last_message.clone();
+
+        use_effect_with_deps(move |ws_message| {
+            if let Some(message) = &**ws_message {
+                match serde_json::de::from_str::<Envelope>(message).map(Envelope::into_event) {
+                    Ok(Some(parsed)) => {
+
+                        match AlertEvent::try_from(parsed) {
+                            Ok(res) => last_message.set(Some(res)),
+                            Err(err) => error_state.set(Some(err.to_string())),
+                        }
+                    },
+                    // heartbeats and notices, nothing to show for them
+                    Ok(None) => (),
+                    Err(err) => error_state.set(Some(err.to_string())),
+                }
+            }
+
+            || ()
+        }, ws.message);
+    }
+
+    html! {
+        <>
+            <AlertsQueue
+                settings={query_data}
+                last_message={last_message.clone()}
+            />
+        </>
+    }
+}
+
+fn main() {
+    wasm_logger::init(wasm_logger::Config::default());
+    yew::start_app::<App>();
+}
@@ -0,0 +1,129 @@
+use twitch_sources_rework::front_common::{ad_break::*, SourceColor};
+
+use strum::{EnumIter, Display, IntoEnumIterator};
+use yew::{UseStateHandle, html, Html, use_state, function_component, Properties, use_state_eq, use_effect_with_deps};
+
+use crate::components::widgets::Carousel;
+use crate::components::widgets::TestButton;
+use crate::components::widgets::source_settings::TextInput;
+use crate::components::widgets::source_settings::SourceColorChooser;
+use crate::components::widgets::source_settings::StaticSourceLink;
+use crate::components::widgets::source_settings::LoginSourceLink;
+use crate::util::login_gate;
+
+const PREVIEW_DURATION_SECONDS: i64 = 30;
+
+#[derive(Default, Clone, PartialEq, Debug, EnumIter, Display)]
+#[strum(serialize_all="snake_case")]
+pub enum AdBreakSkins {
+    #[default]
+    Countdown
+}
+
+impl AdBreakSkins {
+    fn to_html(
+        &self,
+        state: UseStateHandle<AdBreakState>,
+        settings: AdBreakSourceOptions,
+    ) -> Html {
+        match self {
+            AdBreakSkins::Countdown => html! {
+                <components::AdBreakCountdown {settings} last_message={state} />
+            },
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct AdBreakCarouselProps {
+    settings: AdBreakSourceOptions,
+    carousel_state: UseStateHandle<usize>
+}
+
+#[function_component(AdBreakCarousel)]
+fn ad_break_carousel(props: &AdBreakCarouselProps) -> Html {
+    let source_state = use_state(AdBreakState::default);
+
+    // start a sample ad break every time the settings change, to see how it looks
+    {
+        let source_state = source_state.clone();
+        use_effect_with_deps(move |_| {
+            source_state.set(AdBreakState {
+                ends_at: Some(chrono::Utc::now() + chrono::Duration::seconds(PREVIEW_DURATION_SECONDS)),
+                duration_seconds: PREVIEW_DURATION_SECONDS,
+            });
+            || ()
+        }, props.settings.clone());
+    }
+
+    let skins: Vec<Html> = AdBreakSkins::iter().map(|skin| skin.to_html(
+        source_state.clone(),
+        props.settings.clone(),
+    )).collect();
+
+    html! {
+        <Carousel active_item={props.carousel_state.clone()} carousel_size={3} items={skins} height={300} />
+    }
+}
+
+#[function_component(AdBreakSettings)]
+pub fn ad_break_settings() -> Html {
+    let source_color = use_state_eq(|| SourceColor::default());
+    let message = use_state_eq(String::new);
+
+    let carousel_state = use_state_eq(|| 0);
+    let chosen_skin = AdBreakSkins::iter().get(*carousel_state).expect("No way carousel gets out of bounds of the iter");
+
+    let collected_options = AdBreakSourceOptions {
+        color: *source_color,
+        message: (*message).clone(),
+    };
+
+    html! {
+        <>
+            <h4 class="text-center">{ "Settings" }</h4>
+            <div class="container mb-3">
+                <div class="row gx-3 gy-4">
+
+                    <div class="col-6">
+                        <SourceColorChooser color={source_color.clone()} />
+                    </div>
+
+                    <div class="col-6">
+                        <TextInput
+                            title={"Message"}
+                            text_state={message}
+                            placeholder={AdBreakSourceOptions::default_message()}
+                        />
+                    </div>
+
+                    <AdBreakCarousel settings={collected_options.clone()} {carousel_state} />
+
+                    <div>
+                        <h5 class="text-center mb-2">{ "Link to source (will require to login to Twitch in OBS)" }</h5>
+                        // can't not have the generic, unfortunately
+                        <StaticSourceLink<AdBreakSourceOptions> options={collected_options.clone()} source_name={"ad_break"} skin={chosen_skin.to_string()} />
+                    </div>
+                    <div>
+                        <h5 class="text-center mb-2">{ "User-specific link" }</h5>
+                        {
+                            login_gate(html!{
+                                <LoginSourceLink<AdBreakSourceOptions> options={collected_options.clone()} source_name={"ad_break"} skin={chosen_skin.to_string()} />
+                            })
+                        }
+                    </div>
+
+                    <div class="d-flex flex-column justify-content-center align-items-center">
+                        <div>
+                            <h5>{"Start a test event"}</h5>
+                        </div>
+                        <div>
+                            { login_gate(html!{ <TestButton test_name={"ad_break"} timeout_secs={35} /> }) }
+                        </div>
+                    </div>
+
+                </div>
+            </div>
+        </>
+    }
+}
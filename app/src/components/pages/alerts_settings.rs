@@ -0,0 +1,198 @@
+use twitch_sources_rework::front_common::{alerts::*, SourceColor};
+
+use strum::{EnumIter, Display, IntoEnumIterator};
+use yew::{UseStateHandle, html, Html, use_state, function_component, Properties, use_state_eq, use_effect_with_deps};
+
+use crate::components::widgets::Carousel;
+use crate::components::widgets::TestButton;
+use crate::components::widgets::source_settings::NumberInput;
+use crate::components::widgets::source_settings::TextInput;
+use crate::components::widgets::source_settings::SourceColorChooser;
+use crate::components::widgets::source_settings::StaticSourceLink;
+use crate::components::widgets::source_settings::LoginSourceLink;
+use crate::util::login_gate;
+
+#[derive(Default, Clone, PartialEq, Debug, EnumIter, Display)]
+#[strum(serialize_all="snake_case")]
+pub enum AlertsSkins {
+    #[default]
+    Box
+}
+
+impl AlertsSkins {
+    fn to_html(
+        &self,
+        state: UseStateHandle<Option<AlertEvent>>,
+        settings: AlertsSourceOptions,
+    ) -> Html {
+        match self {
+            AlertsSkins::Box => html! {
+                <components::AlertsQueue {settings} last_message={state} />
+            },
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct AlertsCarouselProps {
+    settings: AlertsSourceOptions,
+    carousel_state: UseStateHandle<usize>
+}
+
+#[function_component(AlertsCarousel)]
+fn alerts_carousel(props: &AlertsCarouselProps) -> Html {
+    let source_state: UseStateHandle<Option<AlertEvent>> = use_state(|| None);
+
+    // replay a sample alert every time the settings change, to see how it looks
+    {
+        let source_state = source_state.clone();
+        use_effect_with_deps(move |_| {
+            source_state.set(Some(AlertEvent::new(AlertKind::Follow { user_name: "Cool_User".to_string() })));
+            || ()
+        }, props.settings.clone());
+    }
+
+    let skins: Vec<Html> = AlertsSkins::iter().map(|skin| skin.to_html(
+        source_state.clone(),
+        props.settings.clone(),
+    )).collect();
+
+    html! {
+        <Carousel active_item={props.carousel_state.clone()} carousel_size={3} items={skins} height={300} />
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct AlertTypeSettingsProps {
+    alert_type: AlertType,
+    duration_state: UseStateHandle<i64>,
+    template_state: UseStateHandle<String>,
+}
+
+#[function_component(AlertTypeSettings)]
+fn alert_type_settings(props: &AlertTypeSettingsProps) -> Html {
+    html! {
+        <>
+            <div class="col-4">
+                <NumberInput
+                    title={props.alert_type.name()}
+                    number_state={props.duration_state.clone()}
+                    min_val={1} max_val={60}
+                />
+            </div>
+            <div class="col-8">
+                <TextInput
+                    title={"Text"}
+                    text_state={props.template_state.clone()}
+                    placeholder={AlertsSourceOptions::default_template(props.alert_type)}
+                />
+            </div>
+        </>
+    }
+}
+
+#[function_component(AlertsSettings)]
+pub fn alerts_settings() -> Html {
+    let source_color = use_state_eq(|| SourceColor::default());
+
+    let follow_duration = use_state_eq(|| 5i64);
+    let sub_duration = use_state_eq(|| 5i64);
+    let resub_duration = use_state_eq(|| 5i64);
+    let gift_duration = use_state_eq(|| 5i64);
+    let cheer_duration = use_state_eq(|| 5i64);
+    let raid_duration = use_state_eq(|| 5i64);
+
+    let follow_template = use_state_eq(String::new);
+    let sub_template = use_state_eq(String::new);
+    let resub_template = use_state_eq(String::new);
+    let gift_template = use_state_eq(String::new);
+    let cheer_template = use_state_eq(String::new);
+    let raid_template = use_state_eq(String::new);
+
+    let min_bits = use_state_eq(|| 0i64);
+    let min_raid_viewers = use_state_eq(|| 0i64);
+
+    let carousel_state = use_state_eq(|| 0);
+    let chosen_skin = AlertsSkins::iter().get(*carousel_state).expect("No way carousel gets out of bounds of the iter");
+
+    let collected_options = AlertsSourceOptions {
+        color: *source_color,
+        follow_duration: *follow_duration as u32,
+        sub_duration: *sub_duration as u32,
+        resub_duration: *resub_duration as u32,
+        gift_duration: *gift_duration as u32,
+        cheer_duration: *cheer_duration as u32,
+        raid_duration: *raid_duration as u32,
+        min_bits: *min_bits,
+        min_raid_viewers: *min_raid_viewers,
+        follow_template: (*follow_template).clone(),
+        sub_template: (*sub_template).clone(),
+        resub_template: (*resub_template).clone(),
+        gift_template: (*gift_template).clone(),
+        cheer_template: (*cheer_template).clone(),
+        raid_template: (*raid_template).clone(),
+    };
+
+    html! {
+        <>
+            <h4 class="text-center">{ "Settings" }</h4>
+            <div class="container mb-3">
+                <div class="row gx-3 gy-4">
+
+                    <div class="col-12">
+                        <SourceColorChooser color={source_color.clone()} />
+                    </div>
+
+                    <h5 class="text-center mb-0">{ "Alert durations (seconds) and texts" }</h5>
+                    <AlertTypeSettings alert_type={AlertType::Follow} duration_state={follow_duration} template_state={follow_template} />
+                    <AlertTypeSettings alert_type={AlertType::Sub} duration_state={sub_duration} template_state={sub_template} />
+                    <AlertTypeSettings alert_type={AlertType::Resub} duration_state={resub_duration} template_state={resub_template} />
+                    <AlertTypeSettings alert_type={AlertType::Gift} duration_state={gift_duration} template_state={gift_template} />
+                    <AlertTypeSettings alert_type={AlertType::Cheer} duration_state={cheer_duration} template_state={cheer_template} />
+                    <AlertTypeSettings alert_type={AlertType::Raid} duration_state={raid_duration} template_state={raid_template} />
+
+                    <div class="col-6">
+                        <NumberInput
+                            title={"Minimum bits for a cheer alert"}
+                            number_state={min_bits}
+                            min_val={0} max_val={1_000_000}
+                        />
+                    </div>
+                    <div class="col-6">
+                        <NumberInput
+                            title={"Minimum viewers for a raid alert"}
+                            number_state={min_raid_viewers}
+                            min_val={0} max_val={1_000_000}
+                        />
+                    </div>
+
+                    <AlertsCarousel settings={collected_options.clone()} {carousel_state} />
+
+                    <div>
+                        <h5 class="text-center mb-2">{ "Link to source (will require to login to Twitch in OBS)" }</h5>
+                        // can't not have the generic, unfortunately
+                        <StaticSourceLink<AlertsSourceOptions> options={collected_options.clone()} source_name={"alerts"} skin={chosen_skin.to_string()} />
+                    </div>
+                    <div>
+                        <h5 class="text-center mb-2">{ "User-specific link" }</h5>
+                        {
+                            login_gate(html!{
+                                <LoginSourceLink<AlertsSourceOptions> options={collected_options.clone()} source_name={"alerts"} skin={chosen_skin.to_string()} />
+                            })
+                        }
+                    </div>
+
+                    <div class="d-flex flex-column justify-content-center align-items-center">
+                        <div>
+                            <h5>{"Start a test event"}</h5>
+                        </div>
+                        <div>
+                            { login_gate(html!{ <TestButton test_name={"alerts"} timeout_secs={40} /> }) }
+                        </div>
+                    </div>
+
+                </div>
+            </div>
+        </>
+    }
+}
@@ -6,6 +6,13 @@ use super::Index;
 use super::Page404;
 use super::PredictionsSettings;
 use super::HypetrainSettings;
+use super::PollSettings;
+use super::GoalSettings;
+use super::AlertsSettings;
+use super::RedemptionsSettings;
+use super::CharitySettings;
+use super::StreamStatusSettings;
+use super::AdBreakSettings;
 //use twitch_sources_rework::front_common::predictions::components::PredictionsPie;
 
 #[derive(Clone, Routable, PartialEq, Copy)]
@@ -16,6 +23,20 @@ pub enum BaseRoute {
     Predictions,
     #[at("/hype_train")]
     HypeTrain,
+    #[at("/poll")]
+    Poll,
+    #[at("/goals")]
+    Goals,
+    #[at("/alerts")]
+    Alerts,
+    #[at("/redemptions")]
+    Redemptions,
+    #[at("/charity")]
+    Charity,
+    #[at("/stream_status")]
+    StreamStatus,
+    #[at("/ad_break")]
+    AdBreak,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -27,6 +48,13 @@ fn switch(routes: &BaseRoute) -> Html {
         BaseRoute::NotFound => html! { <Page404 /> },
         BaseRoute::Predictions => html! { <PredictionsSettings /> },
         BaseRoute::HypeTrain => html! { <HypetrainSettings /> },
+        BaseRoute::Poll => html! { <PollSettings /> },
+        BaseRoute::Goals => html! { <GoalSettings /> },
+        BaseRoute::Alerts => html! { <AlertsSettings /> },
+        BaseRoute::Redemptions => html! { <RedemptionsSettings /> },
+        BaseRoute::Charity => html! { <CharitySettings /> },
+        BaseRoute::StreamStatus => html! { <StreamStatusSettings /> },
+        BaseRoute::AdBreak => html! { <AdBreakSettings /> },
     }
 }
 
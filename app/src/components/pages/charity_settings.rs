@@ -0,0 +1,149 @@
+use twitch_sources_rework::{front_common::{charity::*, SourceColor}, common_data::eventsub_msgs::CharityAmount};
+
+use strum::{EnumIter, Display, IntoEnumIterator};
+use yew::{UseStateHandle, html, Html, use_state, function_component, Properties, use_state_eq, use_effect_with_deps};
+
+use crate::components::widgets::Carousel;
+use crate::components::widgets::TestButton;
+use crate::components::widgets::source_settings::Checkbox;
+use crate::components::widgets::source_settings::NumberInput;
+use crate::components::widgets::source_settings::SourceColorChooser;
+use crate::components::widgets::source_settings::StaticSourceLink;
+use crate::components::widgets::source_settings::LoginSourceLink;
+use crate::util::login_gate;
+
+#[derive(Default, Clone, PartialEq, Debug, EnumIter, Display)]
+#[strum(serialize_all="snake_case")]
+pub enum CharitySkins {
+    #[default]
+    Tracker
+}
+
+impl CharitySkins {
+    fn to_html(
+        &self,
+        state: UseStateHandle<CharityCampaignState>,
+        donation_state: UseStateHandle<Option<CharityDonationState>>,
+        settings: CharitySourceOptions,
+    ) -> Html {
+        match self {
+            CharitySkins::Tracker => html! {
+                <components::CharityTracker {settings} last_message={state} last_donation={donation_state} />
+            },
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct CharityCarouselProps {
+    settings: CharitySourceOptions,
+    carousel_state: UseStateHandle<usize>
+}
+
+fn usd(value: i64) -> CharityAmount {
+    CharityAmount { value, decimal_places: 2, currency: "USD".to_string() }
+}
+
+#[function_component(CharityCarousel)]
+fn charity_carousel(props: &CharityCarouselProps) -> Html {
+    let source_state = use_state(|| CharityCampaignState {
+        id: "test".to_string(),
+        charity_name: "Some Cool Charity".to_string(),
+        current_amount: usd(26_050),
+        target_amount: usd(50_000),
+        ended: false,
+    });
+    let donation_state: UseStateHandle<Option<CharityDonationState>> = use_state(|| None);
+
+    // add a sample donation every time the settings change, to see how it looks
+    {
+        let donation_state = donation_state.clone();
+        use_effect_with_deps(move |_| {
+            donation_state.set(Some(CharityDonationState::new("Cool_User".to_string(), usd(500))));
+            || ()
+        }, props.settings);
+    }
+
+    let skins: Vec<Html> = CharitySkins::iter().map(|skin| skin.to_html(
+        source_state.clone(),
+        donation_state.clone(),
+        props.settings,
+    )).collect();
+
+    html! {
+        <Carousel active_item={props.carousel_state.clone()} carousel_size={3} items={skins} height={300} />
+    }
+}
+
+#[function_component(CharitySettings)]
+pub fn charity_settings() -> Html {
+    let source_color = use_state_eq(|| SourceColor::default());
+    let shown_donations = use_state_eq(|| 3i64);
+    let hide_donations = use_state_eq(|| false);
+
+    let carousel_state = use_state_eq(|| 0);
+    let chosen_skin = CharitySkins::iter().get(*carousel_state).expect("No way carousel gets out of bounds of the iter");
+
+    let collected_options = CharitySourceOptions {
+        color: *source_color,
+        shown_donations: *shown_donations as u32,
+        hide_donations: *hide_donations,
+    };
+
+    html! {
+        <>
+            <h4 class="text-center">{ "Settings" }</h4>
+            <div class="container mb-3">
+                <div class="row gx-3 gy-4">
+
+                    <div class="col-6">
+                        <SourceColorChooser color={source_color.clone()} />
+                    </div>
+
+                    <div class="col-6">
+                        <NumberInput
+                            title={"Recent donations shown"}
+                            number_state={shown_donations}
+                            min_val={1} max_val={10}
+                        />
+                    </div>
+
+                    <div class="col-12 d-flex justify-content-center">
+                        <Checkbox
+                            checked_state={hide_donations.clone()}
+                            is_active={true}
+                            id="hideDonations"
+                            label="Hide the recent donations"
+                        />
+                    </div>
+
+                    <CharityCarousel settings={collected_options} {carousel_state} />
+
+                    <div>
+                        <h5 class="text-center mb-2">{ "Link to source (will require to login to Twitch in OBS)" }</h5>
+                        // can't not have the generic, unfortunately
+                        <StaticSourceLink<CharitySourceOptions> options={collected_options.clone()} source_name={"charity"} skin={chosen_skin.to_string()} />
+                    </div>
+                    <div>
+                        <h5 class="text-center mb-2">{ "User-specific link" }</h5>
+                        {
+                            login_gate(html!{
+                                <LoginSourceLink<CharitySourceOptions> options={collected_options.clone()} source_name={"charity"} skin={chosen_skin.to_string()} />
+                            })
+                        }
+                    </div>
+
+                    <div class="d-flex flex-column justify-content-center align-items-center">
+                        <div>
+                            <h5>{"Start a test event"}</h5>
+                        </div>
+                        <div>
+                            { login_gate(html!{ <TestButton test_name={"charity"} timeout_secs={12} /> }) }
+                        </div>
+                    </div>
+
+                </div>
+            </div>
+        </>
+    }
+}
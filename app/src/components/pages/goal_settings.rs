@@ -0,0 +1,127 @@
+use twitch_sources_rework::{front_common::{goals::*, SourceColor}, common_data::eventsub_msgs::GoalType};
+
+use strum::{EnumIter, Display, IntoEnumIterator};
+use yew::{UseStateHandle, html, Html, use_state, function_component, Properties, use_state_eq};
+
+use crate::components::widgets::Carousel;
+use crate::components::widgets::TestButton;
+use crate::components::widgets::source_settings::Checkbox;
+use crate::components::widgets::source_settings::SourceColorChooser;
+use crate::components::widgets::source_settings::StaticSourceLink;
+use crate::components::widgets::source_settings::LoginSourceLink;
+use crate::util::login_gate;
+
+#[derive(Default, Clone, PartialEq, Debug, EnumIter, Display)]
+#[strum(serialize_all="snake_case")]
+pub enum GoalSkins {
+    #[default]
+    Bar
+}
+
+impl GoalSkins {
+    fn to_html(
+        &self,
+        state: UseStateHandle<GoalState>,
+        settings: GoalSourceOptions,
+    ) -> Html {
+        match self {
+            GoalSkins::Bar => html! {
+                <components::GoalBar {settings} last_message={state} />
+            },
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct GoalCarouselProps {
+    settings: GoalSourceOptions,
+    carousel_state: UseStateHandle<usize>
+}
+
+#[function_component(GoalCarousel)]
+fn goal_carousel(props: &GoalCarouselProps) -> Html {
+    let source_state = use_state(|| GoalState {
+        id: "test".to_string(),
+        type_: GoalType::Follow,
+        description: "Road to 1000 followers!".to_string(),
+        current_amount: 640,
+        target_amount: 1000,
+        ended: false,
+        is_achieved: false,
+    });
+
+    // the settings change doesn't send a new message, so the preview shouldn't hide itself
+    let settings = GoalSourceOptions { hide_after_end: false, ..props.settings };
+
+    let skins: Vec<Html> = GoalSkins::iter().map(|skin| skin.to_html(
+        source_state.clone(),
+        settings,
+    )).collect();
+
+    html! {
+        <Carousel active_item={props.carousel_state.clone()} carousel_size={3} items={skins} height={300} />
+    }
+}
+
+#[function_component(GoalSettings)]
+pub fn goal_settings() -> Html {
+    let source_color = use_state_eq(|| SourceColor::default());
+    let hide_after_end = use_state_eq(|| false);
+
+    let carousel_state = use_state_eq(|| 0);
+    let chosen_skin = GoalSkins::iter().get(*carousel_state).expect("No way carousel gets out of bounds of the iter");
+
+    let collected_options = GoalSourceOptions {
+        color: *source_color,
+        hide_after_end: *hide_after_end,
+    };
+
+    html! {
+        <>
+            <h4 class="text-center">{ "Settings" }</h4>
+            <div class="container mb-3">
+                <div class="row gx-3 gy-4">
+
+                    <div class="col-6">
+                        <SourceColorChooser color={source_color.clone()} />
+                    </div>
+
+                    <div class="col-6 d-flex align-items-center">
+                        <Checkbox
+                            checked_state={hide_after_end.clone()}
+                            is_active={true}
+                            id="hideAfterEnd"
+                            label="Hide the goal bar after the goal ends"
+                        />
+                    </div>
+
+                    <GoalCarousel settings={collected_options} {carousel_state} />
+
+                    <div>
+                        <h5 class="text-center mb-2">{ "Link to source (will require to login to Twitch in OBS)" }</h5>
+                        // can't not have the generic, unfortunately
+                        <StaticSourceLink<GoalSourceOptions> options={collected_options.clone()} source_name={"goals"} skin={chosen_skin.to_string()} />
+                    </div>
+                    <div>
+                        <h5 class="text-center mb-2">{ "User-specific link" }</h5>
+                        {
+                            login_gate(html!{
+                                <LoginSourceLink<GoalSourceOptions> options={collected_options.clone()} source_name={"goals"} skin={chosen_skin.to_string()} />
+                            })
+                        }
+                    </div>
+
+                    <div class="d-flex flex-column justify-content-center align-items-center">
+                        <div>
+                            <h5>{"Start a test event"}</h5>
+                        </div>
+                        <div>
+                            { login_gate(html!{ <TestButton test_name={"goals"} timeout_secs={17} /> }) }
+                        </div>
+                    </div>
+
+                </div>
+            </div>
+        </>
+    }
+}
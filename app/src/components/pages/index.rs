@@ -11,6 +11,13 @@ pub fn index() -> Html {
             <div class="list-group p-2">
                 <SourceLink href={BaseRoute::Predictions} name="Predictions" />
                 <SourceLink href={BaseRoute::HypeTrain} name="Hype train" />
+                <SourceLink href={BaseRoute::Poll} name="Polls" />
+                <SourceLink href={BaseRoute::Goals} name="Goals" />
+                <SourceLink href={BaseRoute::Alerts} name="Alerts" />
+                <SourceLink href={BaseRoute::Redemptions} name="Redemptions" />
+                <SourceLink href={BaseRoute::Charity} name="Charity" />
+                <SourceLink href={BaseRoute::StreamStatus} name="Stream status" />
+                <SourceLink href={BaseRoute::AdBreak} name="Ad breaks" />
                 <SourceLink href={BaseRoute::NotFound} name="...with more coming!" disabled=true />
             </div>
         </>
@@ -3,10 +3,24 @@ mod index;
 mod page404;
 mod prediction_settings;
 mod hypetrain_settings;
+mod poll_settings;
+mod goal_settings;
+mod alerts_settings;
+mod redemption_settings;
+mod charity_settings;
+mod stream_status_settings;
+mod ad_break_settings;
 
 pub use base::Base;
 pub use base::BaseRoute;
 pub use index::Index;
 pub use page404::Page404;
 pub use prediction_settings::PredictionsSettings;
-pub use hypetrain_settings::HypetrainSettings;
\ No newline at end of file
+pub use hypetrain_settings::HypetrainSettings;
+pub use poll_settings::PollSettings;
+pub use goal_settings::GoalSettings;
+pub use alerts_settings::AlertsSettings;
+pub use redemption_settings::RedemptionsSettings;
+pub use charity_settings::CharitySettings;
+pub use stream_status_settings::StreamStatusSettings;
+pub use ad_break_settings::AdBreakSettings;
\ No newline at end of file
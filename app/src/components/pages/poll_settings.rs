@@ -0,0 +1,149 @@
+use strum::Display;
+use strum::{EnumIter, IntoEnumIterator};
+use yew::prelude::*;
+use chrono::{offset::Utc, Duration};
+
+use twitch_sources_rework::front_common::SourceColor;
+use twitch_sources_rework::front_common::polls::*;
+
+use crate::components::widgets::Carousel;
+use crate::components::widgets::TestButton;
+use crate::components::widgets::source_settings::Checkbox;
+use crate::components::widgets::source_settings::SourceColorChooser;
+use crate::components::widgets::source_settings::StaticSourceLink;
+use crate::components::widgets::source_settings::LoginSourceLink;
+use crate::util::login_gate;
+
+#[derive(Default, Clone, PartialEq, Debug, EnumIter, Display)]
+#[strum(serialize_all="snake_case")]
+pub enum PollSkins {
+    #[default]
+    List
+}
+
+impl PollSkins {
+    fn to_html(
+        &self,
+        color: SourceColor,
+        show_votes: bool,
+        state: UseStateHandle<PollState>,
+        show_element_state: UseStateHandle<bool>,
+        show_status_state: UseStateHandle<bool>,
+        status_state: UseStateHandle<PollStatus>
+    ) -> Html {
+        match self {
+            PollSkins::List => html! {
+                <components::PollList {state} {color} {show_votes} {show_status_state} {show_element_state} {status_state}/>
+            },
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct PollCarouselProps {
+    color: SourceColor,
+    show_votes: bool,
+    carousel_state: UseStateHandle<usize>
+}
+
+#[function_component(PollCarousel)]
+fn poll_carousel(props: &PollCarouselProps) -> Html {
+    let source_state = use_state(|| PollState {
+        id: "".to_string(),
+        title: "Test title".to_string(),
+        choices: vec![
+            PollChoiceState {
+                id: "1".to_string(),
+                title: "Very complicated long title to test this".to_string(),
+                votes: 12,
+            }, PollChoiceState {
+                id: "2".to_string(),
+                title: "Title2".to_string(),
+                votes: 30,
+            }, PollChoiceState {
+                id: "3".to_string(),
+                title: "Title3".to_string(),
+                votes: 5,
+            }
+        ],
+        end_time: Utc::now() + Duration::minutes(5),
+        status: PollStatus::InProgress
+    });
+
+    let show_element_state = use_state(|| true);
+    let show_status_state = use_state(|| true);
+    let status_state = use_state(|| PollStatus::InProgress);
+
+    let skins: Vec<Html> = PollSkins::iter().map(|skin| skin.to_html(
+        props.color,
+        props.show_votes,
+        source_state.clone(),
+        show_element_state.clone(),
+        show_status_state.clone(),
+        status_state.clone()
+    )).collect();
+
+    html! {
+        <Carousel active_item={props.carousel_state.clone()} carousel_size={3} items={skins} height={300} />
+    }
+}
+
+#[function_component(PollSettings)]
+pub fn poll_settings() -> Html {
+    let source_color = use_state(|| SourceColor::default());
+    let show_votes = use_state(|| false);
+
+    let carousel_state = use_state_eq(|| 0);
+    let chosen_skin = PollSkins::iter().get(*carousel_state).expect("No way carousel gets out of bounds of the iter");
+
+    let collected_options = PollSourceOptions { color: *source_color, show_votes: *show_votes };
+
+    html! {
+        <>
+            <h4 class="text-center">{ "Settings" }</h4>
+            <div class="container mb-3">
+                <div class="row gx-3 gy-4">
+
+                    <div class="col-6">
+                        <SourceColorChooser color={source_color.clone()} />
+                    </div>
+
+                    <div class="col-6 d-flex align-items-center">
+                        <Checkbox
+                            checked_state={show_votes.clone()}
+                            is_active={true}
+                            id="showVotes"
+                            label="Show amount of votes for each choice"
+                        />
+                    </div>
+
+                    <PollCarousel color={*source_color} show_votes={*show_votes} {carousel_state} />
+
+                    <div>
+                        <h5 class="text-center mb-2">{ "Link to source (will require to login to Twitch in OBS)" }</h5>
+                        // can't not have the generic, unfortunately
+                        <StaticSourceLink<PollSourceOptions> options={collected_options.clone()} source_name={"poll"} skin={chosen_skin.to_string()} />
+                    </div>
+                    <div>
+                        <h5 class="text-center mb-2">{ "User-specific link" }</h5>
+                        {
+                            login_gate(html!{
+                                <LoginSourceLink<PollSourceOptions> options={collected_options.clone()} source_name={"poll"} skin={chosen_skin.to_string()} />
+                            })
+                        }
+                    </div>
+
+                    <div class="d-flex flex-column justify-content-center align-items-center">
+                        <div>
+                            <h5>{"Start a test event"}</h5>
+                        </div>
+                        <div>
+                            { login_gate(html!{ <TestButton test_name={"poll"} timeout_secs={18} /> }) }
+                        </div>
+                    </div>
+
+                </div>
+            </div>
+        </>
+    }
+}
@@ -0,0 +1,229 @@
+use twitch_sources_client::apis::default_api::api_custom_rewards_get;
+use twitch_sources_client::models::CustomRewardInfo;
+use twitch_sources_rework::{front_common::{redemptions::*, SourceColor}, common_data::eventsub_msgs::RedemptionStatus};
+
+use strum::{EnumIter, Display, IntoEnumIterator};
+use uuid::Uuid;
+use yew::{UseStateHandle, html, Html, use_state, function_component, Properties, use_state_eq, use_effect_with_deps, InputEvent};
+use yew_hooks::use_async;
+use yewdux::prelude::use_store;
+
+use crate::components::widgets::Carousel;
+use crate::components::widgets::TestButton;
+use crate::components::widgets::source_settings::NumberInput;
+use crate::components::widgets::source_settings::SourceColorChooser;
+use crate::components::widgets::source_settings::StaticSourceLink;
+use crate::components::widgets::source_settings::LoginSourceLink;
+use crate::state::{ClientConfig, ErrorState};
+use crate::util::login_gate;
+
+#[derive(Default, Clone, PartialEq, Debug, EnumIter, Display)]
+#[strum(serialize_all="snake_case")]
+pub enum RedemptionsSkins {
+    #[default]
+    Feed
+}
+
+impl RedemptionsSkins {
+    fn to_html(
+        &self,
+        state: UseStateHandle<Option<RedemptionEvent>>,
+        settings: RedemptionsSourceOptions,
+    ) -> Html {
+        match self {
+            RedemptionsSkins::Feed => html! {
+                <components::RedemptionsFeed {settings} last_message={state} />
+            },
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct RedemptionsCarouselProps {
+    settings: RedemptionsSourceOptions,
+    carousel_state: UseStateHandle<usize>
+}
+
+#[function_component(RedemptionsCarousel)]
+fn redemptions_carousel(props: &RedemptionsCarouselProps) -> Html {
+    let source_state: UseStateHandle<Option<RedemptionEvent>> = use_state(|| None);
+
+    // add a sample redemption every time the settings change, to see how it looks
+    {
+        let source_state = source_state.clone();
+        use_effect_with_deps(move |_| {
+            source_state.set(Some(RedemptionEvent {
+                id: Uuid::new_v4(),
+                redemption_id: Uuid::new_v4().to_string(),
+                reward_id: "test".to_string(),
+                reward_title: "Hydrate!".to_string(),
+                cost: 500,
+                user_name: "Cool_User".to_string(),
+                user_input: "".to_string(),
+                status: RedemptionStatus::Unfulfilled,
+                is_update: false,
+            }));
+            || ()
+        }, props.settings.clone());
+    }
+
+    // the sample reward is not one of the chosen ones, so the preview shows everything
+    let settings = RedemptionsSourceOptions { reward_ids: String::new(), ..props.settings.clone() };
+
+    let skins: Vec<Html> = RedemptionsSkins::iter().map(|skin| skin.to_html(
+        source_state.clone(),
+        settings.clone(),
+    )).collect();
+
+    html! {
+        <Carousel active_item={props.carousel_state.clone()} carousel_size={3} items={skins} height={300} />
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct RewardChooserProps {
+    chosen_rewards: UseStateHandle<Vec<String>>,
+}
+
+#[function_component(RewardChooser)]
+fn reward_chooser(props: &RewardChooserProps) -> Html {
+    let (client_config, _) = use_store::<ClientConfig>();
+    let (_, error_state_setter) = use_store::<ErrorState>();
+
+    let async_state = use_async::<_, Vec<CustomRewardInfo>, ()>(async move {
+        match api_custom_rewards_get(&client_config.config).await {
+            Ok(rewards) => Ok(rewards),
+            Err(err) => {
+                error_state_setter.reduce(|_| ErrorState { show_error: true, error_message: err.to_string() });
+                Err(())
+            }
+        }
+    });
+
+    {
+        let async_state = async_state.clone();
+        use_effect_with_deps(move |_| {
+            async_state.run();
+            || ()
+        }, ());
+    }
+
+    let rewards = match &async_state.data {
+        Some(rewards) if !rewards.is_empty() => rewards,
+        Some(_) => return html! { <p class="text-center">{ "The channel has no custom rewards" }</p> },
+        None if async_state.error.is_some() => return html! { <p class="text-center">{ "Couldn't load the rewards" }</p> },
+        None => return html! { <p class="text-center">{ "Loading rewards..." }</p> },
+    };
+
+    html! {
+        <div class="p-3 border border-dark border-2">
+            <h5 class="text-center">{ "Only show these rewards (none chosen shows all of them)" }</h5>
+            {
+                rewards.iter().map(|reward| {
+                    let checkbox_id = "reward-".to_string() + &reward.id;
+                    let is_chosen = props.chosen_rewards.contains(&reward.id);
+
+                    let oninput = {
+                        let chosen_rewards = props.chosen_rewards.clone();
+                        let reward_id = reward.id.clone();
+
+                        move |_: InputEvent| {
+                            let mut rewards = (*chosen_rewards).clone();
+
+                            if is_chosen {
+                                rewards.retain(|id| *id != reward_id);
+                            } else {
+                                rewards.push(reward_id.clone());
+                            }
+
+                            chosen_rewards.set(rewards);
+                        }
+                    };
+
+                    html! {
+                        <div class="form-check">
+                            <input
+                                type="checkbox"
+                                id={checkbox_id.clone()}
+                                class="form-check-input"
+                                checked={is_chosen}
+                                {oninput}
+                            />
+                            <label class="form-label" for={checkbox_id}>
+                                { format!("{} ({})", reward.title, reward.cost) }
+                            </label>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
+
+#[function_component(RedemptionsSettings)]
+pub fn redemptions_settings() -> Html {
+    let source_color = use_state_eq(|| SourceColor::default());
+    let max_shown = use_state_eq(|| 5i64);
+    let chosen_rewards: UseStateHandle<Vec<String>> = use_state_eq(Vec::new);
+
+    let carousel_state = use_state_eq(|| 0);
+    let chosen_skin = RedemptionsSkins::iter().get(*carousel_state).expect("No way carousel gets out of bounds of the iter");
+
+    let collected_options = RedemptionsSourceOptions {
+        color: *source_color,
+        max_shown: *max_shown as u32,
+        reward_ids: chosen_rewards.join(","),
+    };
+
+    html! {
+        <>
+            <h4 class="text-center">{ "Settings" }</h4>
+            <div class="container mb-3">
+                <div class="row gx-3 gy-4">
+
+                    <div class="col-6">
+                        <SourceColorChooser color={source_color.clone()} />
+                    </div>
+
+                    <div class="col-6">
+                        <NumberInput
+                            title={"Redemptions shown"}
+                            number_state={max_shown}
+                            min_val={1} max_val={20}
+                        />
+                    </div>
+
+                    <div class="col-12">
+                        { login_gate(html!{ <RewardChooser chosen_rewards={chosen_rewards.clone()} /> }) }
+                    </div>
+
+                    <RedemptionsCarousel settings={collected_options.clone()} {carousel_state} />
+
+                    <div>
+                        <h5 class="text-center mb-2">{ "Link to source (will require to login to Twitch in OBS)" }</h5>
+                        // can't not have the generic, unfortunately
+                        <StaticSourceLink<RedemptionsSourceOptions> options={collected_options.clone()} source_name={"redemptions"} skin={chosen_skin.to_string()} />
+                    </div>
+                    <div>
+                        <h5 class="text-center mb-2">{ "User-specific link" }</h5>
+                        {
+                            login_gate(html!{
+                                <LoginSourceLink<RedemptionsSourceOptions> options={collected_options.clone()} source_name={"redemptions"} skin={chosen_skin.to_string()} />
+                            })
+                        }
+                    </div>
+
+                    <div class="d-flex flex-column justify-content-center align-items-center">
+                        <div>
+                            <h5>{"Start a test event"}</h5>
+                        </div>
+                        <div>
+                            { login_gate(html!{ <TestButton test_name={"redemptions"} timeout_secs={10} /> }) }
+                        </div>
+                    </div>
+
+                </div>
+            </div>
+        </>
+    }
+}
@@ -0,0 +1,132 @@
+use twitch_sources_rework::front_common::{stream_status::*, SourceColor};
+
+use strum::{EnumIter, Display, IntoEnumIterator};
+use yew::{UseStateHandle, html, Html, use_state, function_component, Properties, use_state_eq};
+
+use crate::components::widgets::Carousel;
+use crate::components::widgets::TestButton;
+use crate::components::widgets::source_settings::Checkbox;
+use crate::components::widgets::source_settings::SourceColorChooser;
+use crate::components::widgets::source_settings::StaticSourceLink;
+use crate::components::widgets::source_settings::LoginSourceLink;
+use crate::util::login_gate;
+
+#[derive(Default, Clone, PartialEq, Debug, EnumIter, Display)]
+#[strum(serialize_all="snake_case")]
+pub enum StreamStatusSkins {
+    #[default]
+    Uptime
+}
+
+impl StreamStatusSkins {
+    fn to_html(
+        &self,
+        status: UseStateHandle<StreamStatus>,
+        info: UseStateHandle<ChannelInfo>,
+        settings: StreamStatusSourceOptions,
+    ) -> Html {
+        match self {
+            StreamStatusSkins::Uptime => html! {
+                <components::StreamUptime {settings} {status} {info} />
+            },
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct StreamStatusCarouselProps {
+    settings: StreamStatusSourceOptions,
+    carousel_state: UseStateHandle<usize>
+}
+
+#[function_component(StreamStatusCarousel)]
+fn stream_status_carousel(props: &StreamStatusCarouselProps) -> Html {
+    let status = use_state(|| StreamStatus::Live {
+        started_at: chrono::Utc::now() - chrono::Duration::minutes(83)
+    });
+    let info = use_state(|| ChannelInfo {
+        title: "Some decently long stream title, just to see it fits".to_string(),
+        category_name: "Just Chatting".to_string(),
+    });
+
+    let skins: Vec<Html> = StreamStatusSkins::iter().map(|skin| skin.to_html(
+        status.clone(),
+        info.clone(),
+        props.settings,
+    )).collect();
+
+    html! {
+        <Carousel active_item={props.carousel_state.clone()} carousel_size={3} items={skins} height={300} />
+    }
+}
+
+#[function_component(StreamStatusSettings)]
+pub fn stream_status_settings() -> Html {
+    let source_color = use_state_eq(|| SourceColor::default());
+    let hide_title = use_state_eq(|| false);
+    let hide_category = use_state_eq(|| false);
+
+    let carousel_state = use_state_eq(|| 0);
+    let chosen_skin = StreamStatusSkins::iter().get(*carousel_state).expect("No way carousel gets out of bounds of the iter");
+
+    let collected_options = StreamStatusSourceOptions {
+        color: *source_color,
+        hide_title: *hide_title,
+        hide_category: *hide_category,
+    };
+
+    html! {
+        <>
+            <h4 class="text-center">{ "Settings" }</h4>
+            <div class="container mb-3">
+                <div class="row gx-3 gy-4">
+
+                    <div class="col-6">
+                        <SourceColorChooser color={source_color.clone()} />
+                    </div>
+
+                    <div class="col-6 d-flex flex-column justify-content-center">
+                        <Checkbox
+                            checked_state={hide_title.clone()}
+                            is_active={true}
+                            id="hideTitle"
+                            label="Hide the stream title"
+                        />
+                        <Checkbox
+                            checked_state={hide_category.clone()}
+                            is_active={true}
+                            id="hideCategory"
+                            label="Hide the category"
+                        />
+                    </div>
+
+                    <StreamStatusCarousel settings={collected_options} {carousel_state} />
+
+                    <div>
+                        <h5 class="text-center mb-2">{ "Link to source (will require to login to Twitch in OBS)" }</h5>
+                        // can't not have the generic, unfortunately
+                        <StaticSourceLink<StreamStatusSourceOptions> options={collected_options.clone()} source_name={"stream_status"} skin={chosen_skin.to_string()} />
+                    </div>
+                    <div>
+                        <h5 class="text-center mb-2">{ "User-specific link" }</h5>
+                        {
+                            login_gate(html!{
+                                <LoginSourceLink<StreamStatusSourceOptions> options={collected_options.clone()} source_name={"stream_status"} skin={chosen_skin.to_string()} />
+                            })
+                        }
+                    </div>
+
+                    <div class="d-flex flex-column justify-content-center align-items-center">
+                        <div>
+                            <h5>{"Start a test event"}</h5>
+                        </div>
+                        <div>
+                            { login_gate(html!{ <TestButton test_name={"stream_status"} timeout_secs={10} /> }) }
+                        </div>
+                    </div>
+
+                </div>
+            </div>
+        </>
+    }
+}
@@ -32,7 +32,7 @@ pub fn login_state() -> Html {
 
             match res {
                 Ok(data) => login_state_setter.reduce(|_| LoginStateData {
-                    info: Some(LoginInfo { username: data.username }),
+                    info: Some(LoginInfo { username: data.username, relogin_needed: data.relogin_needed }),
                     last_check: chrono::offset::Utc::now().naive_utc()
                 }),
                 // this is quite a cumbersome way to do this, but the best way (i think)
@@ -79,6 +79,14 @@ pub fn login_state() -> Html {
                 }
             } else {
                 match &login_state.info {
+                    Some(data) if data.relogin_needed => {
+                        html! {
+                            <>
+                                <span class="me-2">{ "Twitch login expired, please log in again" }</span>
+                                <LoginButton />
+                            </>
+                        }
+                    },
                     Some(data) => {
                         html! {
                             <span>{ "Welcome, "}{ data.username.clone() }</span>
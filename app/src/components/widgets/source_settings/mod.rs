@@ -4,10 +4,12 @@ mod static_source_link;
 mod login_source_link;
 mod number_input;
 mod checkbox;
+mod text_input;
 
 pub use boolean_chooser::BooleanChooser;
 pub use source_color_chooser::SourceColorChooser;
 pub use static_source_link::StaticSourceLink;
 pub use login_source_link::LoginSourceLink;
 pub use number_input::NumberInput;
-pub use checkbox::Checkbox;
\ No newline at end of file
+pub use checkbox::Checkbox;
+pub use text_input::TextInput;
\ No newline at end of file
@@ -0,0 +1,39 @@
+use wasm_bindgen::JsCast as _;
+use web_sys::Event;
+use yew::{UseStateHandle, Properties, function_component, html, Callback};
+
+#[derive(PartialEq, Properties)]
+pub struct TextInputProps {
+    pub title: &'static str,
+    pub text_state: UseStateHandle<String>,
+    pub placeholder: &'static str,
+}
+
+#[function_component(TextInput)]
+pub fn text_input(props: &TextInputProps) -> Html {
+    html!{
+        <div class="p-3 border border-dark border-2 h-100">
+            <h5 class="text-center">{ props.title }</h5>
+            <div class="form-check">
+                <input
+                    type="text"
+                    class="form-control text-center"
+                    placeholder={props.placeholder}
+                    value={(*props.text_state).clone()}
+                    onchange={
+                        let text_state = props.text_state.clone();
+                        Callback::from(move |ev: Event| {
+                            let input = ev
+                                .target()
+                                .unwrap()
+                                .dyn_into::<web_sys::HtmlInputElement>()
+                                .unwrap();
+
+                            text_state.set(input.value())
+                        }
+                    )}
+                />
+            </div>
+        </div>
+    }
+}
@@ -20,7 +20,8 @@ pub struct ErrorState {
 
 #[derive(Clone, PartialEq)]
 pub struct LoginInfo {
-    pub username: String
+    pub username: String,
+    pub relogin_needed: bool
 }
 
 #[derive(Default, Store, Clone, PartialEq)]
@@ -1,14 +1,12 @@
-use twitch_sources_rework::{front_common::hypetrain::{components::HypetrainNormal, HypetrainState, HypetrainSourceOptions}, common_data::eventsub_msgs::EventSubMessage};
+use twitch_sources_rework::{front_common::hypetrain::{components::HypetrainNormal, HypetrainState, HypetrainSourceOptions}, common_data::envelope::Envelope};
+use twitch_sources_rework::front_common::use_source_socket;
 use yew::{function_component, html, use_effect_with_deps, UseStateHandle, use_state_eq};
-use yew_hooks::{use_web_socket_with_options, UseWebSocketOptions};
 
 #[function_component(App)]
 pub fn app() -> Html {
     // gather data
     let window = web_sys::window().expect("Gotta have a window object");
     let location = window.location();
-    let path = location.host().expect("Gotta have a location origin");
-    let protocol = location.protocol().expect("Gotta have a location protocol");
 
     let query_string = location.search()
         .unwrap_or_default()
@@ -18,11 +16,7 @@ pub fn app() -> Html {
     let query_data = serde_urlencoded::de::from_str::<HypetrainSourceOptions>(&query_string)
         .unwrap_or_default();
 
-    let ws_protocol = if protocol.contains("https") { "wss".to_string() } else { "ws".to_string() };
-    let ws = use_web_socket_with_options(
-        ws_protocol + "://" + &path + "/ws/sources/hype_train",
-        UseWebSocketOptions { reconnect_limit: Some(1000), reconnect_interval: Some(10000), ..Default::default() }
-    );
+    let ws = use_source_socket("hype_train");
 
     // state setup
     let error_state: UseStateHandle<Option<String>> = use_state_eq(|| None);
@@ -35,8 +29,8 @@ pub fn app() -> Html {
 
         use_effect_with_deps(move |ws_message| {
             if let Some(message) = &**ws_message {
-                match serde_json::de::from_str::<EventSubMessage>(message) {
-                    Ok(parsed) => {
+                match serde_json::de::from_str::<Envelope>(message).map(Envelope::into_event) {
+                    Ok(Some(parsed)) => {
 
                         match HypetrainState::try_from(parsed) {
                             Ok(res) => {
@@ -56,6 +50,8 @@ pub fn app() -> Html {
                             Err(err) => error_state.set(Some(err.to_string())),
                         }
                     },
+                    // heartbeats and notices, nothing to show for them
+                    Ok(None) => (),
                     Err(err) => error_state.set(Some(err.to_string())),
                 }
             }
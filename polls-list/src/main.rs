@@ -0,0 +1,101 @@
+use twitch_sources_rework::common_data::envelope::Envelope;
+use twitch_sources_rework::front_common::use_source_socket;
+use twitch_sources_rework::front_common::polls::PollState;
+use twitch_sources_rework::front_common::polls::PollStateAnimator;
+use twitch_sources_rework::front_common::polls::PollSourceOptions;
+use twitch_sources_rework::front_common::polls::PollStatus;
+use twitch_sources_rework::front_common::polls::components::PollList;
+use yew::prelude::*;
+
+#[function_component(App)]
+fn app() -> Html {
+    // gather data
+    let window = web_sys::window().expect("Gotta have a window object");
+    let location = window.location();
+
+    let query_string = location.search()
+        .unwrap_or_default()
+        .trim_start_matches('?')
+        .to_string();
+
+    let query_data = serde_urlencoded::de::from_str::<PollSourceOptions>(&query_string)
+        .unwrap_or_default();
+
+    let ws = use_source_socket("poll");
+
+    // state setup
+    let error_state: UseStateHandle<Option<String>> = use_state(|| None);
+
+    let source = use_state(|| PollState::default());
+    let show_element = use_state(|| false);
+    let show_status = use_state(|| true);
+    let status = use_state(|| PollStatus::default());
+
+    let animator = use_mut_ref(|| PollStateAnimator::new(
+        source.setter(),
+        &source,
+        show_element.setter(),
+        show_status.setter(),
+        status.setter()
+    ));
+
+    {
+        let ws = ws.clone();
+        let state = source.clone();
+        let animator = animator.clone();
+        let error_state = error_state.clone();
+
+        use_effect_with_deps(move |message| {
+            if let Some(message) = &**message {
+                match serde_json::de::from_str::<Envelope>(message).map(Envelope::into_event) {
+                    Ok(Some(parsed)) => {
+
+                        match parsed.try_into() {
+                            Ok(res) => {animator.borrow_mut().set_state(res, &*state)},
+                            Err(err) => error_state.set(Some(err.to_string())),
+                        }
+                    },
+                    // heartbeats and notices, nothing to show for them
+                    Ok(None) => (),
+                    Err(err) => error_state.set(Some(err.to_string())),
+                }
+            }
+            || ()
+        }, ws.message)
+    }
+
+    if let Some(err_text) = &*error_state {
+        return html! {
+            <>
+                <div>{ "Error!" }</div>
+                <div>{ err_text }</div>
+                <div>{ "Try refreshing, and if error occurs again, please let us know" }</div>
+            </>
+        }
+    }
+
+    match *ws.ready_state {
+        yew_hooks::UseWebSocketReadyState::Connecting => html! {
+            <h4 class="text-center">{ "Connecting..." }</h4>
+        },
+        yew_hooks::UseWebSocketReadyState::Open => html! {
+            <PollList
+                color={query_data.color}
+                show_votes={query_data.show_votes}
+                state={source}
+                show_element_state={show_element}
+                show_status_state={show_status}
+                status_state={status}
+            />
+        },
+        yew_hooks::UseWebSocketReadyState::Closing
+      | yew_hooks::UseWebSocketReadyState::Closed => html! {
+            <h4 class="text-center">{ "Connection lost, reconnecting in 10 seconds..." }</h4>
+        },
+    }
+}
+
+fn main() {
+    wasm_logger::init(wasm_logger::Config::default());
+    yew::start_app::<App>();
+}
\ No newline at end of file
@@ -1,4 +1,5 @@
-use twitch_sources_rework::common_data::eventsub_msgs::EventSubMessage;
+use twitch_sources_rework::common_data::envelope::Envelope;
+use twitch_sources_rework::front_common::use_source_socket;
 use twitch_sources_rework::front_common::predictions::PredictionState;
 use twitch_sources_rework::front_common::predictions::PredictionStateAnimator;
 use twitch_sources_rework::front_common::predictions::PredictionsSourceOptions;
@@ -6,16 +7,11 @@ use twitch_sources_rework::front_common::predictions::PredictionStatus;
 use twitch_sources_rework::front_common::predictions::components::PredictionsList;
 use yew::prelude::*;
 
-use yew_hooks::use_web_socket_with_options;
-use yew_hooks::UseWebSocketOptions;
-
 #[function_component(App)]
 fn app() -> Html {
     // gather data
     let window = web_sys::window().expect("Gotta have a window object");
     let location = window.location();
-    let path = location.host().expect("Gotta have a location origin");
-    let protocol = location.protocol().expect("Gotta have a location protocol");
 
     let query_string = location.search()
         .unwrap_or_default()
@@ -25,11 +21,7 @@ fn app() -> Html {
     let query_data = serde_urlencoded::de::from_str::<PredictionsSourceOptions>(&query_string)
         .unwrap_or_default();
 
-    let ws_protocol = if protocol.contains("https") { "wss".to_string() } else { "ws".to_string() };
-    let ws = use_web_socket_with_options(
-        ws_protocol + "://" + &path + "/ws/sources/predictions",
-        UseWebSocketOptions { reconnect_limit: Some(1000), reconnect_interval: Some(10000), ..Default::default() }
-    );
+    let ws = use_source_socket("predictions");
 
     // state setup
     let error_state: UseStateHandle<Option<String>> = use_state(|| None);
@@ -55,14 +47,16 @@ fn app() -> Html {
 
         use_effect_with_deps(move |message| {
             if let Some(message) = &**message {
-                match serde_json::de::from_str::<EventSubMessage>(message) {
-                    Ok(parsed) => {
+                match serde_json::de::from_str::<Envelope>(message).map(Envelope::into_event) {
+                    Ok(Some(parsed)) => {
 
                         match parsed.try_into() {
                             Ok(res) => {animator.borrow_mut().set_state(res, &*state)},
                             Err(err) => error_state.set(Some(err.to_string())),
                         }
                     },
+                    // heartbeats and notices, nothing to show for them
+                    Ok(None) => (),
                     Err(err) => error_state.set(Some(err.to_string())),
                 }
             }
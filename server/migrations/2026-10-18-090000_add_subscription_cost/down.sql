@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+alter table subscription
+drop column cost;
\ No newline at end of file
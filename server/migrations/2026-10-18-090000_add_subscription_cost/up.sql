@@ -0,0 +1,3 @@
+-- Your SQL goes here
+alter table subscription
+add column cost bigint not null default 0;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+alter table twitch_users
+drop column token_invalid;
\ No newline at end of file
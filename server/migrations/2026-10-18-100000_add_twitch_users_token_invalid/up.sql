@@ -0,0 +1,3 @@
+-- Your SQL goes here
+alter table twitch_users
+add column token_invalid boolean not null default false;
\ No newline at end of file
@@ -4,9 +4,9 @@ use diesel_async::RunQueryDsl;
 
 use crate::domain::auth_state::STATE_TIMEOUT;
 
-use super::{db_auth_state, DbError, Repository, ResultDb};
+use super::{db_auth_state, memory, DbError, MemoryRepository, PgRepository, Repository, ResultDb};
 
-#[derive(Queryable)]
+#[derive(Clone, Queryable)]
 pub struct AuthState {
     id: i64,
     state: String,
@@ -33,7 +33,7 @@ pub trait AuthStateDb {
 }
 
 #[async_trait::async_trait(?Send)]
-impl AuthStateDb for Repository {
+impl AuthStateDb for PgRepository {
     async fn get_state(&self, auth_state: &str) -> ResultDb<Option<AuthState>> {
         let mut db_conn = self.get_conn().await?;
 
@@ -63,3 +63,34 @@ impl AuthStateDb for Repository {
         Ok(())
     }
 }
+
+
+#[async_trait::async_trait(?Send)]
+impl AuthStateDb for MemoryRepository {
+    async fn get_state(&self, auth_state: &str) -> ResultDb<Option<AuthState>> {
+        Ok(self.tables().auth_states.iter().find(|item| item.state == auth_state).cloned())
+    }
+
+    async fn save_state(&self, state_token: &str) -> ResultDb<()> {
+        let mut tables = self.tables();
+        let now = memory::now();
+
+        tables.auth_states.retain(|item| item.creation >= now - STATE_TIMEOUT);
+
+        let id = tables.next_id();
+        tables.auth_states.push(AuthState { id, state: state_token.to_string(), creation: now });
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl AuthStateDb for Repository {
+    async fn get_state(&self, auth_state: &str) -> ResultDb<Option<AuthState>> {
+        dispatch!(self, get_state(auth_state))
+    }
+
+    async fn save_state(&self, state_token: &str) -> ResultDb<()> {
+        dispatch!(self, save_state(state_token))
+    }
+}
\ No newline at end of file
@@ -3,11 +3,11 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use rand::Rng;
 
-use super::{quick_login_token, Repository, DbError, ResultDb};
+use super::{quick_login_token, MemoryRepository, PgRepository, Repository, DbError, ResultDb};
 
 const TOKEN_LENGTH: u32 = 20;
 
-#[derive(Queryable)]
+#[derive(Clone, Queryable)]
 pub struct LoginToken {
     pub id: i64,
     pub user_id: i64,
@@ -21,6 +21,11 @@ struct LoginTokenNew {
     token: String
 }
 
+fn new_login_token() -> String {
+    let mut rng = rand::thread_rng();
+    (0..TOKEN_LENGTH).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect()
+}
+
 #[async_trait::async_trait(?Send)]
 #[delegate]
 pub trait LoginTokenDb {
@@ -30,7 +35,7 @@ pub trait LoginTokenDb {
 }
 
 #[async_trait::async_trait(?Send)]
-impl LoginTokenDb for Repository {
+impl LoginTokenDb for PgRepository {
     async fn get_login_token(&self, user_id: i64) -> ResultDb<String> {
         let mut db_conn = self.get_conn().await?;
 
@@ -45,8 +50,7 @@ impl LoginTokenDb for Repository {
     async fn create_login_token(&self, user_id: i64) -> ResultDb<String> {
         let mut db_conn = self.get_conn().await?;
 
-        let mut rng = rand::thread_rng();
-        let login_token: String = (0..TOKEN_LENGTH).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect();
+        let login_token = new_login_token();
 
         diesel::insert_into(quick_login_token::table)
             .values(&LoginTokenNew { user_id, token: login_token.clone()  })
@@ -66,4 +70,53 @@ impl LoginTokenDb for Repository {
 
         Ok(login_token_item.user_id)
     }
+}
+
+#[async_trait::async_trait(?Send)]
+impl LoginTokenDb for MemoryRepository {
+    async fn get_login_token(&self, user_id: i64) -> ResultDb<String> {
+        self.tables().login_tokens
+            .iter()
+            .find(|item| item.user_id == user_id)
+            .map(|item| item.token.clone())
+            .ok_or(DbError::Other)
+    }
+
+    async fn create_login_token(&self, user_id: i64) -> ResultDb<String> {
+        let mut tables = self.tables();
+
+        // same as the foreign key
+        if !tables.users.iter().any(|user| user.id == user_id) {
+            return Err(DbError::Other);
+        }
+
+        let login_token = new_login_token();
+        let id = tables.next_id();
+        tables.login_tokens.push(LoginToken { id, user_id, token: login_token.clone() });
+
+        Ok(login_token)
+    }
+
+    async fn find_token(&self, token: &str) -> ResultDb<i64> {
+        self.tables().login_tokens
+            .iter()
+            .find(|item| item.token == token)
+            .map(|item| item.user_id)
+            .ok_or(DbError::Other)
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl LoginTokenDb for Repository {
+    async fn get_login_token(&self, user_id: i64) -> ResultDb<String> {
+        dispatch!(self, get_login_token(user_id))
+    }
+
+    async fn create_login_token(&self, user_id: i64) -> ResultDb<String> {
+        dispatch!(self, create_login_token(user_id))
+    }
+
+    async fn find_token(&self, token: &str) -> ResultDb<i64> {
+        dispatch!(self, find_token(token))
+    }
 }
\ No newline at end of file
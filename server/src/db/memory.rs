@@ -0,0 +1,42 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::{auth_state::AuthState, login_token::LoginToken, Subscription, TwitchUser};
+
+// rows of every table, kept the way Postgres would return them
+#[derive(Default)]
+pub(super) struct Tables {
+    pub auth_states: Vec<AuthState>,
+    pub login_tokens: Vec<LoginToken>,
+    pub subscriptions: Vec<Subscription>,
+    pub users: Vec<TwitchUser>,
+    last_id: i64,
+}
+
+impl Tables {
+    // one sequence for all tables is fine, ids only need to be unique
+    pub fn next_id(&mut self) -> i64 {
+        self.last_id += 1;
+        self.last_id
+    }
+}
+
+/// Keeps everything in memory, nothing survives a restart.
+#[derive(Clone, Default)]
+pub struct MemoryRepository {
+    tables: Arc<Mutex<Tables>>
+}
+
+impl MemoryRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().expect("Memory repository lock poisoned")
+    }
+}
+
+pub(super) fn now() -> time::PrimitiveDateTime {
+    let odt = time::OffsetDateTime::now_utc();
+    time::PrimitiveDateTime::new(odt.date(), odt.time())
+}
\ No newline at end of file
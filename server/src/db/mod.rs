@@ -1,4 +1,15 @@
+// calls the same method on whichever backend the repository uses
+macro_rules! dispatch {
+    ($repo:expr, $method:ident($($arg:expr),*)) => {
+        match $repo {
+            Repository::Postgres(repo) => repo.$method($($arg),*).await,
+            Repository::Memory(repo) => repo.$method($($arg),*).await,
+        }
+    };
+}
+
 mod schema;
+mod memory;
 mod auth_state;
 mod users;
 mod login_token;
@@ -14,10 +25,12 @@ use schema::subscription as db_subscription;
 pub use auth_state::AuthStateDb;
 pub use users::TwitchUser;
 pub use users::NewTwitchUser;
+pub use users::UserTokens;
 pub use users::TwitchUserDb;
 pub use login_token::LoginTokenDb;
 pub use subscription::Subscription;
 pub use subscription::SubscriptionDb;
+pub use memory::MemoryRepository;
 
 use thiserror::Error;
 
@@ -25,11 +38,11 @@ use crate::DbPool;
 //use crate::errors::{MyErrors, IntoResultMyErr};
 
 #[derive(Clone)]
-pub struct Repository {
+pub struct PgRepository {
     pool: DbPool
 }
 
-impl Repository {
+impl PgRepository {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
@@ -39,9 +52,22 @@ impl Repository {
     }
 }
 
+/// Storage the app runs with, Postgres or in memory for tests and running without a DB.
+#[derive(Clone)]
+pub enum Repository {
+    Postgres(PgRepository),
+    Memory(MemoryRepository),
+}
+
 impl From<DbPool> for Repository {
     fn from(pool: DbPool) -> Self {
-        Self::new(pool)
+        Self::Postgres(PgRepository::new(pool))
+    }
+}
+
+impl From<MemoryRepository> for Repository {
+    fn from(repo: MemoryRepository) -> Self {
+        Self::Memory(repo)
     }
 }
 
@@ -31,6 +31,7 @@ diesel::table! {
         type_ -> Varchar,
         last_connect -> Timestamp,
         last_disconnect -> Timestamp,
+        cost -> Int8,
     }
 }
 
@@ -50,6 +51,7 @@ diesel::table! {
         scopes -> Array<Nullable<Text>>,
         #[max_length = 30]
         broadcaster_type -> Varchar,
+        token_invalid -> Bool,
     }
 }
 
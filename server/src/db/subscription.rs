@@ -5,7 +5,7 @@ use twitch_sources_rework::common_data::eventsub_msgs::SubType;
 
 use crate::http_client::twitch_client::SubData;
 
-use super::{db_subscription, DbError, Repository, ResultDb};
+use super::{db_subscription, memory, DbError, MemoryRepository, PgRepository, Repository, ResultDb};
 
 
 #[derive(Clone, Queryable, Identifiable, AsChangeset, Debug)]
@@ -17,7 +17,8 @@ pub struct Subscription {
     pub sub_id: String,
     pub type_: String,
     pub last_connect: time::PrimitiveDateTime,
-    pub last_disconnect: time::PrimitiveDateTime
+    pub last_disconnect: time::PrimitiveDateTime,
+    pub cost: i64
 }
 
 #[derive(Insertable)]
@@ -27,6 +28,7 @@ struct SubscriptionNew {
     secret: String,
     sub_id: String,
     type_: String,
+    cost: i64,
 }
 
 #[async_trait::async_trait]
@@ -35,13 +37,16 @@ pub trait SubscriptionDb {
     async fn get_subscriptions(&self, sub_types: &[SubType], user_id: Option<i64>) -> ResultDb<Vec<Subscription>>;
     async fn create_subscriptions(&self, new_subs: Vec<SubData>, user_id: Option<i64>) -> ResultDb<Vec<Subscription>>;
     async fn get_subscription(&self, sub_id: &str) -> ResultDb<Option<Subscription>>;
+    async fn get_all_subscriptions(&self) -> ResultDb<Vec<Subscription>>;
     async fn remove_subscription(&self, sub_id: &str) -> ResultDb<()>;
+    /// Removes the ones saved without a secret, which are the websocket transport ones
+    async fn remove_websocket_subscriptions(&self) -> ResultDb<()>;
     async fn update_connect_time_by_id(&self, sub_id: &str) -> ResultDb<()>;
     async fn update_disconnect_time_by_id(&self, sub_id: &str) -> ResultDb<()>;
 }
 
 #[async_trait::async_trait]
-impl SubscriptionDb for Repository {
+impl SubscriptionDb for PgRepository {
     async fn get_subscriptions(&self, sub_types: &[SubType], user_id: Option<i64>) -> ResultDb<Vec<Subscription>> {
         let mut db_conn = self.get_conn().await?;
 
@@ -66,9 +71,11 @@ impl SubscriptionDb for Repository {
 
         let new_subs = new_subs.into_iter().map(|item| SubscriptionNew {
             user_id,
-            secret: item.transport.secret.expect("To have the secret"),
+            // websocket subscriptions don't have a secret
+            secret: item.transport.secret.unwrap_or_default(),
             sub_id: item.id,
             type_: item.type_.to_string(),
+            cost: item.cost,
         }).collect::<Vec<_>>();
 
         diesel::insert_into(db_subscription::dsl::subscription)
@@ -86,6 +93,14 @@ impl SubscriptionDb for Repository {
             .map_err(|_| DbError::Other)
     }
 
+    async fn get_all_subscriptions(&self) -> ResultDb<Vec<Subscription>> {
+        let mut db_conn = self.get_conn().await?;
+
+        db_subscription::dsl::subscription
+            .load::<Subscription>(&mut db_conn).await
+            .map_err(|_| DbError::Other)
+    }
+
     async fn remove_subscription(&self, sub_id: &str) -> ResultDb<()> {
         let mut db_conn = self.get_conn().await?;
 
@@ -97,6 +112,17 @@ impl SubscriptionDb for Repository {
         Ok(())
     }
 
+    async fn remove_websocket_subscriptions(&self) -> ResultDb<()> {
+        let mut db_conn = self.get_conn().await?;
+
+        diesel::delete(db_subscription::dsl::subscription
+            .filter(db_subscription::dsl::secret.eq("")))
+            .execute(&mut db_conn).await
+            .map_err(|_| DbError::Other)?;
+
+        Ok(())
+    }
+
     async fn update_connect_time_by_id(&self, sub_id: &str) -> ResultDb<()> {
         let mut db_conn = self.get_conn().await?;
 
@@ -125,3 +151,119 @@ impl SubscriptionDb for Repository {
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl SubscriptionDb for MemoryRepository {
+    async fn get_subscriptions(&self, sub_types: &[SubType], user_id: Option<i64>) -> ResultDb<Vec<Subscription>> {
+        let sub_types = sub_types.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        Ok(self.tables().subscriptions
+            .iter()
+            .filter(|sub| sub_types.contains(&sub.type_))
+            .filter(|sub| user_id.is_none() || sub.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn create_subscriptions(&self, new_subs: Vec<SubData>, user_id: Option<i64>) -> ResultDb<Vec<Subscription>> {
+        let mut tables = self.tables();
+
+        // same as the unique constraint, nothing is inserted if one of them exists
+        if new_subs.iter().any(|new_sub| tables.subscriptions.iter().any(|sub| sub.sub_id == new_sub.id)) {
+            return Err(DbError::Other);
+        }
+
+        let now = memory::now();
+        let created = new_subs.into_iter().map(|item| Subscription {
+            id: tables.next_id(),
+            user_id,
+            secret: item.transport.secret.unwrap_or_default(),
+            sub_id: item.id,
+            type_: item.type_.to_string(),
+            last_connect: now,
+            last_disconnect: now,
+            cost: item.cost,
+        }).collect::<Vec<_>>();
+
+        tables.subscriptions.extend(created.iter().cloned());
+
+        Ok(created)
+    }
+
+    async fn get_subscription(&self, sub_id: &str) -> ResultDb<Option<Subscription>> {
+        Ok(self.tables().subscriptions.iter().find(|sub| sub.sub_id == sub_id).cloned())
+    }
+
+    async fn get_all_subscriptions(&self) -> ResultDb<Vec<Subscription>> {
+        Ok(self.tables().subscriptions.clone())
+    }
+
+    async fn remove_subscription(&self, sub_id: &str) -> ResultDb<()> {
+        self.tables().subscriptions.retain(|sub| sub.sub_id != sub_id);
+
+        Ok(())
+    }
+
+    async fn remove_websocket_subscriptions(&self) -> ResultDb<()> {
+        self.tables().subscriptions.retain(|sub| !sub.secret.is_empty());
+
+        Ok(())
+    }
+
+    async fn update_connect_time_by_id(&self, sub_id: &str) -> ResultDb<()> {
+        let now = memory::now();
+
+        self.tables().subscriptions
+            .iter_mut()
+            .filter(|sub| sub.sub_id == sub_id)
+            .for_each(|sub| sub.last_connect = now);
+
+        Ok(())
+    }
+
+    async fn update_disconnect_time_by_id(&self, sub_id: &str) -> ResultDb<()> {
+        let now = memory::now();
+
+        self.tables().subscriptions
+            .iter_mut()
+            .filter(|sub| sub.sub_id == sub_id)
+            .for_each(|sub| sub.last_disconnect = now);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl SubscriptionDb for Repository {
+    async fn get_subscriptions(&self, sub_types: &[SubType], user_id: Option<i64>) -> ResultDb<Vec<Subscription>> {
+        dispatch!(self, get_subscriptions(sub_types, user_id))
+    }
+
+    async fn create_subscriptions(&self, new_subs: Vec<SubData>, user_id: Option<i64>) -> ResultDb<Vec<Subscription>> {
+        dispatch!(self, create_subscriptions(new_subs, user_id))
+    }
+
+    async fn get_subscription(&self, sub_id: &str) -> ResultDb<Option<Subscription>> {
+        dispatch!(self, get_subscription(sub_id))
+    }
+
+    async fn get_all_subscriptions(&self) -> ResultDb<Vec<Subscription>> {
+        dispatch!(self, get_all_subscriptions())
+    }
+
+    async fn remove_subscription(&self, sub_id: &str) -> ResultDb<()> {
+        dispatch!(self, remove_subscription(sub_id))
+    }
+
+    async fn remove_websocket_subscriptions(&self) -> ResultDb<()> {
+        dispatch!(self, remove_websocket_subscriptions())
+    }
+
+    async fn update_connect_time_by_id(&self, sub_id: &str) -> ResultDb<()> {
+        dispatch!(self, update_connect_time_by_id(sub_id))
+    }
+
+    async fn update_disconnect_time_by_id(&self, sub_id: &str) -> ResultDb<()> {
+        dispatch!(self, update_disconnect_time_by_id(sub_id))
+    }
+}
@@ -2,7 +2,7 @@ use auto_delegate::delegate;
 use diesel::{prelude::*, associations::HasTable, query_builder::IntoUpdateTarget};
 use diesel_async::{RunQueryDsl, SaveChangesDsl};
 
-use super::{twitch_users, ResultDb, Repository, DbError};
+use super::{memory, twitch_users, db_subscription, ResultDb, MemoryRepository, PgRepository, Repository, DbError};
 
 #[derive(Insertable)]
 #[diesel(table_name = twitch_users)]
@@ -16,7 +16,18 @@ pub struct NewTwitchUser {
     pub broadcaster_type: String
 }
 
-#[derive(Queryable, Identifiable, AsChangeset, Debug)]
+/// What refreshing a token changes, written without touching the rest of the row.
+#[derive(AsChangeset)]
+#[diesel(table_name = twitch_users)]
+pub struct UserTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i32,
+    pub scopes: Vec<String>,
+    pub last_token_refresh: time::PrimitiveDateTime,
+}
+
+#[derive(Clone, Queryable, Identifiable, AsChangeset, Debug)]
 pub struct TwitchUser {
     pub id: i64,
     pub username: String,
@@ -27,7 +38,8 @@ pub struct TwitchUser {
     pub last_token_refresh: time::PrimitiveDateTime,
     pub expires_in: i32,
     pub scopes: Vec<Option<String>>,
-    pub broadcaster_type: String
+    pub broadcaster_type: String,
+    pub token_invalid: bool
 }
 
 #[async_trait::async_trait]
@@ -37,10 +49,14 @@ pub trait TwitchUserDb {
     async fn save_user(&self, user: &TwitchUser) -> ResultDb<()>;
     async fn insert_user(&self, new_user: NewTwitchUser) -> ResultDb<TwitchUser>;
     async fn delete_user(&self, user_id: i64) -> ResultDb<()>;
+    /// Users logged in or with an overlay connected since `active_since`, whose tokens weren't rejected
+    async fn get_active_users_with_valid_tokens(&self, active_since: time::PrimitiveDateTime) -> ResultDb<Vec<TwitchUser>>;
+    async fn update_user_tokens(&self, user_id: i64, tokens: &UserTokens) -> ResultDb<()>;
+    async fn mark_token_invalid(&self, user_id: i64) -> ResultDb<()>;
 }
 
 #[async_trait::async_trait]
-impl TwitchUserDb for Repository {
+impl TwitchUserDb for PgRepository {
     async fn get_user(&self, user_id: i64) -> ResultDb<Option<TwitchUser>> {
         let mut db_conn = self.get_conn().await?;
 
@@ -82,4 +98,171 @@ impl TwitchUserDb for Repository {
 
         Ok(())
     }
+
+    async fn get_active_users_with_valid_tokens(&self, active_since: time::PrimitiveDateTime) -> ResultDb<Vec<TwitchUser>> {
+        let mut db_conn = self.get_conn().await?;
+
+        let connected_since = diesel::dsl::exists(
+            db_subscription::dsl::subscription
+                .filter(db_subscription::dsl::user_id.eq(twitch_users::dsl::id.nullable()))
+                .filter(db_subscription::dsl::last_connect.ge(active_since))
+        );
+
+        twitch_users::dsl::twitch_users
+            .filter(twitch_users::dsl::token_invalid.eq(false))
+            .filter(twitch_users::dsl::last_login.ge(active_since).or(connected_since))
+            .load::<TwitchUser>(&mut db_conn)
+            .await
+            .map_err(|_| DbError::Other)
+    }
+
+    async fn update_user_tokens(&self, user_id: i64, tokens: &UserTokens) -> ResultDb<()> {
+        let mut db_conn = self.get_conn().await?;
+
+        diesel::update(twitch_users::table.filter(twitch_users::dsl::id.eq(user_id)))
+            .set(tokens)
+            .execute(&mut db_conn)
+            .await
+            .map_err(|_| DbError::Other)?;
+
+        Ok(())
+    }
+
+    async fn mark_token_invalid(&self, user_id: i64) -> ResultDb<()> {
+        let mut db_conn = self.get_conn().await?;
+
+        diesel::update(twitch_users::table.filter(twitch_users::dsl::id.eq(user_id)))
+            .set(twitch_users::dsl::token_invalid.eq(true))
+            .execute(&mut db_conn)
+            .await
+            .map_err(|_| DbError::Other)?;
+
+        Ok(())
+    }
 }
+
+
+#[async_trait::async_trait]
+impl TwitchUserDb for MemoryRepository {
+    async fn get_user(&self, user_id: i64) -> ResultDb<Option<TwitchUser>> {
+        Ok(self.tables().users.iter().find(|user| user.id == user_id).cloned())
+    }
+
+    async fn save_user(&self, user: &TwitchUser) -> ResultDb<()> {
+        let mut tables = self.tables();
+
+        let saved_user = tables.users
+            .iter_mut()
+            .find(|saved_user| saved_user.id == user.id)
+            .ok_or(DbError::Other)?;
+        *saved_user = user.clone();
+
+        Ok(())
+    }
+
+    async fn insert_user(&self, new_user: NewTwitchUser) -> ResultDb<TwitchUser> {
+        let mut tables = self.tables();
+
+        if tables.users.iter().any(|user| user.id == new_user.id) {
+            return Err(DbError::Other);
+        }
+
+        let now = memory::now();
+        let user = TwitchUser {
+            id: new_user.id,
+            username: new_user.username,
+            access_token: new_user.access_token,
+            refresh_token: new_user.refresh_token,
+            creation: now,
+            last_login: now,
+            last_token_refresh: now,
+            expires_in: new_user.expires_in,
+            scopes: new_user.scopes.into_iter().map(Some).collect(),
+            broadcaster_type: new_user.broadcaster_type,
+            token_invalid: false,
+        };
+
+        tables.users.push(user.clone());
+
+        Ok(user)
+    }
+
+    async fn delete_user(&self, user_id: i64) -> ResultDb<()> {
+        let mut tables = self.tables();
+
+        tables.users.retain(|user| user.id != user_id);
+        // same as the cascading foreign keys
+        tables.login_tokens.retain(|token| token.user_id != user_id);
+        tables.subscriptions.retain(|sub| sub.user_id != Some(user_id));
+
+        Ok(())
+    }
+
+    async fn get_active_users_with_valid_tokens(&self, active_since: time::PrimitiveDateTime) -> ResultDb<Vec<TwitchUser>> {
+        let tables = self.tables();
+
+        let connected_since = |user_id: i64| tables.subscriptions
+            .iter()
+            .any(|sub| sub.user_id == Some(user_id) && sub.last_connect >= active_since);
+
+        Ok(tables.users
+            .iter()
+            .filter(|user| !user.token_invalid)
+            .filter(|user| user.last_login >= active_since || connected_since(user.id))
+            .cloned()
+            .collect())
+    }
+
+    async fn update_user_tokens(&self, user_id: i64, tokens: &UserTokens) -> ResultDb<()> {
+        let mut tables = self.tables();
+
+        let user = tables.users.iter_mut().find(|user| user.id == user_id).ok_or(DbError::Other)?;
+        user.access_token = tokens.access_token.clone();
+        user.refresh_token = tokens.refresh_token.clone();
+        user.expires_in = tokens.expires_in;
+        user.scopes = tokens.scopes.iter().cloned().map(Some).collect();
+        user.last_token_refresh = tokens.last_token_refresh;
+
+        Ok(())
+    }
+
+    async fn mark_token_invalid(&self, user_id: i64) -> ResultDb<()> {
+        let mut tables = self.tables();
+
+        let user = tables.users.iter_mut().find(|user| user.id == user_id).ok_or(DbError::Other)?;
+        user.token_invalid = true;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TwitchUserDb for Repository {
+    async fn get_user(&self, user_id: i64) -> ResultDb<Option<TwitchUser>> {
+        dispatch!(self, get_user(user_id))
+    }
+
+    async fn save_user(&self, user: &TwitchUser) -> ResultDb<()> {
+        dispatch!(self, save_user(user))
+    }
+
+    async fn insert_user(&self, new_user: NewTwitchUser) -> ResultDb<TwitchUser> {
+        dispatch!(self, insert_user(new_user))
+    }
+
+    async fn delete_user(&self, user_id: i64) -> ResultDb<()> {
+        dispatch!(self, delete_user(user_id))
+    }
+
+    async fn get_active_users_with_valid_tokens(&self, active_since: time::PrimitiveDateTime) -> ResultDb<Vec<TwitchUser>> {
+        dispatch!(self, get_active_users_with_valid_tokens(active_since))
+    }
+
+    async fn update_user_tokens(&self, user_id: i64, tokens: &UserTokens) -> ResultDb<()> {
+        dispatch!(self, update_user_tokens(user_id, tokens))
+    }
+
+    async fn mark_token_invalid(&self, user_id: i64) -> ResultDb<()> {
+        dispatch!(self, mark_token_invalid(user_id))
+    }
+}
\ No newline at end of file
@@ -80,4 +80,33 @@ pub enum CheckStateError {
 pub enum GetNewStateError {
     #[error("Failed getting new state")]
     Fail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthState, CheckStateError};
+    use crate::db::MemoryRepository;
+
+    #[actix_web::test]
+    async fn new_state_gives_data_back() {
+        let repo = MemoryRepository::new();
+
+        let state = AuthState::get_new_state(&repo, "/goals").await.expect("State to be created");
+        let data = AuthState::check_state_and_get_data(&repo, &state).await.expect("State to be valid");
+
+        assert_eq!(data.redirect_uri, "/goals");
+    }
+
+    #[actix_web::test]
+    async fn unknown_state_is_rejected() {
+        let repo = MemoryRepository::new();
+        // made, but never saved to this repository
+        let state = AuthState::get_new_state(&MemoryRepository::new(), "/goals").await.expect("State to be created");
+
+        let res = AuthState::check_state_and_get_data(&repo, &state).await;
+        assert!(matches!(res, Err(CheckStateError::LoginAttemptNotFound)), "Expected not found, got {:?}", res.err());
+
+        let res = AuthState::check_state_and_get_data(&repo, "too_short").await;
+        assert!(matches!(res, Err(CheckStateError::LoginAttemptInvalid)), "Expected invalid, got {:?}", res.err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,366 @@
+use thiserror::Error;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::EventSubMessage;
+
+use crate::{db::{TwitchUserDb, DbError}, domain::users::{MaintainTokenError, TwitchUser}, http_client::twitch_client::{TwitchHttpClient, GetPredictionsError, GetHypeTrainEventsError, GetUserDataError}};
+
+/// Asks Twitch what's going on for the topic right now, made into the event Twitch would've sent,
+/// so overlays handle it like any other message. Only `predictions` and `hype_train` can be asked for.
+pub async fn get_live_event<Ctx: TwitchUserDb + TwitchHttpClient>(
+    ctx: &Ctx,
+    user_id: i64,
+    topic: &str
+) -> Result<Option<EventSubMessage>, GetLiveEventError> {
+    if topic != "predictions" && topic != "hype_train" {
+        return Ok(None);
+    }
+
+    let user = ctx.get_user(user_id).await
+        .map_err(GetLiveEventError::UserGetError)?
+        .ok_or(GetLiveEventError::UserNotFound)?;
+
+    let now = OffsetDateTime::now_utc();
+    let broadcaster_id = user.id.to_string();
+    let username = user.username.clone();
+    let access_token = TwitchUser::fresh_access_token(ctx, user).await?;
+
+    let data = if topic == "predictions" {
+        ctx.get_latest_prediction(&access_token, &broadcaster_id).await?
+            .and_then(http_conv::prediction_data)
+    } else {
+        let events = ctx.get_hype_train_events(&access_token, &broadcaster_id).await?;
+
+        match http_conv::running_hype_train(events, now) {
+            Some(train) => {
+                // helix only gives ids, eventsub also has the logins and names
+                let users = ctx.get_users(&access_token, &http_conv::hype_train_user_ids(&train)).await?;
+                Some(http_conv::hype_train_data(train, &users, &username))
+            },
+            None => None,
+        }
+    };
+
+    Ok(data.map(|data| EventSubMessage {
+        data,
+        msg_time: now.format(&Rfc3339).expect("Current time is always formattable")
+    }))
+}
+
+#[derive(Debug, Error)]
+pub enum GetLiveEventError {
+    #[error("Getting user data from DB failed: {0}")]
+    UserGetError(DbError),
+    #[error("User was not found")]
+    UserNotFound,
+    #[error("Getting a fresh user token failed: {0}")]
+    UserTokenError(#[from] MaintainTokenError),
+    #[error("Getting predictions failed: {0}")]
+    PredictionsError(#[from] GetPredictionsError),
+    #[error("Getting hype train events failed: {0}")]
+    HypeTrainEventsError(#[from] GetHypeTrainEventsError),
+    #[error("Getting hype train contributors failed: {0}")]
+    UsersError(#[from] GetUserDataError),
+}
+
+mod http_conv {
+    use std::collections::HashMap;
+
+    use time::{OffsetDateTime, Duration, format_description::well_known::Rfc3339};
+    use twitch_sources_rework::common_data::eventsub_msgs::{
+        EventSubData, ChannelPredictionProgress, ChannelPredictionLock, PredictionsOutcome, TopPredictior,
+        HypeTrainProgress, HypeTrainData, Contribution, ContributionType
+    };
+
+    use crate::http_client::twitch_client::{
+        PredictionObject, PredictionOutcomeObject, PredictionStatus, HypeTrainEventObject, HypeTrainEventData,
+        HypeTrainContributionObject, HypeTrainContributionType, UserDataObject
+    };
+
+    fn outcome(outcome: PredictionOutcomeObject) -> PredictionsOutcome {
+        PredictionsOutcome {
+            id: outcome.id,
+            title: outcome.title,
+            // eventsub sends these lowercase
+            color: outcome.color.to_lowercase(),
+            users: outcome.users,
+            channel_points: outcome.channel_points,
+            top_predictors: outcome.top_predictors.unwrap_or_default().into_iter().map(|predictor| TopPredictior {
+                user_id: predictor.user_id,
+                user_login: predictor.user_login,
+                user_name: predictor.user_name,
+                channel_points_won: predictor.channel_points_won,
+                channel_points_used: predictor.channel_points_used,
+            }).collect(),
+        }
+    }
+
+    // ended predictions have nothing to show anymore
+    pub(super) fn prediction_data(prediction: PredictionObject) -> Option<EventSubData> {
+        let outcomes = prediction.outcomes.into_iter().map(outcome).collect();
+
+        match prediction.status {
+            PredictionStatus::Active => {
+                let locks_at = OffsetDateTime::parse(&prediction.created_at, &Rfc3339).ok()?
+                    + Duration::seconds(prediction.prediction_window);
+
+                Some(EventSubData::ChannelPredictionProgress(ChannelPredictionProgress {
+                    id: prediction.id,
+                    broadcaster_user_id: prediction.broadcaster_id,
+                    broadcaster_user_login: prediction.broadcaster_login,
+                    broadcaster_user_name: prediction.broadcaster_name,
+                    title: prediction.title,
+                    outcomes,
+                    started_at: prediction.created_at,
+                    locks_at: locks_at.format(&Rfc3339).ok()?,
+                }))
+            },
+            PredictionStatus::Locked => Some(EventSubData::ChannelPredictionLock(ChannelPredictionLock {
+                id: prediction.id,
+                broadcaster_user_id: prediction.broadcaster_id,
+                broadcaster_user_login: prediction.broadcaster_login,
+                broadcaster_user_name: prediction.broadcaster_name,
+                title: prediction.title,
+                outcomes,
+                started_at: prediction.created_at,
+                locked_at: prediction.locked_at.unwrap_or_default(),
+            })),
+            PredictionStatus::Resolved | PredictionStatus::Canceled => None,
+        }
+    }
+
+    pub(super) struct RunningHypeTrain {
+        event: HypeTrainEventData,
+        progress: u64,
+    }
+
+    // the last event stays around after the train is over, only running trains count
+    pub(super) fn running_hype_train(events: Vec<HypeTrainEventObject>, now: OffsetDateTime) -> Option<RunningHypeTrain> {
+        let mut events = events.into_iter().map(|event| event.event_data);
+        let event = events.next()?;
+
+        let expires_at = OffsetDateTime::parse(&event.expires_at, &Rfc3339).ok()?;
+        if expires_at <= now {
+            return None;
+        }
+
+        let earlier: Vec<_> = events.filter(|earlier| earlier.id == event.id).collect();
+        let progress = level_progress(&event, &earlier);
+
+        Some(RunningHypeTrain { event, progress })
+    }
+
+    // helix only has the total of the whole train, the levels before took the goals of their own events.
+    // a level passed within a single contribution has no event, so it's kept under the current goal
+    fn level_progress(event: &HypeTrainEventData, earlier: &[HypeTrainEventData]) -> u64 {
+        let passed_goals: HashMap<u64, u64> = earlier.iter()
+            .filter(|earlier| earlier.level < event.level)
+            .map(|earlier| (earlier.level, earlier.goal))
+            .collect();
+
+        event.total
+            .saturating_sub(passed_goals.values().sum())
+            .min(event.goal)
+    }
+
+    pub(super) fn hype_train_user_ids(train: &RunningHypeTrain) -> Vec<String> {
+        let mut user_ids = vec![train.event.broadcaster_id.clone()];
+
+        let contributors = train.event.top_contributions.iter().chain([&train.event.last_contribution]);
+        for contribution in contributors {
+            if !user_ids.contains(&contribution.user) {
+                user_ids.push(contribution.user.clone());
+            }
+        }
+
+        user_ids
+    }
+
+    // login and name of the user, the id for both when the account is gone
+    fn user_names(user_id: &str, users: &[UserDataObject]) -> (String, String) {
+        match users.iter().find(|user| user.id == user_id) {
+            Some(user) => (user.login.clone(), user.display_name.clone()),
+            None => (user_id.to_string(), user_id.to_string()),
+        }
+    }
+
+    fn contribution(contribution: HypeTrainContributionObject, users: &[UserDataObject]) -> Contribution {
+        let (user_login, user_name) = user_names(&contribution.user, users);
+
+        Contribution {
+            user_id: contribution.user,
+            user_login,
+            user_name,
+            type_: match contribution.type_ {
+                HypeTrainContributionType::Bits => ContributionType::Bits,
+                HypeTrainContributionType::Subs => ContributionType::Subscription,
+                HypeTrainContributionType::Other => ContributionType::Other,
+            },
+            total: contribution.total,
+        }
+    }
+
+    pub(super) fn hype_train_data(train: RunningHypeTrain, users: &[UserDataObject], login: &str) -> EventSubData {
+        let event = train.event;
+
+        // the login saved for the user is the next best thing
+        let (broadcaster_user_login, broadcaster_user_name) = match users.iter().find(|user| user.id == event.broadcaster_id) {
+            Some(user) => (user.login.clone(), user.display_name.clone()),
+            None => (login.to_string(), login.to_string()),
+        };
+
+        EventSubData::HypeTrainProgress(HypeTrainProgress {
+            data: HypeTrainData {
+                id: event.id,
+                broadcaster_user_id: event.broadcaster_id,
+                broadcaster_user_login,
+                broadcaster_user_name,
+                total: event.total,
+                progress: train.progress,
+                goal: event.goal,
+                top_contributions: event.top_contributions.into_iter().map(|top| contribution(top, users)).collect(),
+                last_contribution: contribution(event.last_contribution, users),
+                level: event.level,
+                started_at: event.started_at,
+            },
+            expires_at: event.expires_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use time::{OffsetDateTime, Duration, format_description::well_known::Rfc3339};
+    use twitch_sources_rework::common_data::eventsub_msgs::EventSubData;
+
+    use super::get_live_event;
+    use crate::domain::users::TwitchUser;
+    use crate::util::testing::mock_context;
+
+    fn prediction(status: &str) -> serde_json::Value {
+        json!({
+            "id": "d6676d5c-c86e-44d2-bfc4-100fb48f0656",
+            "broadcaster_id": "10000",
+            "broadcaster_name": "Streamer",
+            "broadcaster_login": "streamer",
+            "title": "Will there be any leaks today?",
+            "winning_outcome_id": null,
+            "outcomes": [
+                { "id": "021e9234-5893-49b4-982e-cfe9a0aaddd9", "title": "Yes", "users": 0, "channel_points": 0, "top_predictors": null, "color": "BLUE" },
+                { "id": "ded84c26-13cb-4b48-8cb5-5bae3ec3a66e", "title": "No", "users": 0, "channel_points": 0, "top_predictors": null, "color": "PINK" }
+            ],
+            "prediction_window": 600,
+            "status": status,
+            "created_at": OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+            "ended_at": null,
+            "locked_at": null
+        })
+    }
+
+    #[actix_web::test]
+    async fn active_prediction_becomes_progress_event() {
+        let (mock, ctx) = mock_context().await;
+        let mock_user = mock.add_user("Streamer");
+
+        let code = mock.authorize(&mock_user.id, &["channel:read:predictions", "channel:read:hype_train"]);
+        let user = TwitchUser::update_or_create_and_get_user(&ctx, &code, "http://localhost").await
+            .expect("User to be created");
+
+        // nothing going on yet
+        let event = get_live_event(&ctx, user.id, "predictions").await.expect("Predictions to be read");
+        assert!(event.is_none());
+
+        mock.add_prediction(&mock_user.id, prediction("ACTIVE"));
+        let event = get_live_event(&ctx, user.id, "predictions").await
+            .expect("Predictions to be read")
+            .expect("Prediction to be active");
+
+        match event.data {
+            EventSubData::ChannelPredictionProgress(data) => {
+                assert_eq!(data.broadcaster_user_id, mock_user.id);
+                assert_eq!(data.outcomes[1].color, "pink");
+            },
+            data => panic!("Expected a prediction progress, got {:?}", data),
+        }
+
+        mock.add_prediction(&mock_user.id, prediction("RESOLVED"));
+        let event = get_live_event(&ctx, user.id, "predictions").await.expect("Predictions to be read");
+        assert!(event.is_none());
+
+        // a train that ran out is over
+        let expired = OffsetDateTime::now_utc() - Duration::minutes(1);
+        mock.add_hype_train_event(&mock_user.id, hype_train(&mock_user.id, &mock_user.id, (1, 600, 1800), expired));
+        let event = get_live_event(&ctx, user.id, "hype_train").await.expect("Hype train events to be read");
+        assert!(event.is_none());
+
+        mock.stop().await;
+    }
+
+    // level, total and goal of the train as of the event
+    fn hype_train(broadcaster_id: &str, contributor_id: &str, (level, total, goal): (u64, u64, u64), expires_at: OffsetDateTime) -> serde_json::Value {
+        let expires_at = expires_at.format(&Rfc3339).unwrap();
+
+        json!({
+            "id": "1b0AsbInCHZW2SQFQkCzqN07Ib2",
+            "event_type": "hypetrain.progression",
+            "event_timestamp": expires_at,
+            "version": "1.0",
+            "event_data": {
+                "id": "70f0c7d8-ff60-4c50-b138-f3a352833b50",
+                "broadcaster_id": broadcaster_id,
+                "total": total,
+                "goal": goal,
+                "level": level,
+                "top_contributions": [],
+                "last_contribution": { "total": 200, "type": "BITS", "user": contributor_id },
+                "started_at": expires_at,
+                "expires_at": expires_at,
+                "cooldown_end_time": expires_at
+            }
+        })
+    }
+
+    #[actix_web::test]
+    async fn running_hype_train_fills_in_what_helix_does_not_give() {
+        let (mock, ctx) = mock_context().await;
+        let mock_user = mock.add_user("Streamer");
+        let gifter = mock.add_user("Gifter");
+
+        let code = mock.authorize(&mock_user.id, &["channel:read:hype_train"]);
+        let user = TwitchUser::update_or_create_and_get_user(&ctx, &code, "http://localhost").await
+            .expect("User to be created");
+
+        let expires_at = OffsetDateTime::now_utc() + Duration::minutes(1);
+
+        // the whole train is the first level
+        mock.add_hype_train_event(&mock_user.id, hype_train(&mock_user.id, &gifter.id, (1, 300, 500), expires_at));
+        let event = get_live_event(&ctx, user.id, "hype_train").await
+            .expect("Hype train events to be read")
+            .expect("Hype train to be running");
+
+        match event.data {
+            EventSubData::HypeTrainProgress(data) => {
+                assert_eq!(data.data.progress, 300);
+                assert_eq!(data.data.broadcaster_user_login, "streamer");
+                assert_eq!(data.data.broadcaster_user_name, "Streamer");
+                assert_eq!(data.data.last_contribution.user_id, gifter.id);
+                assert_eq!(data.data.last_contribution.user_login, "gifter");
+                assert_eq!(data.data.last_contribution.user_name, "Gifter");
+            },
+            data => panic!("Expected a hype train progress, got {:?}", data),
+        }
+
+        // the first level took its goal of the total
+        mock.add_hype_train_event(&mock_user.id, hype_train(&mock_user.id, &gifter.id, (2, 600, 1800), expires_at));
+        let event = get_live_event(&ctx, user.id, "hype_train").await
+            .expect("Hype train events to be read")
+            .expect("Hype train to be running");
+
+        match event.data {
+            EventSubData::HypeTrainProgress(data) => assert_eq!(data.data.progress, 100),
+            data => panic!("Expected a hype train progress, got {:?}", data),
+        }
+
+        mock.stop().await;
+    }
+}
\ No newline at end of file
@@ -1,4 +1,6 @@
 pub mod auth_state;
+pub mod live_event;
 pub mod login_token;
+pub mod rewards;
 pub mod subscription;
 pub mod users;
\ No newline at end of file
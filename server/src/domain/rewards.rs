@@ -0,0 +1,49 @@
+use thiserror::Error;
+
+use crate::{db::{TwitchUserDb, DbError}, http_client::twitch_client::{TwitchHttpClient, GetCustomRewardsError}};
+
+#[derive(Debug)]
+pub struct CustomReward {
+    pub id: String,
+    pub title: String,
+    pub cost: i64,
+    pub is_enabled: bool
+}
+
+impl CustomReward {
+    pub async fn get_rewards<Ctx: TwitchUserDb + TwitchHttpClient>(ctx: &Ctx, user_id: i64) -> Result<Vec<Self>, GetRewardsError> {
+        let user = ctx.get_user(user_id).await
+            .map_err(GetRewardsError::UserGetError)?
+            .ok_or(GetRewardsError::UserNotFound)?;
+
+        let rewards = ctx.get_custom_rewards(&user.access_token, &user.id.to_string()).await?;
+
+        Ok(rewards.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetRewardsError {
+    #[error("Getting user data from DB failed: {0}")]
+    UserGetError(DbError),
+    #[error("User was not found")]
+    UserNotFound,
+    #[error("Getting custom rewards failed: {0}")]
+    CustomRewardsError(#[from] GetCustomRewardsError),
+}
+
+mod http_conv {
+    use super::CustomReward;
+    use crate::http_client::twitch_client::CustomRewardObject;
+
+    impl From<CustomRewardObject> for CustomReward {
+        fn from(reward: CustomRewardObject) -> Self {
+            Self {
+                id: reward.id,
+                title: reward.title,
+                cost: reward.cost,
+                is_enabled: reward.is_enabled,
+            }
+        }
+    }
+}
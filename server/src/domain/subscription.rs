@@ -4,7 +4,10 @@ use sha2::Sha256;
 use thiserror::Error;
 use twitch_sources_rework::common_data::eventsub_msgs::SubType;
 
-use crate::{db::{SubscriptionDb, DbError}, twitch_api::subscribe::{TwitchSubscriptionManager, TwitchSubscriptionError}, http_client::twitch_client::SubCondition};
+use crate::{db::{SubscriptionDb, DbError}, twitch_api::subscribe::{TwitchSubscriptionManager, TwitchSubscriptionError}, http_client::twitch_client::{SubCondition, SubData, SubscriptionCost}, my_redis::{RedisError, cost_cache::SubscriptionCostCache, connection_heartbeat::ConnectionHeartbeat}, util::use_websocket_transport};
+
+// subscriptions are saved to DB only after twitch creates them, so fresh ones can look orphaned for a bit
+const ORPHAN_GRACE_PERIOD: time::Duration = time::Duration::minutes(10);
 
 pub struct Subscription {
     id: i64,
@@ -14,13 +17,14 @@ pub struct Subscription {
     type_: SubType,
     last_connect: time::PrimitiveDateTime,
     last_disconnect: time::PrimitiveDateTime,
+    cost: i64,
 }
 
 type HmacSha256 = Hmac<Sha256>;
 
 // "DB" impl
 impl Subscription {
-    pub async fn get_or_create_subscriptions<Ctx: SubscriptionDb + TwitchSubscriptionManager>(
+    pub async fn get_or_create_subscriptions<Ctx: SubscriptionDb + TwitchSubscriptionManager + SubscriptionCostCache>(
         ctx: &Ctx,
         sub_types: &[SubType],
         sub_cond: SubCondition,
@@ -31,12 +35,30 @@ impl Subscription {
             .map(Into::into)
             .collect::<Vec<Self>>();
         
+        let missing_types = sub_types
+            .iter()
+            .filter(|sub_type| !existing_subs.iter().any(|sub| &sub.type_ == *sub_type))
+            .collect::<Vec<_>>();
+
+        // websocket subscriptions are made with the user's token and count against that user, not the app
+        let cost_owner = if use_websocket_transport() { sub_cond.clone().into_user_id() } else { None };
+
+        // a new subscription costs 1 at most (0 if the user authorized the app), so this is the worst case
+        if !missing_types.is_empty() {
+            if let Some(cost) = ctx.get_subscription_cost(cost_owner).await.map_err(GetOrCreateSubs::CostCacheFail)? {
+                if cost.total_cost + missing_types.len() as i64 > cost.max_total_cost {
+                    return Err(GetOrCreateSubs::CostLimitExceeded {
+                        total_cost: cost.total_cost,
+                        max_total_cost: cost.max_total_cost,
+                    });
+                }
+            }
+        }
+
         let mut new_subs = Vec::new();
         
-        for sub_type in sub_types.iter() {
-            if !existing_subs.iter().any(|sub| &sub.type_ == sub_type) {
-                new_subs.push(ctx.subscribe(sub_cond.clone(), sub_type.clone()))
-            }
+        for sub_type in missing_types {
+            new_subs.push(ctx.subscribe(sub_cond.clone(), sub_type.clone()))
         }
 
         let new_subs = try_join_all(new_subs).await?;
@@ -49,6 +71,36 @@ impl Subscription {
         Ok(existing_subs.into_iter().chain(new_subs.into_iter()).collect())
     }
 
+    /// Latest totals Twitch gave for the app, next to the webhook subscriptions saved here.
+    /// Websocket ones count against their users and don't show up in the app's list.
+    pub async fn get_usage<Ctx: SubscriptionDb + TwitchSubscriptionManager + SubscriptionCostCache>(
+        ctx: &Ctx,
+    ) -> Result<SubscriptionsUsage, GetUsage> {
+        let twitch_cost = match ctx.get_subscription_cost(None).await.map_err(GetUsage::CostCacheFail)? {
+            Some(cost) => cost,
+            None => {
+                // nothing was cached yet, listing the subscriptions caches the totals
+                ctx.get_twitch_subscriptions().await?;
+                ctx.get_subscription_cost(None).await
+                    .map_err(GetUsage::CostCacheFail)?
+                    .ok_or(GetUsage::NoTotals)?
+            }
+        };
+
+        // websocket subscriptions are saved without a secret
+        let saved_subs = ctx.get_all_subscriptions().await
+            .map_err(GetUsage::GetSubscriptionsFail)?
+            .into_iter()
+            .filter(|sub| !sub.secret.is_empty())
+            .collect::<Vec<_>>();
+
+        Ok(SubscriptionsUsage {
+            twitch_cost,
+            saved_count: saved_subs.len() as i64,
+            saved_cost: saved_subs.iter().map(|sub| sub.cost).sum(),
+        })
+    }
+
     pub async fn get_subscription<Repo: SubscriptionDb>(db: &Repo, sub_id: &str) -> Result<Self, GetSub> {
         Ok(
             db
@@ -65,6 +117,105 @@ impl Subscription {
         Ok(())
     }
 
+    pub async fn remove_websocket_subscriptions<Ctx: SubscriptionDb + SubscriptionCostCache>(ctx: &Ctx) -> Result<(), RemoveSub> {
+        let mut user_ids = ctx.get_all_subscriptions().await
+            .map_err(|_| RemoveSub::Fail)?
+            .into_iter()
+            .filter(|sub| sub.secret.is_empty())
+            .filter_map(|sub| sub.user_id)
+            .collect::<Vec<_>>();
+        user_ids.sort_unstable();
+        user_ids.dedup();
+
+        ctx.remove_websocket_subscriptions().await.map_err(|_| RemoveSub::Fail)?;
+
+        // their cost went away along with them, nothing else would lower it
+        for user_id in user_ids {
+            if let Err(err) = ctx.clear_subscription_cost(Some(user_id)).await {
+                log::warn!("Clearing subscription cost of user {} failed: {}", user_id, err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Brings the DB and Twitch in line: drops rows of subscriptions Twitch doesn't have (or won't deliver),
+    /// deletes Twitch subscriptions we don't know about, and unsubscribes the ones idle longer than `idle_window`.
+    pub async fn reconcile_subscriptions<Ctx: SubscriptionDb + TwitchSubscriptionManager + ConnectionHeartbeat>(
+        ctx: &Ctx,
+        idle_window: time::Duration,
+    ) -> Result<ReconcileReport, ReconcileSubs> {
+        // DB first, so anything created in between is at worst a fresh orphan on twitch's side
+        let db_subs = ctx.get_all_subscriptions().await
+            .map_err(ReconcileSubs::GetSubscriptionsFail)?
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<Self>>();
+
+        let twitch_subs = ctx.get_twitch_subscriptions().await?;
+
+        let mut report = ReconcileReport::default();
+
+        // one failing doesn't stop the rest, the next run tries it again
+        for sub in db_subs.iter() {
+            if let Err(err) = Self::reconcile_saved(ctx, sub, &twitch_subs, idle_window, &mut report).await {
+                log::warn!("Reconciling subscription {} failed: {}", sub.sub_id, err);
+                report.failed += 1;
+            }
+        }
+
+        let now = time::OffsetDateTime::now_utc();
+
+        for twitch_sub in twitch_subs.iter() {
+            if db_subs.iter().any(|sub| sub.sub_id == twitch_sub.id) || now - twitch_sub.created_at < ORPHAN_GRACE_PERIOD {
+                continue;
+            }
+
+            match ctx.unsubscribe(&twitch_sub.id).await {
+                Ok(_) => report.orphans_deleted += 1,
+                Err(err) => {
+                    log::warn!("Deleting orphaned subscription {} failed: {}", twitch_sub.id, err);
+                    report.failed += 1;
+                },
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn reconcile_saved<Ctx: SubscriptionDb + TwitchSubscriptionManager + ConnectionHeartbeat>(
+        ctx: &Ctx,
+        sub: &Self,
+        twitch_subs: &[SubData],
+        idle_window: time::Duration,
+        report: &mut ReconcileReport,
+    ) -> Result<(), ReconcileSubs> {
+        let twitch_sub = twitch_subs.iter().find(|twitch_sub| twitch_sub.id == sub.sub_id);
+
+        match twitch_sub {
+            Some(twitch_sub) if twitch_sub.status.is_alive() => {
+                if !sub.is_idle(idle_window) {
+                    return Ok(());
+                }
+
+                // an overlay connected for longer than the window only shows up here
+                if ctx.is_subscription_connected(&sub.sub_id).await.map_err(ReconcileSubs::HeartbeatFail)? {
+                    return Ok(());
+                }
+
+                ctx.unsubscribe(&sub.sub_id).await?;
+                report.idle_unsubscribed += 1;
+            },
+            Some(_) => {
+                ctx.unsubscribe(&sub.sub_id).await?;
+                report.dead_removed += 1;
+            },
+            None => report.dead_removed += 1,
+        }
+
+        ctx.remove_subscription(&sub.sub_id).await.map_err(ReconcileSubs::RemoveSubscriptionFail)
+    }
+
     pub async fn update_connect_time_by_id<Repo: SubscriptionDb>(db: &Repo, sub_id: &str) -> Result<(), UpdateConnect> {
         db.update_connect_time_by_id(sub_id).await.map_err(|_| UpdateConnect::Fail)
     }
@@ -77,20 +228,39 @@ impl Subscription {
 // struct impl
 impl Subscription {
     pub fn verify_msg(&self, msg: &[u8], expected_signature: &[u8]) -> bool {
-        let mut hasher = HmacSha256::new_from_slice(self.secret.as_bytes()).expect("HMAC can take key of any size");
+        // websocket transport subscriptions are saved without a secret, anyone could sign for them
+        if self.secret.is_empty() {
+            return false;
+        }
 
+        let mut hasher = HmacSha256::new_from_slice(self.secret.as_bytes()).expect("HMAC can take key of any size");
         hasher.update(msg);
-        let res_hash = [
-            b"sha256=",
-            hex::encode(&*hasher.finalize().into_bytes()).as_bytes()
-        ].concat();
 
-        return res_hash == expected_signature
+        let expected_hash = match expected_signature
+            .strip_prefix(b"sha256=")
+            .and_then(|hash| hex::decode(hash).ok())
+        {
+            Some(hash) => hash,
+            None => return false,
+        };
+
+        // constant time comparison, so the signature can't be guessed by timing the responses
+        hasher.verify_slice(&expected_hash).is_ok()
     }
 
     pub fn sub_id(&self) -> &str {
         &self.sub_id
     }
+
+    // nobody was connected for the whole window, whether someone is right now is up to the heartbeat.
+    // client wide subscriptions are always needed
+    fn is_idle(&self, idle_window: time::Duration) -> bool {
+        // sockets of a server that went down never wrote their disconnect
+        let last_seen = self.last_connect.max(self.last_disconnect);
+
+        self.user_id.is_some()
+            && time::OffsetDateTime::now_utc() - last_seen.assume_utc() > idle_window
+    }
 }
 
 #[derive(Debug, Error)]
@@ -101,6 +271,51 @@ pub enum GetOrCreateSubs {
     TwitchSubcriptionFail(#[from] TwitchSubscriptionError),
     #[error("Saving subscriptions failed: {0}")]
     CreateSubscriptionFail(DbError),
+    #[error("Getting cached subscription cost failed: {0}")]
+    CostCacheFail(RedisError),
+    #[error("Subscription cost limit would be exceeded: {total_cost} of {max_total_cost} used")]
+    CostLimitExceeded {
+        total_cost: i64,
+        max_total_cost: i64,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum GetUsage {
+    #[error("Getting cached subscription cost failed: {0}")]
+    CostCacheFail(RedisError),
+    #[error("Requesting subscriptions failed: {0}")]
+    TwitchSubcriptionFail(#[from] TwitchSubscriptionError),
+    #[error("Twitch gave no subscription totals")]
+    NoTotals,
+    #[error("Getting subscriptions failed: {0}")]
+    GetSubscriptionsFail(DbError),
+}
+
+pub struct SubscriptionsUsage {
+    pub twitch_cost: SubscriptionCost,
+    pub saved_count: i64,
+    pub saved_cost: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct ReconcileReport {
+    pub dead_removed: usize,
+    pub orphans_deleted: usize,
+    pub idle_unsubscribed: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Error)]
+pub enum ReconcileSubs {
+    #[error("Getting subscriptions failed: {0}")]
+    GetSubscriptionsFail(DbError),
+    #[error("Twitch subscription request failed: {0}")]
+    TwitchSubcriptionFail(#[from] TwitchSubscriptionError),
+    #[error("Removing subscription failed: {0}")]
+    RemoveSubscriptionFail(DbError),
+    #[error("Checking subscription heartbeat failed: {0}")]
+    HeartbeatFail(RedisError),
 }
 
 #[derive(Debug, Error)]
@@ -143,7 +358,150 @@ mod db_conv {
                 type_: sub.type_.try_into().unwrap(),
                 last_connect: sub.last_connect,
                 last_disconnect: sub.last_disconnect,
+                cost: sub.cost,
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use hmac::Mac as _;
+    use time::OffsetDateTime;
+    use twitch_sources_rework::common_data::eventsub_msgs::SubType;
+
+    use super::{GetOrCreateSubs, HmacSha256, Subscription};
+    use crate::db::SubscriptionDb;
+    use crate::twitch_api::subscribe::TwitchSubscriptionManager;
+    use crate::http_client::twitch_client::{SubCondition, SubscriptionCost};
+    use crate::my_redis::{connection_heartbeat::ConnectionHeartbeat, cost_cache::SubscriptionCostCache};
+    use crate::util::testing::mock_context;
+
+    fn subscription(secret: &str) -> Subscription {
+        let now = OffsetDateTime::now_utc();
+        let now = time::PrimitiveDateTime::new(now.date(), now.time());
+
+        Subscription {
+            id: 1,
+            user_id: Some(1337),
+            secret: secret.to_string(),
+            sub_id: "f1c2a387-161a-49f9-a165-0f21d7a4e1c4".to_string(),
+            type_: SubType::ChannelPollBegin,
+            last_connect: now,
+            last_disconnect: now,
+            cost: 1,
+        }
+    }
+
+    fn sign(secret: &str, msg: &[u8]) -> Vec<u8> {
+        let mut hasher = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+        hasher.update(msg);
+
+        format!("sha256={}", hex::encode(hasher.finalize().into_bytes())).into_bytes()
+    }
+
+    #[test]
+    fn refuses_messages_for_subscriptions_without_secret() {
+        let msg = b"message-idtimestamp{}";
+
+        assert!(subscription("secret").verify_msg(msg, &sign("secret", msg)));
+        assert!(!subscription("secret").verify_msg(msg, &sign("other", msg)));
+        assert!(!subscription("").verify_msg(msg, &sign("", msg)));
+    }
+
+    #[actix_web::test]
+    async fn creates_missing_and_reuses_existing() {
+        let (mock, ctx) = mock_context().await;
+        let user = mock.add_user("Streamer");
+        let sub_cond = SubCondition::BroadcasterUserId(user.id.clone());
+
+        let subs = Subscription::get_or_create_subscriptions(&ctx, &[SubType::ChannelRaid], sub_cond.clone()).await
+            .expect("Subscription to be created");
+        assert_eq!(subs.len(), 1);
+        assert_eq!(mock.subscriptions().len(), 1);
+
+        let subs = Subscription::get_or_create_subscriptions(&ctx, &[SubType::ChannelRaid, SubType::StreamOnline], sub_cond).await
+            .expect("Subscriptions to be created");
+        assert_eq!(subs.len(), 2);
+        // the raid one was already there
+        assert_eq!(mock.subscriptions().len(), 2);
+
+        let cost = ctx.get_subscription_cost(None).await.expect("Cost to be read").expect("Cost to be cached");
+        assert_eq!(cost.total_cost, 2);
+
+        mock.stop().await;
+    }
+
+    #[actix_web::test]
+    async fn refuses_over_cost_limit() {
+        let (mock, ctx) = mock_context().await;
+        let user = mock.add_user("Streamer");
+
+        ctx.update_subscription_cost(None, &SubscriptionCost { total: 10, total_cost: 10, max_total_cost: 10 }).await
+            .expect("Cost to be cached");
+
+        let res = Subscription::get_or_create_subscriptions(
+            &ctx,
+            &[SubType::ChannelRaid],
+            SubCondition::BroadcasterUserId(user.id.clone())
+        ).await;
+
+        assert!(matches!(res, Err(GetOrCreateSubs::CostLimitExceeded { .. })), "Expected cost limit error, got {:?}", res.err());
+        assert!(mock.subscriptions().is_empty());
+
+        mock.stop().await;
+    }
+
+    #[actix_web::test]
+    async fn keeps_subscriptions_someone_is_still_connected_to() {
+        let (mock, ctx) = mock_context().await;
+        let user = mock.add_user("Streamer");
+
+        let subs = Subscription::get_or_create_subscriptions(
+            &ctx,
+            &[SubType::ChannelRaid, SubType::StreamOnline],
+            SubCondition::BroadcasterUserId(user.id.clone())
+        ).await.expect("Subscriptions to be created");
+        let (connected, gone) = (subs[0].sub_id().to_string(), subs[1].sub_id().to_string());
+
+        // two overlays of the first one, one of them goes away
+        Subscription::update_connect_time_by_id(&ctx, &connected).await.expect("Connect to be saved");
+        Subscription::update_connect_time_by_id(&ctx, &connected).await.expect("Connect to be saved");
+        Subscription::update_disconnect_time_by_id(&ctx, &connected).await.expect("Disconnect to be saved");
+        ctx.beat_subscriptions(&[connected.clone()]).await.expect("Heartbeat to be saved");
+
+        // the other one dropped without a heartbeat left behind
+        Subscription::update_connect_time_by_id(&ctx, &gone).await.expect("Connect to be saved");
+
+        let report = Subscription::reconcile_subscriptions(&ctx, time::Duration::ZERO).await.expect("Reconcile to work");
+        assert_eq!(report.idle_unsubscribed, 1);
+        assert_eq!(report.failed, 0);
+
+        let left = ctx.get_all_subscriptions().await.expect("Subscriptions to be read");
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].sub_id, connected);
+        assert_eq!(mock.subscriptions().len(), 1);
+
+        mock.stop().await;
+    }
+
+    #[actix_web::test]
+    async fn forgets_cost_once_subscriptions_are_deleted() {
+        let (mock, ctx) = mock_context().await;
+        let user = mock.add_user("Streamer");
+
+        let subs = Subscription::get_or_create_subscriptions(
+            &ctx,
+            &[SubType::ChannelRaid],
+            SubCondition::BroadcasterUserId(user.id.clone())
+        ).await.expect("Subscription to be created");
+        assert!(ctx.get_subscription_cost(None).await.expect("Cost to be read").is_some());
+
+        ctx.unsubscribe(subs[0].sub_id()).await.expect("Subscription to be deleted");
+
+        // an outdated total would keep refusing new ones
+        assert!(ctx.get_subscription_cost(None).await.expect("Cost to be read").is_none());
+
+        mock.stop().await;
+    }
 }
\ No newline at end of file
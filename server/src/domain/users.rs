@@ -1,6 +1,8 @@
 use thiserror::Error;
 
-use crate::{db::{TwitchUserDb, DbError, NewTwitchUser}, http_client::twitch_client::{TwitchHttpClient, GetUserTokenError, GetUserDataError}};
+use time::{OffsetDateTime, PrimitiveDateTime, Duration};
+
+use crate::{db::{TwitchUserDb, DbError, NewTwitchUser, TwitchUser as DbTwitchUser, UserTokens}, http_client::twitch_client::{TwitchHttpClient, GetUserTokenError, GetUserDataError, RefreshUserTokenError, ValidateTokenError}};
 
 #[derive(Debug)]
 pub struct TwitchUser {
@@ -8,7 +10,16 @@ pub struct TwitchUser {
     pub username: String,
     pub creation: time::PrimitiveDateTime,
     pub broadcaster_type: String,
-    pub scopes: Vec<String>
+    pub scopes: Vec<String>,
+    pub token_invalid: bool
+}
+
+// enough for the request the token is about to be used for
+const REFRESH_BEFORE_USE_MARGIN: Duration = Duration::minutes(5);
+
+fn now_primitive() -> PrimitiveDateTime {
+    let now = OffsetDateTime::now_utc();
+    PrimitiveDateTime::new(now.date(), now.time())
 }
 
 impl TwitchUser {
@@ -28,6 +39,9 @@ impl TwitchUser {
             Some(mut user) => {
                 user.access_token = user_access_data.access_token;
                 user.refresh_token = user_access_data.refresh_token;
+                user.expires_in = user_access_data.expires_in;
+                user.last_token_refresh = now_primitive();
+                user.token_invalid = false;
                 user.username = user_data.login;
                 user.scopes = user_access_data.scope.into_iter().map(Some).collect();
 
@@ -63,6 +77,126 @@ impl TwitchUser {
     pub async fn delete_user<Ctx: TwitchUserDb>(ctx: &Ctx, user_id: i64) -> Result<(), DeleteUserError> {
         ctx.delete_user(user_id).await.map_err(DeleteUserError::UserDeleteError)
     }
+
+    /// Refreshes tokens that expire within `refresh_margin` and validates the rest, as Twitch
+    /// requires. Users whose tokens can't be refreshed are marked, so they are asked to log in again.
+    /// Only users active within `active_window` are checked, the others get new tokens when they log in.
+    pub async fn maintain_tokens<Ctx: TwitchUserDb + TwitchHttpClient>(
+        ctx: &Ctx,
+        refresh_margin: Duration,
+        active_window: Duration,
+    ) -> Result<TokenMaintenanceReport, GetUserError> {
+        let active_since = now_primitive() - active_window;
+        let users = ctx.get_active_users_with_valid_tokens(active_since).await.map_err(GetUserError::UserGetError)?;
+
+        let mut report = TokenMaintenanceReport::default();
+
+        for user in users {
+            let user_id = user.id;
+
+            match Self::maintain_token(ctx, user, refresh_margin).await {
+                Ok(TokenState::Valid) => report.validated += 1,
+                Ok(TokenState::Refreshed) => report.refreshed += 1,
+                Ok(TokenState::Invalid) => report.marked_invalid += 1,
+                Err(err) => {
+                    // one user failing shouldn't stop the others
+                    log::warn!("Token maintenance for user {} failed: {}", user_id, err);
+                    report.failed += 1;
+                },
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// The user's access token, refreshed first if it's about to expire. Tokens of users who weren't
+    /// active aren't maintained, so they're usually stale by the time an overlay of theirs comes back.
+    pub async fn fresh_access_token<Ctx: TwitchUserDb + TwitchHttpClient>(
+        ctx: &Ctx,
+        user: DbTwitchUser,
+    ) -> Result<String, MaintainTokenError> {
+        if user.token_invalid {
+            return Err(MaintainTokenError::TokenRevoked);
+        }
+
+        if !expires_within(&user, REFRESH_BEFORE_USE_MARGIN) {
+            return Ok(user.access_token);
+        }
+
+        Self::refresh_token(ctx, &user).await?.ok_or(MaintainTokenError::TokenRevoked)
+    }
+
+    async fn maintain_token<Ctx: TwitchUserDb + TwitchHttpClient>(
+        ctx: &Ctx,
+        user: DbTwitchUser,
+        refresh_margin: Duration,
+    ) -> Result<TokenState, MaintainTokenError> {
+        // refreshing checks the token too, so validation is only needed for the ones that stay
+        let needs_refresh = expires_within(&user, refresh_margin)
+            || match ctx.validate_token(&user.access_token).await {
+                Ok(_) => false,
+                Err(ValidateTokenError::InvalidToken) => true,
+                Err(err) => return Err(err.into()),
+            };
+
+        if !needs_refresh {
+            return Ok(TokenState::Valid);
+        }
+
+        match Self::refresh_token(ctx, &user).await? {
+            Some(_) => Ok(TokenState::Refreshed),
+            None => Ok(TokenState::Invalid),
+        }
+    }
+
+    // the new access token, `None` if twitch refused and the user was marked to log in again.
+    // only the token columns are written, a login at the same time could've changed the rest of the row
+    async fn refresh_token<Ctx: TwitchUserDb + TwitchHttpClient>(
+        ctx: &Ctx,
+        user: &DbTwitchUser,
+    ) -> Result<Option<String>, MaintainTokenError> {
+        match ctx.refresh_user_token(&user.refresh_token).await {
+            Ok(tokens) => {
+                let tokens = UserTokens {
+                    access_token: tokens.access_token,
+                    refresh_token: tokens.refresh_token,
+                    expires_in: tokens.expires_in,
+                    scopes: tokens.scope,
+                    last_token_refresh: now_primitive(),
+                };
+
+                ctx.update_user_tokens(user.id, &tokens).await.map_err(MaintainTokenError::UserSaveError)?;
+
+                Ok(Some(tokens.access_token))
+            },
+            Err(RefreshUserTokenError::InvalidToken) => {
+                ctx.mark_token_invalid(user.id).await.map_err(MaintainTokenError::UserSaveError)?;
+
+                Ok(None)
+            },
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+fn expires_within(user: &DbTwitchUser, margin: Duration) -> bool {
+    let expires_at = user.last_token_refresh.assume_utc() + Duration::seconds(user.expires_in.into());
+
+    expires_at - OffsetDateTime::now_utc() < margin
+}
+
+enum TokenState {
+    Valid,
+    Refreshed,
+    Invalid,
+}
+
+#[derive(Debug, Default)]
+pub struct TokenMaintenanceReport {
+    pub validated: usize,
+    pub refreshed: usize,
+    pub marked_invalid: usize,
+    pub failed: usize,
 }
 
 #[derive(Debug, Error)]
@@ -87,6 +221,18 @@ pub enum GetUserError {
     UserGetError(DbError)
 }
 
+#[derive(Debug, Error)]
+pub enum MaintainTokenError {
+    #[error("Validating token failed: {0}")]
+    ValidateError(#[from] ValidateTokenError),
+    #[error("Refreshing token failed: {0}")]
+    RefreshError(#[from] RefreshUserTokenError),
+    #[error("Saving user data in DB failed: {0}")]
+    UserSaveError(DbError),
+    #[error("Token was revoked, the user has to log in again")]
+    TokenRevoked,
+}
+
 #[derive(Debug, Error)]
 pub enum DeleteUserError {
     #[error("Deleting user data from DB failed: {0}")]
@@ -106,7 +252,107 @@ mod db_conv {
                 broadcaster_type: user.broadcaster_type,
                 // Option, because weird DB shenanigans, can be unwrapped
                 scopes: user.scopes.into_iter().map(Option::unwrap).collect(),
+                token_invalid: user.token_invalid,
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+
+    use super::{now_primitive, TwitchUser};
+    use crate::db::TwitchUserDb;
+    use crate::util::testing::mock_context;
+
+    #[actix_web::test]
+    async fn login_creates_then_updates_user() {
+        let (mock, ctx) = mock_context().await;
+        let mock_user = mock.add_user("Streamer");
+
+        let code = mock.authorize(&mock_user.id, &["bits:read"]);
+        let user = TwitchUser::update_or_create_and_get_user(&ctx, &code, "http://localhost").await
+            .expect("User to be created");
+
+        assert_eq!(user.id.to_string(), mock_user.id);
+        assert_eq!(user.username, "streamer");
+        assert_eq!(user.scopes, vec!["bits:read"]);
+
+        // logging in again with more scopes keeps the same user
+        let code = mock.authorize(&mock_user.id, &["bits:read", "channel:read:polls"]);
+        let user = TwitchUser::update_or_create_and_get_user(&ctx, &code, "http://localhost").await
+            .expect("User to be updated");
+
+        assert_eq!(user.scopes, vec!["bits:read", "channel:read:polls"]);
+
+        let saved = TwitchUser::get_user(&ctx, user.id).await.expect("User to be read").expect("User to exist");
+        assert_eq!(saved.scopes, user.scopes);
+
+        mock.stop().await;
+    }
+
+    #[actix_web::test]
+    async fn maintains_tokens_of_active_users_only() {
+        let (mock, ctx) = mock_context().await;
+
+        let mut user_ids = Vec::new();
+        for name in ["Active", "Gone"] {
+            let mock_user = mock.add_user(name);
+            let code = mock.authorize(&mock_user.id, &["bits:read"]);
+            let user = TwitchUser::update_or_create_and_get_user(&ctx, &code, "http://localhost").await
+                .expect("User to be created");
+
+            user_ids.push(user.id);
+        }
+
+        let mut gone = ctx.get_user(user_ids[1]).await.expect("User to be read").expect("User to exist");
+        gone.last_login = now_primitive() - Duration::days(30);
+        ctx.save_user(&gone).await.expect("User to be saved");
+
+        // both fail validation, so they'd be refreshed
+        mock.expire_tokens();
+
+        let report = TwitchUser::maintain_tokens(&ctx, Duration::ZERO, Duration::weeks(1)).await
+            .expect("Maintenance to run");
+        assert_eq!(report.refreshed, 1);
+        assert_eq!(report.failed, 0);
+
+        let active = ctx.get_user(user_ids[0]).await.expect("User to be read").expect("User to exist");
+        assert!(!active.token_invalid);
+
+        let saved_gone = ctx.get_user(user_ids[1]).await.expect("User to be read").expect("User to exist");
+        assert_eq!(saved_gone.access_token, gone.access_token);
+        assert_eq!(saved_gone.last_token_refresh, gone.last_token_refresh);
+
+        mock.stop().await;
+    }
+
+    #[actix_web::test]
+    async fn refreshes_stale_token_before_use() {
+        let (mock, ctx) = mock_context().await;
+        let mock_user = mock.add_user("Streamer");
+
+        let code = mock.authorize(&mock_user.id, &["channel:read:predictions"]);
+        let user = TwitchUser::update_or_create_and_get_user(&ctx, &code, "http://localhost").await
+            .expect("User to be created");
+
+        // away for longer than tokens are maintained
+        let mut stale = ctx.get_user(user.id).await.expect("User to be read").expect("User to exist");
+        stale.last_token_refresh = now_primitive() - Duration::days(30);
+        ctx.save_user(&stale).await.expect("User to be saved");
+        mock.expire_tokens();
+
+        let access_token = TwitchUser::fresh_access_token(&ctx, stale.clone()).await.expect("Token to be refreshed");
+        assert_ne!(access_token, stale.access_token);
+
+        let saved = ctx.get_user(user.id).await.expect("User to be read").expect("User to exist");
+        assert_eq!(saved.access_token, access_token);
+
+        // not stale anymore, used as it is
+        let reused = TwitchUser::fresh_access_token(&ctx, saved).await.expect("Token to be given");
+        assert_eq!(reused, access_token);
+
+        mock.stop().await;
+    }
 }
\ No newline at end of file
@@ -1,4 +1,56 @@
 pub mod twitch_client;
+mod request;
+
+pub use request::HelixError;
+
+use request::RateLimit;
+
+use crate::util::{get_twitch_api_url, get_twitch_auth_url, get_twitch_key, get_twitch_secret};
 
 #[derive(Clone)]
-pub struct HttpClient(pub reqwest::Client);
\ No newline at end of file
+pub struct HttpClient {
+    client: reqwest::Client,
+    rate_limit: RateLimit,
+    api_url: String,
+    auth_url: String,
+    // client id and secret, the environment has them when not set
+    credentials: Option<(String, String)>,
+    // where twitch sends webhooks, the environment has it when not set
+    webhook_base_url: Option<String>,
+}
+
+impl HttpClient {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self::with_base_urls(client, get_twitch_api_url(), get_twitch_auth_url())
+    }
+
+    /// Client that talks to a different Twitch, like a local mock in tests.
+    pub fn with_base_urls(client: reqwest::Client, api_url: String, auth_url: String) -> Self {
+        Self { client, rate_limit: RateLimit::default(), api_url, auth_url, credentials: None, webhook_base_url: None }
+    }
+
+    /// Uses these instead of `TWITCH_KEY` and `TWITCH_SECRET`, like the ones a local mock was started with.
+    pub fn with_credentials(self, client_id: &str, client_secret: &str) -> Self {
+        Self { credentials: Some((client_id.to_string(), client_secret.to_string())), ..self }
+    }
+
+    /// Has Twitch send webhooks to this base URL instead of the one in `WEBHOOK_BASE_URL`.
+    pub fn with_webhook_base_url(self, base_url: &str) -> Self {
+        Self { webhook_base_url: Some(base_url.to_string()), ..self }
+    }
+
+    pub fn client_id(&self) -> String {
+        match &self.credentials {
+            Some((client_id, _)) => client_id.clone(),
+            None => get_twitch_key(),
+        }
+    }
+
+    fn client_secret(&self) -> String {
+        match &self.credentials {
+            Some((_, client_secret)) => client_secret.clone(),
+            None => get_twitch_secret(),
+        }
+    }
+
+}
\ No newline at end of file
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::Rng;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use reqwest::header::AUTHORIZATION;
+use serde::Deserialize;
+use tokio::time::sleep as async_sleep;
+
+use super::HttpClient;
+
+const MAX_ATTEMPTS: u32 = 3;
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+// used when a 429 doesn't say when the bucket refills
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(1);
+
+/// Error body Twitch sends along with non-2xx statuses.
+#[derive(Debug, Deserialize)]
+pub struct HelixError {
+    pub status: u16,
+    #[serde(default)]
+    pub error: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+impl std::fmt::Display for HelixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.status, self.error, self.message)
+    }
+}
+
+impl HelixError {
+    pub fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+pub(crate) enum SendError {
+    Http(reqwest::Error),
+    Api(HelixError),
+}
+
+/// Time until which requests wait, because Twitch said the rate limit bucket is empty.
+/// The app token and each user token have a bucket of their own, so they're tracked by the
+/// `Authorization` header the request was sent with.
+#[derive(Clone, Default)]
+pub(crate) struct RateLimit(Arc<Mutex<HashMap<String, SystemTime>>>);
+
+impl RateLimit {
+    fn wait_time(&self, bucket: &str) -> Option<Duration> {
+        let mut blocked = self.0.lock().expect("Rate limit lock poisoned");
+        let blocked_until = *blocked.get(bucket)?;
+
+        match blocked_until.duration_since(SystemTime::now()) {
+            Ok(wait) => Some(wait),
+            Err(_) => {
+                // refilled already, nothing to keep around
+                blocked.remove(bucket);
+                None
+            },
+        }
+    }
+
+    fn update(&self, bucket: &str, response: &Response) {
+        let header = |name: &str| response
+            .headers()
+            .get(name)
+            .and_then(|val| val.to_str().ok())
+            .and_then(|val| val.parse::<u64>().ok());
+
+        // identity endpoints don't send these
+        if let (Some(remaining), Some(reset)) = (header("Ratelimit-Remaining"), header("Ratelimit-Reset")) {
+            self.set_remaining(bucket, remaining, reset);
+        }
+    }
+
+    fn set_remaining(&self, bucket: &str, remaining: u64, reset: u64) {
+        let mut blocked = self.0.lock().expect("Rate limit lock poisoned");
+
+        if remaining == 0 {
+            blocked.insert(bucket.to_string(), UNIX_EPOCH + Duration::from_secs(reset));
+        } else {
+            blocked.remove(bucket);
+        }
+    }
+}
+
+// requests without a token (like getting one) share a bucket
+fn bucket_of(request: &RequestBuilder) -> String {
+    request
+        .try_clone()
+        .and_then(|request| request.build().ok())
+        .and_then(|request| request.headers().get(AUTHORIZATION)?.to_str().ok().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn backoff(attempt: u32) -> Duration {
+    let jitter = rand::thread_rng().gen_range(0..BACKOFF_BASE.as_millis() as u64);
+    BACKOFF_BASE * 2u32.pow(attempt) + Duration::from_millis(jitter)
+}
+
+impl HttpClient {
+    /// Sends the request, waiting out the rate limit and 429s. Retries on 5xx and connection
+    /// errors only if `idempotent`, since otherwise the first try might've gone through.
+    /// Non-2xx responses come back as [`SendError::Api`].
+    pub(crate) async fn send(&self, request: RequestBuilder, idempotent: bool) -> Result<Response, SendError> {
+        let bucket = bucket_of(&request);
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let can_retry = attempt < MAX_ATTEMPTS;
+
+            if let Some(wait) = self.rate_limit.wait_time(&bucket) {
+                async_sleep(wait).await;
+            }
+
+            let current_request = request
+                .try_clone()
+                .expect("Twitch requests don't have streaming bodies");
+
+            let response = match current_request.send().await {
+                Ok(response) => response,
+                Err(err) if idempotent && can_retry && (err.is_connect() || err.is_timeout()) => {
+                    async_sleep(backoff(attempt)).await;
+                    continue;
+                },
+                Err(err) => return Err(SendError::Http(err)),
+            };
+
+            self.rate_limit.update(&bucket, &response);
+            let status = response.status();
+
+            // rate limited requests aren't processed, so any of them can be retried
+            if status == StatusCode::TOO_MANY_REQUESTS && can_retry {
+                async_sleep(self.rate_limit.wait_time(&bucket).unwrap_or(DEFAULT_RATE_LIMIT_WAIT)).await;
+                continue;
+            }
+
+            if status.is_server_error() && idempotent && can_retry {
+                async_sleep(backoff(attempt)).await;
+                continue;
+            }
+
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let body = response.text().await.map_err(SendError::Http)?;
+            let error = serde_json::from_str::<HelixError>(&body).unwrap_or(HelixError {
+                status: status.as_u16(),
+                error: status.canonical_reason().unwrap_or_default().to_string(),
+                message: body,
+            });
+
+            return Err(SendError::Api(error));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::RateLimit;
+
+    #[test]
+    fn keeps_a_bucket_per_token() {
+        let rate_limit = RateLimit::default();
+        let reset = (SystemTime::now() + std::time::Duration::from_secs(30)).duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        rate_limit.set_remaining("Bearer app", 0, reset);
+
+        assert!(rate_limit.wait_time("Bearer app").is_some());
+        // users have their own buckets, the app running out doesn't hold them up
+        assert!(rate_limit.wait_time("Bearer user").is_none());
+
+        rate_limit.set_remaining("Bearer app", 799, reset);
+        assert!(rate_limit.wait_time("Bearer app").is_none());
+
+        // already refilled
+        rate_limit.set_remaining("Bearer user", 0, 1);
+        assert!(rate_limit.wait_time("Bearer user").is_none());
+    }
+}
\ No newline at end of file
@@ -1,81 +1,151 @@
 
-use crate::util::get_twitch_key;
+use crate::util::get_webhook_base_url;
 
 use super::HttpClient;
+use super::request::SendError;
 
 use auto_delegate::delegate;
-pub use get_new_token_info::GetTokenError;
+use reqwest::StatusCode;
+pub use get_new_token_info::{GetTokenError, NewTokenResponse};
 pub use get_user_token_info::{UserTokenResponse, GetUserTokenError};
-pub use subscribe_info::{SubCondition, SubData, SubscribeRequestError};
+pub use subscribe_info::{SubCondition, SubConditionData, SubData, SubStatus, SubTransport, SubscriptionCost, SubscribeRequestError, ListSubscriptionsError, DeleteSubscriptionError};
+pub use refresh_user_token_info::RefreshUserTokenError;
+pub use validate_token_info::{TokenValidation, ValidateTokenError};
 pub use get_user_data_info::{UserDataObject, GetUserDataError};
+pub use get_custom_rewards_info::{CustomRewardObject, GetCustomRewardsError};
+pub use get_predictions_info::{PredictionObject, PredictionOutcomeObject, PredictorObject, PredictionStatus, GetPredictionsError};
+pub use get_hype_train_events_info::{HypeTrainEventObject, HypeTrainEventData, HypeTrainContributionObject, HypeTrainContributionType, GetHypeTrainEventsError};
 use twitch_sources_rework::common_data::eventsub_msgs::SubType;
 
-pub const TWITCH_API_URI: &str = "https://api.twitch.tv/helix";
-pub const TWITCH_API_AUTH: &str = "https://id.twitch.tv";
-
 #[async_trait::async_trait(?Send)]
 #[delegate]
 pub trait TwitchHttpClient {
-    async fn get_new_token(&self) -> Result<String, GetTokenError>;
+    async fn get_new_token(&self) -> Result<NewTokenResponse, GetTokenError>;
     async fn create_subscription(
         &self,
         sub_cond: SubCondition,
         sub_type: SubType,
-        callback_url: &str,
-        secret: &str,
-        app_token: &str
-    ) -> Result<subscribe_info::SubData, subscribe_info::SubscribeRequestError>;
+        transport: SubTransport,
+        token: &str
+    ) -> Result<(SubData, SubscriptionCost), SubscribeRequestError>;
+    async fn list_subscriptions(&self, app_token: &str) -> Result<(Vec<SubData>, SubscriptionCost), ListSubscriptionsError>;
+    async fn delete_subscription(&self, sub_id: &str, app_token: &str) -> Result<(), DeleteSubscriptionError>;
     async fn get_user_token(&self, code: &str, host: &str) -> Result<UserTokenResponse, GetUserTokenError>;
+    async fn refresh_user_token(&self, refresh_token: &str) -> Result<UserTokenResponse, RefreshUserTokenError>;
+    async fn validate_token(&self, access_token: &str) -> Result<TokenValidation, ValidateTokenError>;
     async fn get_user_data(&self, user_access_token: &str) -> Result<UserDataObject, GetUserDataError>;
+    async fn get_users(&self, user_access_token: &str, user_ids: &[String]) -> Result<Vec<UserDataObject>, GetUserDataError>;
+    async fn get_custom_rewards(&self, user_access_token: &str, broadcaster_id: &str) -> Result<Vec<CustomRewardObject>, GetCustomRewardsError>;
+    async fn get_latest_prediction(&self, user_access_token: &str, broadcaster_id: &str) -> Result<Option<PredictionObject>, GetPredictionsError>;
+    async fn get_hype_train_events(&self, user_access_token: &str, broadcaster_id: &str) -> Result<Vec<HypeTrainEventObject>, GetHypeTrainEventsError>;
+    /// Base of the URL twitch sends webhooks to, if there's one other than ngrok or the production one.
+    fn webhook_base_url(&self) -> Option<String>;
 }
 
 #[async_trait::async_trait(?Send)]
 impl TwitchHttpClient for HttpClient {
-    async fn get_new_token(&self) -> Result<String, GetTokenError> {
-        let response = self.0.post(&(TWITCH_API_AUTH.to_string() + "/oauth2/token"))
-            .json(&get_new_token_info::NewTokenRequest::default())
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(GetTokenError::HttpError)?
-            .error_for_status()
-            .map_err(GetTokenError::HttpError)?
-            .json::<get_new_token_info::NewTokenResponse>()
+    async fn get_new_token(&self) -> Result<NewTokenResponse, GetTokenError> {
+        let request = self.client.post(self.auth_url.clone() + "/oauth2/token")
+            .json(&get_new_token_info::NewTokenRequest::new(self.client_id(), self.client_secret()))
+            .header("Content-Type", "application/json");
+
+        // an extra token minted on a retry doesn't hurt anything
+        let response = self.send(request, true)
+            .await?
+            .json::<NewTokenResponse>()
             .await
             .map_err(GetTokenError::DeserializeError)?;
 
-        Ok(response.access_token)
+        Ok(response)
     }
 
     async fn create_subscription(
         &self,
         sub_cond: SubCondition,
         sub_type: SubType,
-        callback_url: &str,
-        secret: &str,
-        app_token: &str
-    ) -> Result<SubData, SubscribeRequestError> {
-        let response = self.0.post(TWITCH_API_URI.to_string() + "/eventsub/subscriptions")
-            .header("Client-ID", get_twitch_key())
-            .bearer_auth(app_token)
-            .json(&subscribe_info::SubRequest::new(sub_cond, sub_type, callback_url, secret))
-            .send()
-            .await
-            .map_err(SubscribeRequestError::HttpError)?
+        transport: SubTransport,
+        token: &str
+    ) -> Result<(SubData, SubscriptionCost), SubscribeRequestError> {
+        let request = self.client.post(self.api_url.clone() + "/eventsub/subscriptions")
+            .header("Client-ID", self.client_id())
+            .bearer_auth(token)
+            .json(&subscribe_info::SubRequest::new(sub_cond, sub_type, transport));
+
+        let response = match self.send(request, false).await {
+            Ok(response) => response,
+            Err(SendError::Api(err)) if err.status() == StatusCode::UNAUTHORIZED => return Err(SubscribeRequestError::Unauthorized),
+            Err(err) => return Err(err.into()),
+        };
+
+        let response = response
             .json::<subscribe_info::SubResponse>()
             .await
             .map_err(SubscribeRequestError::DeserializeError)?;
 
-        response.data.into_iter().next().ok_or(SubscribeRequestError::ResponseSubEmpty)
+        let sub_data = response.data.into_iter().next().ok_or(SubscribeRequestError::ResponseSubEmpty)?;
+
+        Ok((sub_data, response.cost))
+    }
+
+    async fn list_subscriptions(&self, app_token: &str) -> Result<(Vec<SubData>, SubscriptionCost), ListSubscriptionsError> {
+        let mut subs = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        // pages are followed until twitch stops giving a cursor, totals are the same on every page
+        let cost = loop {
+            let mut request = self.client.get(self.api_url.clone() + "/eventsub/subscriptions")
+                .header("Client-ID", self.client_id())
+                .bearer_auth(app_token);
+
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("after", cursor)]);
+            }
+
+            let response = match self.send(request, true).await {
+                Ok(response) => response,
+                Err(SendError::Api(err)) if err.status() == StatusCode::UNAUTHORIZED => return Err(ListSubscriptionsError::Unauthorized),
+                Err(err) => return Err(err.into()),
+            };
+
+            let response = response
+                .json::<subscribe_info::ListSubResponse>()
+                .await
+                .map_err(ListSubscriptionsError::DeserializeError)?;
+
+            subs.extend(response.data);
+
+            cursor = response.pagination.cursor;
+            if cursor.is_none() {
+                break response.cost;
+            }
+        };
+
+        Ok((subs, cost))
+    }
+
+    async fn delete_subscription(&self, sub_id: &str, app_token: &str) -> Result<(), DeleteSubscriptionError> {
+        let request = self.client.delete(self.api_url.clone() + "/eventsub/subscriptions")
+            .query(&[("id", sub_id)])
+            .header("Client-ID", self.client_id())
+            .bearer_auth(app_token);
+
+        match self.send(request, true).await {
+            Ok(_) => Ok(()),
+            // already gone is as good as deleted
+            Err(SendError::Api(err)) if err.status() == StatusCode::NOT_FOUND => Ok(()),
+            Err(SendError::Api(err)) if err.status() == StatusCode::UNAUTHORIZED => Err(DeleteSubscriptionError::Unauthorized),
+            Err(err) => Err(err.into()),
+        }
     }
 
     async fn get_user_token(&self, code: &str, host: &str) -> Result<UserTokenResponse, GetUserTokenError> {
-        let mut resp = self.0.post(TWITCH_API_AUTH.to_string() + "/oauth2/token")
-            .json(&get_user_token_info::UserTokenRequest::new(code, host))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(GetUserTokenError::HttpError)?
+        let request = self.client.post(self.auth_url.clone() + "/oauth2/token")
+            .json(&get_user_token_info::UserTokenRequest::new(self.client_id(), self.client_secret(), code, host))
+            .header("Content-Type", "application/json");
+
+        // the code can only be used once
+        let mut resp = self.send(request, false)
+            .await?
             .json::<UserTokenResponse>()
             .await
             .map_err(GetUserTokenError::DeserializeError)?;
@@ -85,29 +155,165 @@ impl TwitchHttpClient for HttpClient {
         Ok(resp)
     }
 
+    async fn refresh_user_token(&self, refresh_token: &str) -> Result<UserTokenResponse, RefreshUserTokenError> {
+        let request = self.client.post(self.auth_url.clone() + "/oauth2/token")
+            .form(&refresh_user_token_info::RefreshTokenRequest::new(self.client_id(), self.client_secret(), refresh_token));
+
+        let response = match self.send(request, false).await {
+            Ok(response) => response,
+            // twitch answers with 400 when the refresh token is no longer valid
+            Err(SendError::Api(err)) if matches!(err.status(), StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED) => {
+                return Err(RefreshUserTokenError::InvalidToken)
+            },
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut resp = response
+            .json::<UserTokenResponse>()
+            .await
+            .map_err(RefreshUserTokenError::DeserializeError)?;
+
+        resp.scope.sort_unstable();
+
+        Ok(resp)
+    }
+
+    async fn validate_token(&self, access_token: &str) -> Result<TokenValidation, ValidateTokenError> {
+        let request = self.client.get(self.auth_url.clone() + "/oauth2/validate")
+            .header("Authorization", format!("OAuth {}", access_token));
+
+        let response = match self.send(request, true).await {
+            Ok(response) => response,
+            Err(SendError::Api(err)) if err.status() == StatusCode::UNAUTHORIZED => return Err(ValidateTokenError::InvalidToken),
+            Err(err) => return Err(err.into()),
+        };
+
+        response
+            .json::<TokenValidation>()
+            .await
+            .map_err(ValidateTokenError::DeserializeError)
+    }
+
     async fn get_user_data(&self, user_access_token: &str) -> Result<UserDataObject, GetUserDataError> {
-        let response = self.0.get(TWITCH_API_URI.to_string() + "/users")
+        let request = self.client.get(self.api_url.clone() + "/users")
             .header("Authorization", format!("Bearer {}", user_access_token))
-            .header("Client-Id", get_twitch_key())
-            .send()
-            .await
-            .map_err(GetUserDataError::HttpError)?
-            .error_for_status()
-            .map_err(GetUserDataError::HttpError)?
+            .header("Client-Id", self.client_id());
+
+        let response = self.send(request, true)
+            .await?
             .json::<get_user_data_info::UserDataResponse>()
             .await
             .map_err(GetUserDataError::DeserializeError)?;
 
         response.data.into_iter().next().ok_or(GetUserDataError::ResponseUserEmpty)
     }
+
+    async fn get_users(&self, user_access_token: &str, user_ids: &[String]) -> Result<Vec<UserDataObject>, GetUserDataError> {
+        // helix takes up to 100 ids, each as its own `id` param
+        let ids: Vec<_> = user_ids.iter().take(100).map(|user_id| ("id", user_id)).collect();
+
+        let request = self.client.get(self.api_url.clone() + "/users")
+            .query(&ids)
+            .header("Authorization", format!("Bearer {}", user_access_token))
+            .header("Client-Id", self.client_id());
+
+        let response = self.send(request, true)
+            .await?
+            .json::<get_user_data_info::UserDataResponse>()
+            .await
+            .map_err(GetUserDataError::DeserializeError)?;
+
+        Ok(response.data)
+    }
+
+    async fn get_custom_rewards(&self, user_access_token: &str, broadcaster_id: &str) -> Result<Vec<CustomRewardObject>, GetCustomRewardsError> {
+        let request = self.client.get(self.api_url.clone() + "/channel_points/custom_rewards")
+            .query(&[("broadcaster_id", broadcaster_id)])
+            .header("Authorization", format!("Bearer {}", user_access_token))
+            .header("Client-Id", self.client_id());
+
+        let response = self.send(request, true)
+            .await?
+            .json::<get_custom_rewards_info::CustomRewardsResponse>()
+            .await
+            .map_err(GetCustomRewardsError::DeserializeError)?;
+
+        Ok(response.data)
+    }
+
+    async fn get_latest_prediction(&self, user_access_token: &str, broadcaster_id: &str) -> Result<Option<PredictionObject>, GetPredictionsError> {
+        // newest come first
+        let request = self.client.get(self.api_url.clone() + "/predictions")
+            .query(&[("broadcaster_id", broadcaster_id), ("first", "1")])
+            .header("Authorization", format!("Bearer {}", user_access_token))
+            .header("Client-Id", self.client_id());
+
+        let response = self.send(request, true)
+            .await?
+            .json::<get_predictions_info::PredictionsResponse>()
+            .await
+            .map_err(GetPredictionsError::DeserializeError)?;
+
+        Ok(response.data.into_iter().next())
+    }
+
+    async fn get_hype_train_events(&self, user_access_token: &str, broadcaster_id: &str) -> Result<Vec<HypeTrainEventObject>, GetHypeTrainEventsError> {
+        // newest come first, a page is enough to cover the levels of the running train
+        let request = self.client.get(self.api_url.clone() + "/hypetrain/events")
+            .query(&[("broadcaster_id", broadcaster_id), ("first", "100")])
+            .header("Authorization", format!("Bearer {}", user_access_token))
+            .header("Client-Id", self.client_id());
+
+        let response = self.send(request, true)
+            .await?
+            .json::<get_hype_train_events_info::HypeTrainEventsResponse>()
+            .await
+            .map_err(GetHypeTrainEventsError::DeserializeError)?;
+
+        Ok(response.data)
+    }
+
+    fn webhook_base_url(&self) -> Option<String> {
+        self.webhook_base_url.clone().or_else(get_webhook_base_url)
+    }
 }
 
+// every request error has the same two ways to fail on sending
+macro_rules! from_send_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<SendError> for $error {
+                fn from(err: SendError) -> Self {
+                    match err {
+                        SendError::Http(err) => Self::HttpError(err),
+                        SendError::Api(err) => Self::ApiError(err),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_send_error!(
+    GetTokenError,
+    SubscribeRequestError,
+    ListSubscriptionsError,
+    DeleteSubscriptionError,
+    GetUserTokenError,
+    RefreshUserTokenError,
+    ValidateTokenError,
+    GetUserDataError,
+    GetCustomRewardsError,
+    GetPredictionsError,
+    GetHypeTrainEventsError
+);
+
 
 mod get_new_token_info {
     use serde::{Serialize, Deserialize};
     use thiserror::Error;
 
-    use crate::util::{get_twitch_key, get_twitch_secret};
+    use crate::http_client::HelixError;
 
     #[derive(Serialize)]
     pub(crate) struct NewTokenRequest {
@@ -116,18 +322,18 @@ mod get_new_token_info {
         pub grant_type: String
     }
 
-    impl Default for NewTokenRequest {
-        fn default() -> Self {
+    impl NewTokenRequest {
+        pub fn new(client_id: String, client_secret: String) -> Self {
             Self {
-                client_id: get_twitch_key(),
-                client_secret: get_twitch_secret(),
+                client_id,
+                client_secret,
                 grant_type: "client_credentials".to_string()
             }
         }
     }
 
     #[derive(Deserialize)]
-    pub(crate) struct NewTokenResponse {
+    pub struct NewTokenResponse {
         pub access_token: String,
         pub expires_in: i32,
         pub token_type: String
@@ -137,6 +343,8 @@ mod get_new_token_info {
     pub enum GetTokenError {
         #[error("Error while executing an HTTP request: {0}")]
         HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
         #[error("Error while deserializing an HTTP response: {0}")]
         DeserializeError(reqwest::Error),
     }
@@ -147,6 +355,7 @@ mod subscribe_info {
     use thiserror::Error;
     use twitch_sources_rework::common_data::eventsub_msgs::SubType;
 
+    use crate::http_client::HelixError;
     use crate::util::get_twitch_key;
 
     #[derive(Clone, Serialize, Deserialize)]
@@ -168,14 +377,76 @@ mod subscribe_info {
         pub fn client_id() -> Self {
             Self::ClientId(get_twitch_key())
         }
+
+        // some types want the user in a different field, or in more than one
+        pub fn to_data(&self, sub_type: &SubType) -> SubConditionData {
+            match (self, sub_type) {
+                (SubCondition::ClientId(client_id), _) => SubConditionData {
+                    client_id: Some(client_id.clone()),
+                    ..Default::default()
+                },
+                (SubCondition::BroadcasterUserId(user_id), SubType::ChannelFollow) => SubConditionData {
+                    broadcaster_user_id: Some(user_id.clone()),
+                    moderator_user_id: Some(user_id.clone()),
+                    ..Default::default()
+                },
+                (SubCondition::BroadcasterUserId(user_id), SubType::ChannelRaid) => SubConditionData {
+                    to_broadcaster_user_id: Some(user_id.clone()),
+                    ..Default::default()
+                },
+                (SubCondition::BroadcasterUserId(user_id), _) => SubConditionData {
+                    broadcaster_user_id: Some(user_id.clone()),
+                    ..Default::default()
+                },
+            }
+        }
     }
 
-    #[derive(Serialize, Deserialize)]
+    // condition as it's sent to and received from twitch
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    pub struct SubConditionData {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub broadcaster_user_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub moderator_user_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub from_broadcaster_user_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub to_broadcaster_user_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub client_id: Option<String>,
+    }
+
+    // webhooks use callback and secret, websockets use session_id
+    #[derive(Clone, Serialize, Deserialize)]
     pub struct SubTransport {
         pub method: String,
-        pub callback: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        pub secret: Option<String>
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub callback: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub secret: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub session_id: Option<String>,
+    }
+
+    impl SubTransport {
+        pub fn webhook(callback_url: &str, secret: &str) -> Self {
+            Self {
+                method: "webhook".to_string(),
+                callback: Some(callback_url.to_string()),
+                secret: Some(secret.to_string()),
+                session_id: None,
+            }
+        }
+
+        pub fn websocket(session_id: &str) -> Self {
+            Self {
+                method: "websocket".to_string(),
+                callback: None,
+                secret: None,
+                session_id: Some(session_id.to_string()),
+            }
+        }
     }
 
     #[derive(Serialize)]
@@ -183,26 +454,22 @@ mod subscribe_info {
         #[serde(rename = "type")]
         pub type_: SubType,
         pub version: String,
-        pub condition: SubCondition,
+        pub condition: SubConditionData,
         pub transport: SubTransport
     }
 
     impl SubRequest {
-        pub(crate) fn new(sub_cond: SubCondition, sub_type: SubType, callback_url: &str, secret: &str) -> Self {
+        pub(crate) fn new(sub_cond: SubCondition, sub_type: SubType, transport: SubTransport) -> Self {
             Self {
+                version: sub_type.version().to_string(),
+                condition: sub_cond.to_data(&sub_type),
                 type_: sub_type,
-                version: "1".to_string(),
-                condition: sub_cond,
-                transport: SubTransport {
-                    method: "webhook".to_string(),
-                    callback: callback_url.to_string(),
-                    secret: Some(secret.to_string())
-                },
+                transport,
             }
         }
     }
 
-    #[derive(Serialize, Deserialize)]
+    #[derive(Serialize, Deserialize, PartialEq)]
     #[serde(rename_all="snake_case")]
     pub enum SubStatus {
         Enabled,
@@ -212,7 +479,23 @@ mod subscribe_info {
         AuthorizationRevoked,
         ModeratorRemoved,
         UserRemoved,
-        VersionRemoved
+        VersionRemoved,
+        WebsocketDisconnected,
+        WebsocketFailedPingPong,
+        WebsocketReceivedInboundTraffic,
+        WebsocketConnectionUnused,
+        WebsocketInternalError,
+        WebsocketNetworkTimeout,
+        WebsocketNetworkError,
+        #[serde(other)]
+        Unknown
+    }
+
+    impl SubStatus {
+        // anything else won't get events anymore
+        pub fn is_alive(&self) -> bool {
+            matches!(self, SubStatus::Enabled | SubStatus::WebhookCallbackVerificationPending)
+        }
     }
 
     #[derive(Deserialize)]
@@ -223,28 +506,76 @@ mod subscribe_info {
         pub type_: SubType,
         pub version: String,
         pub cost: i64,
-        pub condition: SubCondition,
+        pub condition: SubConditionData,
         pub transport: SubTransport,
         #[serde(with = "time::serde::rfc3339")]
         pub created_at: time::OffsetDateTime,
     }
     
-    #[derive(Deserialize)]
-    pub(crate) struct SubResponse {
-        pub data: Vec<SubData>,
+    // totals as twitch counts them, for the app or for the user whose token made the subscriptions
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct SubscriptionCost {
         pub total: i64,
         pub total_cost: i64,
         pub max_total_cost: i64
     }
 
+    #[derive(Deserialize)]
+    pub(crate) struct SubResponse {
+        pub data: Vec<SubData>,
+        #[serde(flatten)]
+        pub cost: SubscriptionCost
+    }
+
+    #[derive(Default, Deserialize)]
+    pub(crate) struct Pagination {
+        #[serde(default)]
+        pub cursor: Option<String>
+    }
+
+    #[derive(Deserialize)]
+    pub(crate) struct ListSubResponse {
+        pub data: Vec<SubData>,
+        #[serde(default)]
+        pub pagination: Pagination,
+        #[serde(flatten)]
+        pub cost: SubscriptionCost,
+    }
+
     #[derive(Debug, Error)]
     pub enum SubscribeRequestError {
         #[error("Error while executing an HTTP request: {0}")]
         HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
         #[error("Error while deserializing an HTTP response: {0}")]
         DeserializeError(reqwest::Error),
         #[error("Response is valid, but had subscription data empty")]
-        ResponseSubEmpty
+        ResponseSubEmpty,
+        #[error("Token was rejected")]
+        Unauthorized
+    }
+
+    #[derive(Debug, Error)]
+    pub enum ListSubscriptionsError {
+        #[error("Error while executing an HTTP request: {0}")]
+        HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
+        #[error("Error while deserializing an HTTP response: {0}")]
+        DeserializeError(reqwest::Error),
+        #[error("Token was rejected")]
+        Unauthorized,
+    }
+
+    #[derive(Debug, Error)]
+    pub enum DeleteSubscriptionError {
+        #[error("Error while executing an HTTP request: {0}")]
+        HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
+        #[error("Token was rejected")]
+        Unauthorized,
     }
 }
 
@@ -252,7 +583,8 @@ mod get_user_token_info {
     use serde::{Serialize, Deserialize};
     use thiserror::Error;
 
-    use crate::{REDIRECT_URL, util::{get_twitch_key, get_twitch_secret}};
+    use crate::http_client::HelixError;
+    use crate::REDIRECT_URL;
 
     #[derive(Serialize)]
     pub(crate) struct UserTokenRequest {
@@ -264,10 +596,10 @@ mod get_user_token_info {
     }
 
     impl UserTokenRequest {
-        pub fn new(code: &str, host: &str) -> Self {
+        pub fn new(client_id: String, client_secret: String, code: &str, host: &str) -> Self {
             UserTokenRequest {
-                client_id: get_twitch_key(),
-                client_secret: get_twitch_secret(),
+                client_id,
+                client_secret,
                 code: code.to_string(),
                 grant_type: "authorization_code".to_string(),
                 redirect_uri: host.to_string() + REDIRECT_URL
@@ -287,19 +619,91 @@ mod get_user_token_info {
     pub enum GetUserTokenError {
         #[error("Error while executing an HTTP request: {0}")]
         HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
+        #[error("Error while deserializing an HTTP response: {0}")]
+        DeserializeError(reqwest::Error),
+    }
+}
+
+mod refresh_user_token_info {
+    use serde::Serialize;
+    use thiserror::Error;
+
+    use crate::http_client::HelixError;
+
+    #[derive(Serialize)]
+    pub(crate) struct RefreshTokenRequest {
+        client_id: String,
+        client_secret: String,
+        grant_type: String,
+        refresh_token: String
+    }
+
+    impl RefreshTokenRequest {
+        pub fn new(client_id: String, client_secret: String, refresh_token: &str) -> Self {
+            Self {
+                client_id,
+                client_secret,
+                grant_type: "refresh_token".to_string(),
+                refresh_token: refresh_token.to_string()
+            }
+        }
+    }
+
+    #[derive(Debug, Error)]
+    pub enum RefreshUserTokenError {
+        #[error("Error while executing an HTTP request: {0}")]
+        HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
         #[error("Error while deserializing an HTTP response: {0}")]
         DeserializeError(reqwest::Error),
+        #[error("Refresh token is invalid")]
+        InvalidToken,
+    }
+}
+
+mod validate_token_info {
+    use serde::Deserialize;
+    use thiserror::Error;
+
+    use crate::http_client::HelixError;
+
+    #[derive(Deserialize)]
+    pub struct TokenValidation {
+        pub client_id: String,
+        pub login: Option<String>,
+        pub user_id: Option<String>,
+        #[serde(default)]
+        pub scopes: Option<Vec<String>>,
+        pub expires_in: i64
+    }
+
+    #[derive(Debug, Error)]
+    pub enum ValidateTokenError {
+        #[error("Error while executing an HTTP request: {0}")]
+        HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
+        #[error("Error while deserializing an HTTP response: {0}")]
+        DeserializeError(reqwest::Error),
+        #[error("Token is invalid")]
+        InvalidToken,
     }
 }
 
 mod get_user_data_info {
     use serde::Deserialize;
     use thiserror::Error;
+
+    use crate::http_client::HelixError;
     
     #[derive(Deserialize)]
     pub struct UserDataObject {
         pub id: String,
         pub login: String,
+        pub display_name: String,
         pub broadcaster_type: String
     }
     #[derive(Deserialize)]
@@ -311,9 +715,167 @@ mod get_user_data_info {
     pub enum GetUserDataError {
         #[error("Error while executing an HTTP request: {0}")]
         HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
         #[error("Error while deserializing an HTTP response: {0}")]
         DeserializeError(reqwest::Error),
         #[error("Response is valid, but had user data empty")]
         ResponseUserEmpty
     }
+}
+
+mod get_custom_rewards_info {
+    use serde::Deserialize;
+    use thiserror::Error;
+
+    use crate::http_client::HelixError;
+
+    #[derive(Deserialize)]
+    pub struct CustomRewardObject {
+        pub id: String,
+        pub title: String,
+        pub cost: i64,
+        pub is_enabled: bool
+    }
+    #[derive(Deserialize)]
+    pub(crate) struct CustomRewardsResponse {
+        pub data: Vec<CustomRewardObject>
+    }
+
+    #[derive(Debug, Error)]
+    pub enum GetCustomRewardsError {
+        #[error("Error while executing an HTTP request: {0}")]
+        HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
+        #[error("Error while deserializing an HTTP response: {0}")]
+        DeserializeError(reqwest::Error),
+    }
+}
+
+mod get_predictions_info {
+    use serde::Deserialize;
+    use thiserror::Error;
+
+    use crate::http_client::HelixError;
+
+    #[derive(Deserialize)]
+    pub struct PredictorObject {
+        pub user_id: String,
+        pub user_login: String,
+        pub user_name: String,
+        pub channel_points_used: i64,
+        pub channel_points_won: Option<i64>
+    }
+
+    #[derive(Deserialize)]
+    pub struct PredictionOutcomeObject {
+        pub id: String,
+        pub title: String,
+        pub users: i64,
+        pub channel_points: i64,
+        // null until someone predicts
+        pub top_predictors: Option<Vec<PredictorObject>>,
+        pub color: String
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all="UPPERCASE")]
+    pub enum PredictionStatus {
+        Active,
+        Canceled,
+        Locked,
+        Resolved
+    }
+
+    #[derive(Deserialize)]
+    pub struct PredictionObject {
+        pub id: String,
+        pub broadcaster_id: String,
+        pub broadcaster_name: String,
+        pub broadcaster_login: String,
+        pub title: String,
+        pub winning_outcome_id: Option<String>,
+        pub outcomes: Vec<PredictionOutcomeObject>,
+        // seconds from created_at until it locks
+        pub prediction_window: i64,
+        pub status: PredictionStatus,
+        pub created_at: String,
+        pub ended_at: Option<String>,
+        pub locked_at: Option<String>
+    }
+    #[derive(Deserialize)]
+    pub(crate) struct PredictionsResponse {
+        pub data: Vec<PredictionObject>
+    }
+
+    #[derive(Debug, Error)]
+    pub enum GetPredictionsError {
+        #[error("Error while executing an HTTP request: {0}")]
+        HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
+        #[error("Error while deserializing an HTTP response: {0}")]
+        DeserializeError(reqwest::Error),
+    }
+}
+
+mod get_hype_train_events_info {
+    use serde::Deserialize;
+    use thiserror::Error;
+
+    use crate::http_client::HelixError;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all="UPPERCASE")]
+    pub enum HypeTrainContributionType {
+        Bits,
+        Subs,
+        Other
+    }
+
+    #[derive(Deserialize)]
+    pub struct HypeTrainContributionObject {
+        pub total: u64,
+        #[serde(rename="type")]
+        pub type_: HypeTrainContributionType,
+        // only the id of the user, no login or name
+        pub user: String
+    }
+
+    #[derive(Deserialize)]
+    pub struct HypeTrainEventData {
+        pub id: String,
+        pub broadcaster_id: String,
+        pub total: u64,
+        pub goal: u64,
+        pub level: u64,
+        pub top_contributions: Vec<HypeTrainContributionObject>,
+        pub last_contribution: HypeTrainContributionObject,
+        pub started_at: String,
+        pub expires_at: String,
+        pub cooldown_end_time: String
+    }
+
+    #[derive(Deserialize)]
+    pub struct HypeTrainEventObject {
+        pub id: String,
+        pub event_type: String,
+        pub event_timestamp: String,
+        pub event_data: HypeTrainEventData
+    }
+    #[derive(Deserialize)]
+    pub(crate) struct HypeTrainEventsResponse {
+        pub data: Vec<HypeTrainEventObject>
+    }
+
+    #[derive(Debug, Error)]
+    pub enum GetHypeTrainEventsError {
+        #[error("Error while executing an HTTP request: {0}")]
+        HttpError(reqwest::Error),
+        #[error("Twitch responded with an error: {0}")]
+        ApiError(HelixError),
+        #[error("Error while deserializing an HTTP response: {0}")]
+        DeserializeError(reqwest::Error),
+    }
 }
\ No newline at end of file
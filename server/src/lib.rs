@@ -0,0 +1,22 @@
+pub mod db;
+pub mod domain;
+pub mod util;
+pub mod errors;
+pub mod http_client;
+pub mod routes;
+pub mod websockets;
+pub mod sse;
+pub mod twitch_api;
+pub mod middlewares;
+pub mod my_redis;
+
+pub use util::DbPool;
+pub use util::RedisPool;
+
+pub const REDIRECT_URL: &str = "/twitch_login/";
+pub const WEBHOOK_URL: &str = "/webhook/";
+
+#[cfg(not(debug_assertions))]
+pub const PROD_BASE_URL: &str = "https://will_see.com";
+
+pub type RunningTests = dashmap::DashSet<String>;
\ No newline at end of file
@@ -1,14 +1,3 @@
-mod db;
-mod domain;
-mod util;
-mod errors;
-mod http_client;
-mod routes;
-mod websockets;
-mod twitch_api;
-mod middlewares;
-mod my_redis;
-
 use actix_web::cookie::Key;
 
 use actix_files::{Files, NamedFile};
@@ -16,62 +5,70 @@ use actix_web::web as web_ax;
 use actix_web::{web::Data, App, HttpServer};
 use actix_web::middleware::Logger;
 use actix_web::dev::{fn_service, ServiceResponse, ServiceRequest};
-use http_client::twitch_client::SubCondition;
 use paperclip::actix::OpenApiExt;
 use paperclip::actix::web;
 
 use actix_session::SessionMiddleware;
+use actix_session::storage::{CookieSessionStore, SessionStore};
 
+use server::{domain, middlewares, routes, sse, twitch_api, util, websockets};
+use server::{RunningTests, REDIRECT_URL, WEBHOOK_URL};
+use server::http_client::twitch_client::SubCondition;
 use twitch_sources_rework::common_data::eventsub_msgs::SubType;
-pub use util::DbPool;
-pub use util::RedisPool;
-
-const REDIRECT_URL: &str = "/twitch_login/";
-const WEBHOOK_URL: &str = "/webhook/";
-
-#[cfg(not(debug_assertions))]
-const PROD_BASE_URL: &str = "https://will_see.com";
-
-pub type RunningTests = dashmap::DashSet<String>;
 
 #[actix_web::main]
 async fn main() {
     util::init_debug_log();
     util::init_dotenv();
 
-    let db_pool = util::create_connection_pool()
-        .expect("Unable to create DB pool");
-
-    let redis_session = util::get_redis_session().await
-        .expect("Unable to connect to Redis");
-
-    let redis_pool = util::get_redis_client_pool()
-        .expect("Unable to connect to Redis");
-
-    let context = util::Context::new();
-
-    let http_client = reqwest::Client::new();
-
     let secret_key_val = std::env::var("SECRET").expect("SECRET must be set");
     let secret_key = Key::from(secret_key_val.as_bytes());
 
-    // check and create needed subscriptions
-    domain::subscription::Subscription::get_or_create_subscriptions(
-        &context,
-        &[SubType::UserAuthorizationRevoke],
-        SubCondition::client_id(),
-    )
-        .await
-        .expect("UserAuthorizationRevoke subscription failed");
-
+    // single node mode without Postgres and Redis, sessions are kept in cookies
+    let (context, redis_session) = if util::use_in_memory_storage() {
+        (util::Context::in_memory(), None)
+    } else {
+        let redis_session = util::get_redis_session().await
+            .expect("Unable to connect to Redis");
+
+        (util::Context::new(), Some(redis_session))
+    };
+
+    actix_web::rt::spawn(twitch_api::user_tokens::run_token_maintainer(context.clone()));
+
+    if util::use_websocket_transport() {
+        // client-wide events like UserAuthorizationRevoke are webhook-only, so there's nothing to subscribe to here
+        actix_web::rt::spawn(twitch_api::eventsub_ws::run_eventsub_client(context.clone()));
+    } else {
+        // check and create needed subscriptions
+        domain::subscription::Subscription::get_or_create_subscriptions(
+            &context,
+            &[SubType::UserAuthorizationRevoke],
+            SubCondition::client_id(),
+        )
+            .await
+            .expect("UserAuthorizationRevoke subscription failed");
+
+        // websocket subscriptions end with their session, only webhook ones can pile up
+        actix_web::rt::spawn(twitch_api::reconciler::run_reconciler(context.clone()));
+    }
+
+    match redis_session {
+        Some(redis_session) => run_server(context, secret_key, move || redis_session.clone()).await,
+        None => run_server(context, secret_key, CookieSessionStore::default).await,
+    }
+}
+
+async fn run_server<Store, F>(context: util::Context, secret_key: Key, session_store: F)
+where
+    Store: SessionStore + 'static,
+    F: Fn() -> Store + Clone + Send + 'static,
+{
     _ = HttpServer::new(move || {
         App::new()
             .wrap(Logger::default())
             .wrap_api()
-            .wrap(SessionMiddleware::new(redis_session.clone(), secret_key.clone()))
-            .app_data(Data::new(db_pool.clone()))
-            .app_data(Data::new(redis_pool.clone()))
-            .app_data(Data::new(http_client.clone()))
+            .wrap(SessionMiddleware::new(session_store(), secret_key.clone()))
             .app_data(context.clone())
             .app_data(Data::new(RunningTests::new()))
             .service(
@@ -80,6 +77,8 @@ async fn main() {
                     .route("/login_check", web::get().to(routes::login_check))
                     .route("/generate_login_token", web::get().to(routes::generate_login_token))
                     .route("/test", web::get().to(routes::execute_test))
+                    .route("/custom_rewards", web::get().to(routes::custom_rewards))
+                    .route("/admin/subscription_usage", web::get().to(routes::subscription_usage))
             )
             .route(REDIRECT_URL, web::get().to(routes::twitch_login_end))
             .with_json_spec_at("/api_spec/v2")
@@ -90,6 +89,28 @@ async fn main() {
                 web_ax::scope("/ws")
                     .service(
                         // builds a scope with all websockets
+                        {
+                            let mut scope = web_ax::scope("/sources")
+                                .route("/multi", web_ax::get().to(websockets::multi_websocket_starter));
+
+                            for websocket_data in websockets::WEBSOCKET_DATA_TYPES {
+                                scope = scope.route(
+                                    &("/".to_string() + websocket_data.topic),
+                                    web_ax::get().to(
+                                        |query, req, session, stream, ctx|
+                                            websockets::websocket_starter(websocket_data, query, req, session, stream, ctx)
+                                    )
+                                )
+                            }
+
+                            scope
+                        }
+                    )
+            )
+            .service(
+                web_ax::scope("/sse")
+                    .service(
+                        // same topics as the websockets
                         {
                             let mut scope = web_ax::scope("/sources");
 
@@ -97,8 +118,8 @@ async fn main() {
                                 scope = scope.route(
                                     &("/".to_string() + websocket_data.topic),
                                     web_ax::get().to(
-                                        |req, session, stream, ctx|
-                                            websockets::websocket_starter(websocket_data, req, session, stream, ctx)
+                                        |req, session, ctx|
+                                            sse::sse_starter(websocket_data, req, session, ctx)
                                     )
                                 )
                             }
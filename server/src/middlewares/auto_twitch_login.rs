@@ -73,7 +73,7 @@ where
                 .map_err(|err| MyErrors::InternalServerError(err.to_string()))?;
             let host = req.connection_info().scheme().to_string() + "://" + req.connection_info().host();
 
-            let twitch_api_query = serde_urlencoded::ser::to_string(LoginUrlResponse::new(&host, &new_state))?;
+            let twitch_api_query = serde_urlencoded::ser::to_string(LoginUrlResponse::new(&host, &new_state, ctx.http_client.client_id()))?;
 
             Ok(
                 req
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use auto_delegate::delegate;
+use redis::AsyncCommands;
+
+use super::{ResultRedis, MemoryRedis, PooledRedis, RedisClient, RedisError};
+
+/// How often sockets say their subscriptions are still in use.
+pub const SUBSCRIPTION_BEAT_INTERVAL: Duration = Duration::from_secs(60);
+// a couple of missed beats, sockets of a server that went down stop counting after this
+const HEARTBEAT_TTL: Duration = Duration::from_secs(3 * 60);
+
+fn heartbeat_key(sub_id: &str) -> String {
+    "sub_connected:".to_string() + sub_id
+}
+
+/// Keeps track of subscriptions some socket uses right now, across all servers.
+/// Keys expire by themselves, so sockets that never got to say they're gone don't keep them around.
+#[async_trait::async_trait(?Send)]
+#[delegate]
+pub trait ConnectionHeartbeat {
+    async fn beat_subscriptions(&self, sub_ids: &[String]) -> ResultRedis<()>;
+    async fn is_subscription_connected(&self, sub_id: &str) -> ResultRedis<bool>;
+}
+
+#[async_trait::async_trait(?Send)]
+impl ConnectionHeartbeat for PooledRedis {
+    async fn beat_subscriptions(&self, sub_ids: &[String]) -> ResultRedis<()> {
+        if sub_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut redis_conn = self.get_conn().await?;
+
+        let mut pipe = redis::pipe();
+        for sub_id in sub_ids {
+            pipe.set_ex(heartbeat_key(sub_id), 1, HEARTBEAT_TTL.as_secs() as usize).ignore();
+        }
+
+        pipe.query_async(&mut redis_conn).await.map_err(|_| RedisError::Other)
+    }
+
+    async fn is_subscription_connected(&self, sub_id: &str) -> ResultRedis<bool> {
+        let mut redis_conn = self.get_conn().await?;
+
+        redis_conn.exists(heartbeat_key(sub_id)).await.map_err(|_| RedisError::Other)
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ConnectionHeartbeat for MemoryRedis {
+    async fn beat_subscriptions(&self, sub_ids: &[String]) -> ResultRedis<()> {
+        for sub_id in sub_ids {
+            self.set(&heartbeat_key(sub_id), "1", Some(HEARTBEAT_TTL));
+        }
+
+        Ok(())
+    }
+
+    async fn is_subscription_connected(&self, sub_id: &str) -> ResultRedis<bool> {
+        Ok(self.get(&heartbeat_key(sub_id)).is_some())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ConnectionHeartbeat for RedisClient {
+    async fn beat_subscriptions(&self, sub_ids: &[String]) -> ResultRedis<()> {
+        dispatch!(self, beat_subscriptions(sub_ids))
+    }
+
+    async fn is_subscription_connected(&self, sub_id: &str) -> ResultRedis<bool> {
+        dispatch!(self, is_subscription_connected(sub_id))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use auto_delegate::delegate;
+use redis::AsyncCommands;
+
+use crate::http_client::twitch_client::SubscriptionCost;
+
+use super::{ResultRedis, MemoryRedis, PooledRedis, RedisClient, RedisError};
+
+const SUBSCRIPTION_COST_KEY: &str = "eventsub_subscription_cost";
+// twitch only gives totals when something is created or listed, so they're kept short
+// in case something went away without being cleared, like subscriptions twitch revoked
+const SUBSCRIPTION_COST_TTL: Duration = Duration::from_secs(5 * 60);
+
+// webhook subscriptions count against the app, websocket ones against the user whose token made them
+fn cost_key(user_id: Option<i64>) -> String {
+    match user_id {
+        Some(user_id) => format!("{}:{}", SUBSCRIPTION_COST_KEY, user_id),
+        None => SUBSCRIPTION_COST_KEY.to_string(),
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+#[delegate]
+pub trait SubscriptionCostCache {
+    /// Latest totals of the app with `None`, of the user's websocket subscriptions otherwise.
+    async fn get_subscription_cost(&self, user_id: Option<i64>) -> ResultRedis<Option<SubscriptionCost>>;
+    async fn update_subscription_cost(&self, user_id: Option<i64>, cost: &SubscriptionCost) -> ResultRedis<()>;
+    /// Forgets the totals after subscriptions are deleted, the next one created gets fresh ones.
+    async fn clear_subscription_cost(&self, user_id: Option<i64>) -> ResultRedis<()>;
+}
+
+#[async_trait::async_trait(?Send)]
+impl SubscriptionCostCache for PooledRedis {
+    async fn get_subscription_cost(&self, user_id: Option<i64>) -> ResultRedis<Option<SubscriptionCost>> {
+        let mut redis_conn = self.get_conn().await?;
+
+        let val: Option<String> = redis_conn.get(cost_key(user_id)).await.map_err(|_| RedisError::Other)?;
+
+        Ok(val.and_then(|val| serde_json::from_str(&val).ok()))
+    }
+
+    async fn update_subscription_cost(&self, user_id: Option<i64>, cost: &SubscriptionCost) -> ResultRedis<()> {
+        let mut redis_conn = self.get_conn().await?;
+
+        let val = serde_json::to_string(cost).expect("No way we fail serialization");
+        redis_conn.set_ex(cost_key(user_id), val, SUBSCRIPTION_COST_TTL.as_secs() as usize).await.map_err(|_| RedisError::Other)?;
+
+        Ok(())
+    }
+
+    async fn clear_subscription_cost(&self, user_id: Option<i64>) -> ResultRedis<()> {
+        let mut redis_conn = self.get_conn().await?;
+
+        redis_conn.del(cost_key(user_id)).await.map_err(|_| RedisError::Other)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl SubscriptionCostCache for MemoryRedis {
+    async fn get_subscription_cost(&self, user_id: Option<i64>) -> ResultRedis<Option<SubscriptionCost>> {
+        Ok(self.get(&cost_key(user_id)).and_then(|val| serde_json::from_str(&val).ok()))
+    }
+
+    async fn update_subscription_cost(&self, user_id: Option<i64>, cost: &SubscriptionCost) -> ResultRedis<()> {
+        let val = serde_json::to_string(cost).expect("No way we fail serialization");
+        self.set(&cost_key(user_id), &val, Some(SUBSCRIPTION_COST_TTL));
+
+        Ok(())
+    }
+
+    async fn clear_subscription_cost(&self, user_id: Option<i64>) -> ResultRedis<()> {
+        self.del(&cost_key(user_id));
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl SubscriptionCostCache for RedisClient {
+    async fn get_subscription_cost(&self, user_id: Option<i64>) -> ResultRedis<Option<SubscriptionCost>> {
+        dispatch!(self, get_subscription_cost(user_id))
+    }
+
+    async fn update_subscription_cost(&self, user_id: Option<i64>, cost: &SubscriptionCost) -> ResultRedis<()> {
+        dispatch!(self, update_subscription_cost(user_id, cost))
+    }
+
+    async fn clear_subscription_cost(&self, user_id: Option<i64>) -> ResultRedis<()> {
+        dispatch!(self, clear_subscription_cost(user_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubscriptionCostCache;
+    use crate::http_client::twitch_client::SubscriptionCost;
+    use crate::my_redis::MemoryRedis;
+
+    #[actix_web::test]
+    async fn keeps_app_and_user_totals_apart() {
+        let redis = MemoryRedis::new();
+
+        redis.update_subscription_cost(None, &SubscriptionCost { total: 5, total_cost: 5, max_total_cost: 10000 }).await
+            .expect("Cost to be cached");
+        redis.update_subscription_cost(Some(1337), &SubscriptionCost { total: 2, total_cost: 2, max_total_cost: 10 }).await
+            .expect("Cost to be cached");
+
+        let app = redis.get_subscription_cost(None).await.expect("Cost to be read").expect("Cost to be cached");
+        assert_eq!(app.total_cost, 5);
+
+        let user = redis.get_subscription_cost(Some(1337)).await.expect("Cost to be read").expect("Cost to be cached");
+        assert_eq!(user.max_total_cost, 10);
+
+        assert!(redis.get_subscription_cost(Some(42)).await.expect("Cost to be read").is_none());
+
+        redis.clear_subscription_cost(Some(1337)).await.expect("Cost to be cleared");
+        assert!(redis.get_subscription_cost(Some(1337)).await.expect("Cost to be read").is_none());
+        assert!(redis.get_subscription_cost(None).await.expect("Cost to be read").is_some());
+    }
+}
\ No newline at end of file
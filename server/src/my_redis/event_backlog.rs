@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use auto_delegate::delegate;
+
+use super::{ResultRedis, MemoryRedis, PooledRedis, RedisClient, RedisError};
+
+// enough to cover a few seconds of a reconnecting socket during a busy hype train
+const BACKLOG_LENGTH: usize = 100;
+// topics nothing happened on for this long start over from 1
+const BACKLOG_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn seq_key(user_id: &str, topic: &str) -> String {
+    "event_seq:".to_string() + user_id + ":" + topic
+}
+
+fn backlog_key(user_id: &str, topic: &str) -> String {
+    "event_backlog:".to_string() + user_id + ":" + topic
+}
+
+// seq and the backlog entry have to be in step, or the stream refuses the id
+const APPEND_SCRIPT: &str = r"
+local seq = redis.call('INCR', KEYS[1])
+redis.call('XADD', KEYS[2], 'MAXLEN', ARGV[2], seq .. '-0', 'payload', ARGV[1])
+redis.call('EXPIRE', KEYS[1], ARGV[3])
+redis.call('EXPIRE', KEYS[2], ARGV[3])
+return seq
+";
+
+/// Puts the sequence number in front of a message on the bus, sources get it along with the event.
+pub fn with_seq(seq: u64, payload: &str) -> String {
+    format!("seq:{}\n{}", seq, payload)
+}
+
+/// The sequence number and the message itself, messages published without one
+/// (like the widget tests) come through as they are.
+pub fn split_seq(message: &str) -> (Option<u64>, &str) {
+    let framed = message.strip_prefix("seq:")
+        .and_then(|rest| rest.split_once('\n'))
+        .and_then(|(seq, payload)| Some((seq.parse().ok()?, payload)));
+
+    match framed {
+        Some((seq, payload)) => (Some(seq), payload),
+        None => (None, message),
+    }
+}
+
+// only what came right after `since` is of any use, anything with a gap needs a snapshot instead
+fn covering(since: u64, latest: u64, events: Vec<(u64, String)>) -> Option<Vec<(u64, String)>> {
+    if since > latest {
+        // numbering started over, the source is from before that
+        return None;
+    }
+
+    match events.first() {
+        Some((first, _)) if *first != since + 1 => None,
+        None if since != latest => None,
+        _ => Some(events),
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+#[delegate]
+pub trait EventBacklog {
+    /// Keeps the event around for sources that reconnect and gives it the next sequence number of the topic.
+    async fn append_event(&self, user_id: &str, topic: &str, payload: &str) -> ResultRedis<u64>;
+    /// Everything published to the topic after `since`, `None` if some of it isn't kept anymore.
+    async fn get_events_since(&self, user_id: &str, topic: &str, since: u64) -> ResultRedis<Option<Vec<(u64, String)>>>;
+}
+
+#[async_trait::async_trait(?Send)]
+impl EventBacklog for PooledRedis {
+    async fn append_event(&self, user_id: &str, topic: &str, payload: &str) -> ResultRedis<u64> {
+        let mut redis_conn = self.get_conn().await?;
+
+        redis::Script::new(APPEND_SCRIPT)
+            .key(seq_key(user_id, topic))
+            .key(backlog_key(user_id, topic))
+            .arg(payload)
+            .arg(BACKLOG_LENGTH)
+            .arg(BACKLOG_TTL.as_secs())
+            .invoke_async(&mut redis_conn).await
+            .map_err(|_| RedisError::Other)
+    }
+
+    async fn get_events_since(&self, user_id: &str, topic: &str, since: u64) -> ResultRedis<Option<Vec<(u64, String)>>> {
+        let mut redis_conn = self.get_conn().await?;
+
+        // entry ids are `<seq>-0`
+        let entries: Vec<(String, Vec<String>)> = redis::cmd("XRANGE")
+            .arg(backlog_key(user_id, topic))
+            .arg(format!("{}-0", since + 1))
+            .arg("+")
+            .query_async(&mut redis_conn).await
+            .map_err(|_| RedisError::Other)?;
+
+        let latest: Option<u64> = redis::cmd("GET")
+            .arg(seq_key(user_id, topic))
+            .query_async(&mut redis_conn).await
+            .map_err(|_| RedisError::Other)?;
+
+        let mut events = Vec::new();
+        for (id, fields) in entries {
+            let seq = id.split_once('-').and_then(|(seq, _)| seq.parse().ok()).ok_or(RedisError::Other)?;
+            // fields come as name, value pairs and there's only the payload
+            let payload = fields.into_iter().nth(1).ok_or(RedisError::Other)?;
+
+            events.push((seq, payload));
+        }
+
+        Ok(covering(since, latest.unwrap_or(0), events))
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl EventBacklog for MemoryRedis {
+    async fn append_event(&self, user_id: &str, topic: &str, payload: &str) -> ResultRedis<u64> {
+        Ok(self.append(&backlog_key(user_id, topic), payload, BACKLOG_LENGTH))
+    }
+
+    async fn get_events_since(&self, user_id: &str, topic: &str, since: u64) -> ResultRedis<Option<Vec<(u64, String)>>> {
+        let (latest, events) = self.range_since(&backlog_key(user_id, topic), since);
+
+        Ok(covering(since, latest, events))
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl EventBacklog for RedisClient {
+    async fn append_event(&self, user_id: &str, topic: &str, payload: &str) -> ResultRedis<u64> {
+        dispatch!(self, append_event(user_id, topic, payload))
+    }
+
+    async fn get_events_since(&self, user_id: &str, topic: &str, since: u64) -> ResultRedis<Option<Vec<(u64, String)>>> {
+        dispatch!(self, get_events_since(user_id, topic, since))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventBacklog, BACKLOG_LENGTH, split_seq, with_seq};
+    use crate::my_redis::MemoryRedis;
+
+    #[test]
+    fn seq_goes_along_with_the_message() {
+        assert_eq!(split_seq(&with_seq(7, "{\"a\":1}")), (Some(7), "{\"a\":1}"));
+        assert_eq!(split_seq("{\"a\":1}"), (None, "{\"a\":1}"));
+    }
+
+    #[actix_web::test]
+    async fn gives_what_was_missed_while_it_is_kept() {
+        let redis = MemoryRedis::new();
+
+        for i in 1..=3 {
+            let seq = redis.append_event("1337", "polls", &i.to_string()).await.expect("Append to work");
+            assert_eq!(seq, i);
+        }
+
+        let missed = redis.get_events_since("1337", "polls", 1).await.expect("Read to work");
+        assert_eq!(missed, Some(vec![(2, "2".to_string()), (3, "3".to_string())]));
+
+        // nothing missed, nothing to send
+        let missed = redis.get_events_since("1337", "polls", 3).await.expect("Read to work");
+        assert_eq!(missed, Some(Vec::new()));
+
+        // from before a restart
+        let missed = redis.get_events_since("1337", "polls", 10).await.expect("Read to work");
+        assert_eq!(missed, None);
+
+        for i in 4..=(BACKLOG_LENGTH as u64 + 3) {
+            redis.append_event("1337", "polls", &i.to_string()).await.expect("Append to work");
+        }
+
+        // the first ones aren't kept anymore
+        let missed = redis.get_events_since("1337", "polls", 1).await.expect("Read to work");
+        assert_eq!(missed, None);
+    }
+}
\ No newline at end of file
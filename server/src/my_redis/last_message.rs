@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use auto_delegate::delegate;
+use redis::AsyncCommands;
+
+use super::{ResultRedis, MemoryRedis, PooledRedis, RedisClient, RedisError};
+
+fn last_message_key(user_id: &str, topic: &str) -> String {
+    "last_message:".to_string() + user_id + ":" + topic
+}
+
+#[async_trait::async_trait(?Send)]
+#[delegate]
+pub trait LastMessageStore {
+    async fn save_last_message(&self, user_id: &str, topic: &str, message: &str, ttl: Duration) -> ResultRedis<()>;
+    async fn get_last_message(&self, user_id: &str, topic: &str) -> ResultRedis<Option<String>>;
+    async fn remove_last_message(&self, user_id: &str, topic: &str) -> ResultRedis<()>;
+}
+
+#[async_trait::async_trait(?Send)]
+impl LastMessageStore for PooledRedis {
+    async fn save_last_message(&self, user_id: &str, topic: &str, message: &str, ttl: Duration) -> ResultRedis<()> {
+        let mut redis_conn = self.get_conn().await?;
+
+        // redis takes whole seconds, round up so short ttls don't become 0
+        let ttl_secs = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+
+        redis_conn.set_ex(last_message_key(user_id, topic), message, ttl_secs as usize).await
+            .map_err(|_| RedisError::Other)?;
+
+        Ok(())
+    }
+
+    async fn get_last_message(&self, user_id: &str, topic: &str) -> ResultRedis<Option<String>> {
+        let mut redis_conn = self.get_conn().await?;
+
+        redis_conn.get(last_message_key(user_id, topic)).await.map_err(|_| RedisError::Other)
+    }
+
+    async fn remove_last_message(&self, user_id: &str, topic: &str) -> ResultRedis<()> {
+        let mut redis_conn = self.get_conn().await?;
+
+        redis_conn.del(last_message_key(user_id, topic)).await.map_err(|_| RedisError::Other)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl LastMessageStore for MemoryRedis {
+    async fn save_last_message(&self, user_id: &str, topic: &str, message: &str, ttl: Duration) -> ResultRedis<()> {
+        self.set(&last_message_key(user_id, topic), message, Some(ttl));
+
+        Ok(())
+    }
+
+    async fn get_last_message(&self, user_id: &str, topic: &str) -> ResultRedis<Option<String>> {
+        Ok(self.get(&last_message_key(user_id, topic)))
+    }
+
+    async fn remove_last_message(&self, user_id: &str, topic: &str) -> ResultRedis<()> {
+        self.del(&last_message_key(user_id, topic));
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl LastMessageStore for RedisClient {
+    async fn save_last_message(&self, user_id: &str, topic: &str, message: &str, ttl: Duration) -> ResultRedis<()> {
+        dispatch!(self, save_last_message(user_id, topic, message, ttl))
+    }
+
+    async fn get_last_message(&self, user_id: &str, topic: &str) -> ResultRedis<Option<String>> {
+        dispatch!(self, get_last_message(user_id, topic))
+    }
+
+    async fn remove_last_message(&self, user_id: &str, topic: &str) -> ResultRedis<()> {
+        dispatch!(self, remove_last_message(user_id, topic))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,98 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct Entry {
+    value: String,
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn is_alive(&self) -> bool {
+        self.expires_at.map_or(true, |expires_at| expires_at > Instant::now())
+    }
+}
+
+// a capped redis stream with the counter numbering its entries
+#[derive(Default)]
+struct Backlog {
+    latest: u64,
+    events: VecDeque<(u64, String)>,
+}
+
+/// Keys kept in memory, only works within a single server.
+#[derive(Clone, Default)]
+pub struct MemoryRedis {
+    values: Arc<Mutex<HashMap<String, Entry>>>,
+    backlogs: Arc<Mutex<HashMap<String, Backlog>>>,
+}
+
+impl MemoryRedis {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the few redis commands the traits need
+
+    pub(super) fn get(&self, key: &str) -> Option<String> {
+        let values = self.values.lock().expect("Memory redis lock poisoned");
+
+        values.get(key).filter(|entry| entry.is_alive()).map(|entry| entry.value.clone())
+    }
+
+    pub(super) fn set(&self, key: &str, value: &str, ttl: Option<Duration>) {
+        self.set_if(key, value, ttl, false);
+    }
+
+    /// `SET NX`, returns `false` if the key was already set
+    pub(super) fn set_nx(&self, key: &str, value: &str, ttl: Option<Duration>) -> bool {
+        self.set_if(key, value, ttl, true)
+    }
+
+    fn set_if(&self, key: &str, value: &str, ttl: Option<Duration>, only_new: bool) -> bool {
+        let mut values = self.values.lock().expect("Memory redis lock poisoned");
+
+        // expired keys aren't looked at again, so they're dropped on writes
+        values.retain(|_, entry| entry.is_alive());
+
+        if only_new && values.contains_key(key) {
+            return false;
+        }
+
+        values.insert(key.to_string(), Entry {
+            value: value.to_string(),
+            expires_at: ttl.map(|ttl| Instant::now() + ttl),
+        });
+
+        true
+    }
+
+    pub(super) fn del(&self, key: &str) {
+        self.values.lock().expect("Memory redis lock poisoned").remove(key);
+    }
+
+    /// Numbers the value and keeps the last `max_len` of them, like `INCR` followed by `XADD MAXLEN`
+    pub(super) fn append(&self, key: &str, value: &str, max_len: usize) -> u64 {
+        let mut backlogs = self.backlogs.lock().expect("Memory redis lock poisoned");
+        let backlog = backlogs.entry(key.to_string()).or_default();
+
+        backlog.latest += 1;
+        backlog.events.push_back((backlog.latest, value.to_string()));
+
+        while backlog.events.len() > max_len {
+            backlog.events.pop_front();
+        }
+
+        backlog.latest
+    }
+
+    /// The latest number along with everything kept after `since`
+    pub(super) fn range_since(&self, key: &str, since: u64) -> (u64, Vec<(u64, String)>) {
+        let backlogs = self.backlogs.lock().expect("Memory redis lock poisoned");
+
+        match backlogs.get(key) {
+            Some(backlog) => (backlog.latest, backlog.events.iter().filter(|(seq, _)| *seq > since).cloned().collect()),
+            None => (0, Vec::new()),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use futures::{StreamExt, future::{self, Either}};
+use tokio::sync::{mpsc, oneshot, broadcast::{self, error::RecvError}};
+
+use super::{ChannelMessageStream, MessageStream, PooledRedis, PubsubCommand, PubsubControl, RedisError, RedisPool, ResultRedis, topic_channel};
+
+// slow websockets skip messages past this, same as they'd miss them on a Redis reconnect
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Pubsub within this process, for running a single server without Redis.
+#[derive(Clone)]
+pub struct BroadcastBus {
+    channel: broadcast::Sender<(String, Vec<u8>)>,
+}
+
+impl Default for BroadcastBus {
+    fn default() -> Self {
+        Self { channel: broadcast::channel(CHANNEL_CAPACITY).0 }
+    }
+}
+
+impl BroadcastBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn publish(&self, channel: &str, message: &[u8]) {
+        // no subscribers isn't an error, same as in redis
+        _ = self.channel.send((channel.to_string(), message.to_vec()));
+    }
+
+    async fn pubsub(&self) -> ResultRedis<(PubsubControl, ChannelMessageStream)> {
+        let (control, commands) = mpsc::unbounded_channel();
+        let state = (self.channel.subscribe(), commands, HashSet::new());
+
+        Ok((control, Box::pin(futures::stream::unfold(state, |(mut receiver, mut commands, mut channels)| async move {
+            loop {
+                let next = {
+                    let command = commands.recv();
+                    let message = receiver.recv();
+                    futures::pin_mut!(command, message);
+
+                    match future::select(command, message).await {
+                        Either::Left((command, _)) => Either::Left(command),
+                        Either::Right((message, _)) => Either::Right(message),
+                    }
+                };
+
+                match next {
+                    Either::Left(Some(PubsubCommand::Subscribe(channel, done))) => {
+                        channels.insert(channel);
+
+                        if let Some(done) = done {
+                            _ = done.send(());
+                        }
+                    },
+                    Either::Left(Some(PubsubCommand::Unsubscribe(channel))) => {
+                        channels.remove(&channel);
+                    },
+                    Either::Left(None) => return None,
+                    Either::Right(Ok((channel, message))) if channels.contains(&channel) => {
+                        let payload = String::from_utf8_lossy(&message).into_owned();
+                        return Some((Ok((channel, payload)), (receiver, commands, channels)));
+                    },
+                    Either::Right(Ok(_)) | Either::Right(Err(RecvError::Lagged(_))) => continue,
+                    Either::Right(Err(RecvError::Closed)) => return None,
+                }
+            }
+        }))))
+    }
+}
+
+/// Where events are published for the source websockets. Redis lets several servers share them,
+/// the broadcast one only reaches sockets connected to this server.
+#[derive(Clone)]
+pub enum MessageBus {
+    Redis(PooledRedis),
+    Broadcast(BroadcastBus),
+}
+
+impl MessageBus {
+    /// A pubsub connection that channels can be added to and removed from while it runs,
+    /// messages come with the channel they're for. The stream ends if the connection is lost.
+    /// Sockets don't use this directly, they all share one through `PubsubDispatcher`.
+    pub async fn pubsub(&self) -> ResultRedis<(PubsubControl, ChannelMessageStream)> {
+        match self {
+            MessageBus::Redis(bus) => bus.pubsub().await,
+            MessageBus::Broadcast(bus) => bus.pubsub().await,
+        }
+    }
+
+    /// Messages published to the user's topic from now on, on a connection of its own.
+    pub async fn subscribe(&self, user_id: i64, topic: &str) -> ResultRedis<MessageStream> {
+        let (control, messages) = self.pubsub().await?;
+
+        let (done, subscribed) = oneshot::channel();
+        control.send(PubsubCommand::Subscribe(topic_channel(&user_id.to_string(), topic), Some(done)))
+            .map_err(|_| RedisError::Other)?;
+
+        // the stream keeps going only as long as something can still send it commands
+        let mut messages = messages.map(move |msg| {
+            let _control = &control;
+            msg.map(|(_, payload)| payload)
+        });
+
+        // nothing comes through before the subscription is done, so this only drives it
+        futures::pin_mut!(subscribed);
+        match future::select(subscribed, messages.next()).await {
+            Either::Left((Ok(()), _)) => Ok(Box::pin(messages)),
+            Either::Left((Err(_), _)) | Either::Right(_) => Err(RedisError::Other),
+        }
+    }
+}
+
+impl From<RedisPool> for MessageBus {
+    fn from(pool: RedisPool) -> Self {
+        Self::Redis(PooledRedis::new(pool))
+    }
+}
+
+impl From<BroadcastBus> for MessageBus {
+    fn from(bus: BroadcastBus) -> Self {
+        Self::Broadcast(bus)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,74 @@
+use auto_delegate::delegate;
+use redis::AsyncCommands;
+
+use std::time::Duration;
+
+use super::{ResultRedis, MemoryRedis, PooledRedis, RedisClient, RedisError};
+
+// messages older than this are rejected anyway, so ids don't need to be kept longer
+const SEEN_MESSAGE_TTL_SECS: usize = 10 * 60;
+
+#[async_trait::async_trait(?Send)]
+#[delegate]
+pub trait MessageDeduplicator {
+    /// Marks the message id as seen, returns `false` if it already was.
+    async fn mark_message_seen(&self, msg_id: &str) -> ResultRedis<bool>;
+    /// Unmarks the message id, so a redelivery of it isn't ignored.
+    async fn forget_message(&self, msg_id: &str) -> ResultRedis<()>;
+}
+
+fn seen_message_key(msg_id: &str) -> String {
+    "eventsub_message:".to_string() + msg_id
+}
+
+#[async_trait::async_trait(?Send)]
+impl MessageDeduplicator for PooledRedis {
+    async fn mark_message_seen(&self, msg_id: &str) -> ResultRedis<bool> {
+        let mut redis_conn = self.get_conn().await?;
+
+        // SET NX returns nil if the key already exists
+        let res: Option<String> = redis::cmd("SET")
+            .arg(seen_message_key(msg_id))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(SEEN_MESSAGE_TTL_SECS)
+            .query_async(&mut redis_conn)
+            .await
+            .map_err(|_| RedisError::Other)?;
+
+        Ok(res.is_some())
+    }
+
+    async fn forget_message(&self, msg_id: &str) -> ResultRedis<()> {
+        let mut redis_conn = self.get_conn().await?;
+
+        redis_conn.del(seen_message_key(msg_id)).await.map_err(|_| RedisError::Other)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MessageDeduplicator for MemoryRedis {
+    async fn mark_message_seen(&self, msg_id: &str) -> ResultRedis<bool> {
+        Ok(self.set_nx(&seen_message_key(msg_id), "1", Some(Duration::from_secs(SEEN_MESSAGE_TTL_SECS as u64))))
+    }
+
+    async fn forget_message(&self, msg_id: &str) -> ResultRedis<()> {
+        self.del(&seen_message_key(msg_id));
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MessageDeduplicator for RedisClient {
+    async fn mark_message_seen(&self, msg_id: &str) -> ResultRedis<bool> {
+        dispatch!(self, mark_message_seen(msg_id))
+    }
+
+    async fn forget_message(&self, msg_id: &str) -> ResultRedis<()> {
+        dispatch!(self, forget_message(msg_id))
+    }
+}
\ No newline at end of file
@@ -1,17 +1,65 @@
+// calls the same method on whichever backend the client uses
+macro_rules! dispatch {
+    ($client:expr, $method:ident($($arg:expr),*)) => {
+        match $client {
+            RedisClient::Redis(client) => client.$method($($arg),*).await,
+            RedisClient::Memory(client) => client.$method($($arg),*).await,
+        }
+    };
+}
+
 pub mod token_cache;
 pub mod publisher;
+pub mod message_dedup;
+pub mod cost_cache;
+pub mod last_message;
+pub mod event_backlog;
+pub mod connection_heartbeat;
+pub mod message_bus;
+mod memory;
+
+use std::pin::Pin;
 
 use deadpool_redis::Connection;
+use futures::{Stream, StreamExt, future::{self, Either}};
 use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+
+pub use memory::MemoryRedis;
+pub use message_bus::{BroadcastBus, MessageBus};
 
 use crate::RedisPool;
+use crate::util::get_redis_connection;
+
+pub type MessageStream = Pin<Box<dyn Stream<Item = ResultRedis<String>>>>;
+/// Messages along with the channel they were published to
+pub type ChannelMessageStream = Pin<Box<dyn Stream<Item = ResultRedis<(String, String)>>>>;
+
+/// Changes which channels a [`RedisClient::pubsub`] connection listens to.
+/// Subscribing answers on the sender once messages for the channel will come through.
+pub enum PubsubCommand {
+    Subscribe(String, Option<oneshot::Sender<()>>),
+    Unsubscribe(String),
+}
+
+pub type PubsubControl = mpsc::UnboundedSender<PubsubCommand>;
+
+/// The channel messages for the user's topic are published to
+pub fn topic_channel(user_id: &str, topic: &str) -> String {
+    user_id.to_string() + ":" + topic
+}
+
+/// The topic part of a channel from [`topic_channel`]
+pub fn channel_topic(channel: &str) -> &str {
+    channel.split_once(':').map_or(channel, |(_, topic)| topic)
+}
 
 #[derive(Clone)]
-pub struct RedisClient {
+pub struct PooledRedis {
     pool: RedisPool
 }
 
-impl RedisClient {
+impl PooledRedis {
     pub fn new(pool: RedisPool) -> Self {
         Self { pool }
     }
@@ -19,11 +67,70 @@ impl RedisClient {
     pub async fn get_conn(&self) -> ResultRedis<Connection> {
         self.pool.get().await.map_err(|_| RedisError::Other)
     }
+
+    async fn pubsub(&self) -> ResultRedis<(PubsubControl, ChannelMessageStream)> {
+        // pubsub takes the whole connection, so it's not one from the pool
+        let conn = get_redis_connection().await.map_err(|_| RedisError::Other)?;
+        let (control, commands) = mpsc::unbounded_channel();
+
+        let messages = futures::stream::unfold((conn.into_pubsub(), commands), |(mut pub_sub, mut commands)| async move {
+            loop {
+                // the message stream borrows the connection, so it's let go of for every command
+                let next = {
+                    let command = commands.recv();
+                    let messages = pub_sub.on_message();
+                    futures::pin_mut!(command, messages);
+
+                    match future::select(command, messages.next()).await {
+                        Either::Left((command, _)) => Either::Left(command),
+                        Either::Right((msg, _)) => Either::Right(msg),
+                    }
+                };
+
+                match next {
+                    Either::Left(Some(PubsubCommand::Subscribe(channel, done))) => {
+                        pub_sub.subscribe(channel).await.ok()?;
+
+                        if let Some(done) = done {
+                            _ = done.send(());
+                        }
+                    },
+                    Either::Left(Some(PubsubCommand::Unsubscribe(channel))) => pub_sub.unsubscribe(channel).await.ok()?,
+                    // nothing can subscribe anymore, so nothing's listening either
+                    Either::Left(None) => return None,
+                    Either::Right(Some(msg)) => {
+                        let item = msg.get_payload::<String>()
+                            .map(|payload| (msg.get_channel_name().to_string(), payload))
+                            .map_err(|_| RedisError::Other);
+
+                        return Some((item, (pub_sub, commands)));
+                    },
+                    // the connection is gone
+                    Either::Right(None) => return None,
+                }
+            }
+        });
+
+        Ok((control, Box::pin(messages)))
+    }
+}
+
+/// Redis, or its in memory stand-in for tests and running without Redis.
+#[derive(Clone)]
+pub enum RedisClient {
+    Redis(PooledRedis),
+    Memory(MemoryRedis),
 }
 
 impl From<RedisPool> for RedisClient {
     fn from(pool: RedisPool) -> Self {
-        Self::new(pool)
+        Self::Redis(PooledRedis::new(pool))
+    }
+}
+
+impl From<MemoryRedis> for RedisClient {
+    fn from(client: MemoryRedis) -> Self {
+        Self::Memory(client)
     }
 }
 
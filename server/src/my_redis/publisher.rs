@@ -1,7 +1,7 @@
 use auto_delegate::delegate;
 use redis::AsyncCommands;
 
-use super::{ResultRedis, RedisClient, RedisError};
+use super::{ResultRedis, BroadcastBus, MessageBus, PooledRedis, RedisError, topic_channel};
 
 #[async_trait::async_trait]
 #[delegate]
@@ -10,13 +10,32 @@ pub trait MessagePublisher {
 }
 
 #[async_trait::async_trait]
-impl MessagePublisher for RedisClient {
+impl MessagePublisher for PooledRedis {
     async fn publish_message(&self, user_id: &str, topic: &str, message: &[u8]) -> ResultRedis<()> {
         let mut redis_conn = self.get_conn().await?;
         
-        redis_conn.publish(user_id.to_string() + ":" + topic, message).await
+        redis_conn.publish(topic_channel(user_id, topic), message).await
             .map_err(|_| RedisError::Other)?;
 
         Ok(())
     }
+}
+
+#[async_trait::async_trait]
+impl MessagePublisher for BroadcastBus {
+    async fn publish_message(&self, user_id: &str, topic: &str, message: &[u8]) -> ResultRedis<()> {
+        self.publish(&topic_channel(user_id, topic), message);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl MessagePublisher for MessageBus {
+    async fn publish_message(&self, user_id: &str, topic: &str, message: &[u8]) -> ResultRedis<()> {
+        match self {
+            MessageBus::Redis(bus) => bus.publish_message(user_id, topic, message).await,
+            MessageBus::Broadcast(bus) => bus.publish_message(user_id, topic, message).await,
+        }
+    }
 }
\ No newline at end of file
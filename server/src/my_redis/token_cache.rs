@@ -1,41 +1,86 @@
 use auto_delegate::delegate;
 use redis::AsyncCommands;
-use time::{OffsetDateTime, format_description::well_known::Rfc3339, Duration};
 
-use super::{ResultRedis, RedisClient, RedisError};
+use std::time::Duration;
+
+use super::{ResultRedis, MemoryRedis, PooledRedis, RedisClient, RedisError};
+
+const APP_TOKEN_KEY: &str = "twitch_app_access_token";
+// token is dropped a bit before twitch expires it, so it's not used right at the edge
+const EXPIRY_MARGIN_SECS: i64 = 5 * 60;
 
 #[async_trait::async_trait(?Send)]
 #[delegate]
 pub trait TokenCache {
     async fn try_get_existing_token(&self) -> ResultRedis<Option<String>>;
-    async fn update_token(&self, token: &str) -> ResultRedis<()>;
+    async fn update_token(&self, token: &str, expires_in: i64) -> ResultRedis<()>;
+    /// Removes the token, unless it was already replaced with another one.
+    async fn remove_token(&self, token: &str) -> ResultRedis<()>;
 }
 
 #[async_trait::async_trait(?Send)]
-impl TokenCache for RedisClient {
+impl TokenCache for PooledRedis {
     async fn try_get_existing_token(&self) -> ResultRedis<Option<String>> {
         let mut redis_conn = self.get_conn().await?;
 
-        if let Ok(val) = redis_conn.get::<_, String>("twitch_app_access_token_creation").await {
-            let datetime = OffsetDateTime::parse(&val, &Rfc3339)
-                .expect("To parse datetime of app token fetch time");
-            
-            if OffsetDateTime::now_utc() - datetime < Duration::days(1) {
-                if let Ok(token) = redis_conn.get("twitch_app_access_token").await {
-                    return Ok(Some(token));
-                }
-            }
-        }
-
-        Ok(None)
+        redis_conn.get(APP_TOKEN_KEY).await.map_err(|_| RedisError::Other)
     }
     
-    async fn update_token(&self, token: &str) -> ResultRedis<()> {
+    async fn update_token(&self, token: &str, expires_in: i64) -> ResultRedis<()> {
         let mut redis_conn = self.get_conn().await?;
 
-        redis_conn.set("twitch_app_access_token", token).await.map_err(|_| RedisError::Other)?;
-        redis_conn.set("twitch_app_access_token_creation", OffsetDateTime::now_utc().format(&Rfc3339).unwrap()).await.map_err(|_| RedisError::Other)?;
+        let ttl = (expires_in - EXPIRY_MARGIN_SECS).max(1) as usize;
+        redis_conn.set_ex(APP_TOKEN_KEY, token, ttl).await.map_err(|_| RedisError::Other)?;
 
         Ok(())
     }
+
+    async fn remove_token(&self, token: &str) -> ResultRedis<()> {
+        let mut redis_conn = self.get_conn().await?;
+
+        let existing: Option<String> = redis_conn.get(APP_TOKEN_KEY).await.map_err(|_| RedisError::Other)?;
+
+        if existing.as_deref() == Some(token) {
+            redis_conn.del(APP_TOKEN_KEY).await.map_err(|_| RedisError::Other)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl TokenCache for MemoryRedis {
+    async fn try_get_existing_token(&self) -> ResultRedis<Option<String>> {
+        Ok(self.get(APP_TOKEN_KEY))
+    }
+
+    async fn update_token(&self, token: &str, expires_in: i64) -> ResultRedis<()> {
+        let ttl = (expires_in - EXPIRY_MARGIN_SECS).max(1) as u64;
+        self.set(APP_TOKEN_KEY, token, Some(Duration::from_secs(ttl)));
+
+        Ok(())
+    }
+
+    async fn remove_token(&self, token: &str) -> ResultRedis<()> {
+        if self.get(APP_TOKEN_KEY).as_deref() == Some(token) {
+            self.del(APP_TOKEN_KEY);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl TokenCache for RedisClient {
+    async fn try_get_existing_token(&self) -> ResultRedis<Option<String>> {
+        dispatch!(self, try_get_existing_token())
+    }
+
+    async fn update_token(&self, token: &str, expires_in: i64) -> ResultRedis<()> {
+        dispatch!(self, update_token(token, expires_in))
+    }
+
+    async fn remove_token(&self, token: &str) -> ResultRedis<()> {
+        dispatch!(self, remove_token(token))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,42 @@
+use actix_web::web::Json;
+use paperclip::actix::{Apiv2Schema, api_v2_operation};
+use serde::Serialize;
+
+use crate::domain::subscription::{Subscription, SubscriptionsUsage};
+use crate::errors::{MyErrors, IntoResultMyErr};
+use crate::util::{Context, get_admin_user_ids};
+use crate::util::session_state::TypedSession;
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct SubscriptionUsageInfo {
+    total: i64,
+    total_cost: i64,
+    max_total_cost: i64,
+    saved_count: i64,
+    saved_cost: i64
+}
+
+impl From<SubscriptionsUsage> for SubscriptionUsageInfo {
+    fn from(usage: SubscriptionsUsage) -> Self {
+        Self {
+            total: usage.twitch_cost.total,
+            total_cost: usage.twitch_cost.total_cost,
+            max_total_cost: usage.twitch_cost.max_total_cost,
+            saved_count: usage.saved_count,
+            saved_cost: usage.saved_cost
+        }
+    }
+}
+
+#[api_v2_operation]
+pub async fn subscription_usage(session: TypedSession, ctx: Context) -> Result<Json<SubscriptionUsageInfo>, MyErrors> {
+    let user_id = session.get_user_id()?.ok_or(MyErrors::AccessDenied)?;
+
+    if !get_admin_user_ids().contains(&user_id) {
+        return Err(MyErrors::AccessDenied);
+    }
+
+    let usage = Subscription::get_usage(&ctx).await.into_my()?;
+
+    Ok(Json(usage.into()))
+}
@@ -13,7 +13,6 @@ use crate::REDIRECT_URL;
 use crate::domain::auth_state::AuthState;
 use crate::domain::login_token::LoginToken;
 use crate::util::Context;
-use crate::util::get_twitch_key;
 use crate::util::session_state::TypedSession;
 use crate::websockets::WEBSOCKET_DATA_TYPES;
 
@@ -28,11 +27,9 @@ pub struct LoginUrlResponse {
 }
 
 impl LoginUrlResponse {
-    pub fn new(host: &str, state: &str) -> Self {
-        let twitch_key = get_twitch_key();
-
+    pub fn new(host: &str, state: &str, client_id: String) -> Self {
         Self {
-            client_id: twitch_key,
+            client_id,
             redirect_uri: host.to_string() + REDIRECT_URL,
             response_type: "code".to_string(),
             scope: WEBSOCKET_DATA_TYPES
@@ -56,7 +53,7 @@ pub async fn login_url(request: HttpRequest, query: Query<LoginUrlRequest>, ctx:
     let new_state = AuthState::get_new_state(&ctx.repository, full_uri).await.map_err(e500)?;
     
     let host = request.connection_info().scheme().to_string() + "://" + request.connection_info().host();
-    Ok(Json(LoginUrlResponse::new(&host, &new_state)))
+    Ok(Json(LoginUrlResponse::new(&host, &new_state, ctx.http_client.client_id())))
 }
 
 
@@ -109,15 +106,17 @@ pub async fn twitch_login_end(
 
 #[derive(Serialize, Apiv2Schema)]
 pub struct UserInfo {
-    username: String
+    username: String,
+    // token couldn't be refreshed, the user has to log in again
+    relogin_needed: bool
 }
 #[api_v2_operation]
 pub async fn login_check(session: TypedSession, ctx: Context) -> Result<Json<UserInfo>, MyErrors> {
     match session.get_user_id()? {
         Some(user_id) => {
-            let username = TwitchUser::get_user(&ctx, user_id).await.into_my()?
-                .ok_or(MyErrors::AccessDenied)?.username;
-            Ok(Json(UserInfo { username }))
+            let user = TwitchUser::get_user(&ctx, user_id).await.into_my()?
+                .ok_or(MyErrors::AccessDenied)?;
+            Ok(Json(UserInfo { username: user.username, relogin_needed: user.token_invalid }))
         },
         None => Err(MyErrors::AccessDenied),
     }
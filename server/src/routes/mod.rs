@@ -1,11 +1,15 @@
+mod admin;
 mod login;
+mod rewards;
 mod webhook;
 mod widget_tests;
 
+pub use admin::subscription_usage;
 pub use login::login_url;
 pub use login::login_check;
 pub use login::twitch_login_end;
 pub use login::generate_login_token;
+pub use rewards::custom_rewards;
 pub use webhook::webhook;
 pub use widget_tests::execute_test;
 
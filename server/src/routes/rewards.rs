@@ -0,0 +1,36 @@
+use actix_web::web::Json;
+use paperclip::actix::{Apiv2Schema, api_v2_operation};
+use serde::Serialize;
+
+use crate::domain::rewards::CustomReward;
+use crate::errors::{MyErrors, IntoResultMyErr};
+use crate::util::Context;
+use crate::util::session_state::TypedSession;
+
+#[derive(Serialize, Apiv2Schema)]
+pub struct CustomRewardInfo {
+    id: String,
+    title: String,
+    cost: i64,
+    is_enabled: bool
+}
+
+impl From<CustomReward> for CustomRewardInfo {
+    fn from(reward: CustomReward) -> Self {
+        Self {
+            id: reward.id,
+            title: reward.title,
+            cost: reward.cost,
+            is_enabled: reward.is_enabled
+        }
+    }
+}
+
+#[api_v2_operation]
+pub async fn custom_rewards(session: TypedSession, ctx: Context) -> Result<Json<Vec<CustomRewardInfo>>, MyErrors> {
+    let user_id = session.get_user_id()?.ok_or(MyErrors::AccessDenied)?;
+
+    let rewards = CustomReward::get_rewards(&ctx, user_id).await.into_my()?;
+
+    Ok(Json(rewards.into_iter().map(Into::into).collect()))
+}
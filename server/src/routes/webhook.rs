@@ -5,6 +5,7 @@ use actix_web::HttpResponse;
 use actix_web::web::Bytes;
 use serde::Deserialize;
 use serde_json::Value;
+use time::{OffsetDateTime, Duration, format_description::well_known::Rfc3339};
 use twitch_sources_rework::common_data::eventsub_msgs::EventSubMessage;
 
 use crate::domain::subscription::GetSub;
@@ -12,9 +13,15 @@ use crate::domain::subscription::Subscription;
 use crate::errors::IntoResultMyErr;
 use crate::errors::MyErrors;
 use crate::http_client::twitch_client::SubData;
+use crate::my_redis::message_dedup::MessageDeduplicator;
 use crate::util::Context;
 use crate::twitch_api::message_handler::EventMessageHandler;
 
+// Twitch recommends ignoring anything older, so old messages can't be replayed
+const MAX_MESSAGE_AGE: Duration = Duration::minutes(10);
+// Twitch's clock can be a bit ahead of ours, anything further is made up
+const MAX_CLOCK_SKEW: Duration = Duration::minutes(1);
+
 #[derive(Deserialize)]
 #[serde(rename_all="snake_case")]
 pub enum WebhookRequestType {
@@ -47,15 +54,15 @@ pub async fn webhook(request: HttpRequest, body: Bytes, ctx: Context) -> Result<
         .ok_or(MyErrors::InternalServerError("Missing twitch header".to_string()))?
         .as_bytes();
 
+    let msg_id = request
+        .headers()
+        .get("Twitch-Eventsub-Message-Id")
+        .ok_or(MyErrors::InternalServerError("Missing twitch header".to_string()))?
+        .as_bytes();
+
     // this part checks for all unhappy paths and ends the function
     match sub {
         Ok(sub) => {
-            let msg_id = request
-                .headers()
-                .get("Twitch-Eventsub-Message-Id")
-                .ok_or(MyErrors::InternalServerError("Missing twitch header".to_string()))?
-                .as_bytes();
-
             let twitch_signature = request
                 .headers()
                 .get("Twitch-Eventsub-Message-Signature")
@@ -65,6 +72,16 @@ pub async fn webhook(request: HttpRequest, body: Bytes, ctx: Context) -> Result<
             if !sub.verify_msg(&[msg_id, timestamp, &*body].concat(), twitch_signature) {
                 return Err(MyErrors::AccessDenied)
             }
+
+            let msg_time = OffsetDateTime::parse(
+                from_utf8(timestamp).map_err(|_| MyErrors::AccessDenied)?,
+                &Rfc3339
+            ).map_err(|_| MyErrors::AccessDenied)?;
+
+            let age = OffsetDateTime::now_utc() - msg_time;
+            if age > MAX_MESSAGE_AGE || age < -MAX_CLOCK_SKEW {
+                return Err(MyErrors::AccessDenied)
+            }
         },
         Err(GetSub::NotFound) if msg_type == b"revocation" => {
             return Ok(HttpResponse::Accepted().body(""))
@@ -84,13 +101,24 @@ pub async fn webhook(request: HttpRequest, body: Bytes, ctx: Context) -> Result<
             return Ok(HttpResponse::Ok().body(challenge_string));
         },
         WebhookRequestType::Event(event) => {
+            let msg_id = from_utf8(msg_id).map_err(|_| MyErrors::AccessDenied)?;
+
+            // Twitch delivers at least once, redeliveries are acknowledged but not published again
+            if !ctx.mark_message_seen(msg_id).await.into_my()? {
+                return Ok(HttpResponse::Accepted().body(""));
+            }
+
             let message = EventSubMessage::new(
                 &post.subscription.type_,
                 from_utf8(timestamp).expect("This can never be not valid ascii/utf8"),
                 event
             ).into_my()?;
             
-            ctx.handle_message(message).await.into_my()?;
+            if let Err(err) = ctx.handle_message(message).await {
+                // let Twitch's retry go through
+                _ = ctx.forget_message(msg_id).await;
+                return Err(err).into_my();
+            }
             
             return Ok(HttpResponse::Accepted().body(""));
         },
@@ -3,21 +3,24 @@ use actix_web::web::{Query, Json, Data};
 use paperclip::actix::{Apiv2Schema, api_v2_operation};
 use serde::Deserialize;
 use crate::RunningTests;
-use crate::util::HypetrainTestActor;
+use crate::util::{HypetrainTestActor, PollTestActor, GoalsTestActor, AlertsTestActor, RedemptionsTestActor, CharityTestActor, StreamStatusTestActor, AdBreakTestActor};
 use crate::util::{session_state::TypedSession, PredictionsTestActor};
 use crate::errors::MyErrors;
+use crate::util::Context;
 
 #[api_v2_operation]
 pub async fn execute_test(
     session: TypedSession,
     tests_set: Data<RunningTests>,
-    query: Query<TestWrap>
+    query: Query<TestWrap>,
+    ctx: Context
 ) -> Result<Json<()>, MyErrors> {
     match query.test {
         AvaliableTests::Predictions => {
             PredictionsTestActor::new(
                 (*tests_set).clone(),
-                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
             )
             .map_err(|_| MyErrors::AccessDenied)?
             .start();
@@ -25,7 +28,71 @@ pub async fn execute_test(
         AvaliableTests::HypeTrain => {
             HypetrainTestActor::new(
                 (*tests_set).clone(),
-                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
+            )
+            .map_err(|_| MyErrors::AccessDenied)?
+            .start();
+        },
+        AvaliableTests::Poll => {
+            PollTestActor::new(
+                (*tests_set).clone(),
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
+            )
+            .map_err(|_| MyErrors::AccessDenied)?
+            .start();
+        },
+        AvaliableTests::Goals => {
+            GoalsTestActor::new(
+                (*tests_set).clone(),
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
+            )
+            .map_err(|_| MyErrors::AccessDenied)?
+            .start();
+        },
+        AvaliableTests::Alerts => {
+            AlertsTestActor::new(
+                (*tests_set).clone(),
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
+            )
+            .map_err(|_| MyErrors::AccessDenied)?
+            .start();
+        },
+        AvaliableTests::Redemptions => {
+            RedemptionsTestActor::new(
+                (*tests_set).clone(),
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
+            )
+            .map_err(|_| MyErrors::AccessDenied)?
+            .start();
+        },
+        AvaliableTests::Charity => {
+            CharityTestActor::new(
+                (*tests_set).clone(),
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
+            )
+            .map_err(|_| MyErrors::AccessDenied)?
+            .start();
+        },
+        AvaliableTests::StreamStatus => {
+            StreamStatusTestActor::new(
+                (*tests_set).clone(),
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
+            )
+            .map_err(|_| MyErrors::AccessDenied)?
+            .start();
+        },
+        AvaliableTests::AdBreak => {
+            AdBreakTestActor::new(
+                (*tests_set).clone(),
+                session.get_user_id()?.ok_or(MyErrors::AccessDenied)?,
+                ctx.bus.clone()
             )
             .map_err(|_| MyErrors::AccessDenied)?
             .start();
@@ -39,7 +106,14 @@ pub async fn execute_test(
 #[serde(rename_all="snake_case")]
 pub enum AvaliableTests {
     Predictions,
-    HypeTrain
+    HypeTrain,
+    Poll,
+    Goals,
+    Alerts,
+    Redemptions,
+    Charity,
+    StreamStatus,
+    AdBreak
 }
 
 #[derive(Deserialize, Apiv2Schema)]
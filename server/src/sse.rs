@@ -0,0 +1,39 @@
+use actix::Actor;
+use actix_web::HttpRequest;
+use actix_web::HttpResponse;
+use actix_web::http::header;
+
+use crate::domain::users::TwitchUser;
+use crate::errors::MyErrors;
+use crate::util::Context;
+use crate::util::session_state::TypedSession;
+use crate::util::sse_manager::GenericPassthroughSse;
+use crate::websockets::{WebsocketData, prepare_topic};
+
+/// `/sse/sources/<topic>`, the same events as the websocket of the topic for sources that can't use one.
+pub async fn sse_starter(
+    data: &WebsocketData,
+    req: HttpRequest,
+    session: TypedSession,
+    ctx: Context
+) -> Result<HttpResponse, MyErrors> {
+    let user_id = session.get_user_id()?.ok_or(MyErrors::AccessDenied)?;
+
+    let user = TwitchUser::get_user(&ctx, user_id).await?.ok_or(MyErrors::AccessDenied)?;
+    let sub_ids = prepare_topic(data, &user, &ctx).await?;
+
+    // EventSource sends it by itself when it reconnects
+    let last_event_id = req.headers().get("Last-Event-ID").and_then(|id| id.to_str().ok());
+
+    let (source, events) = GenericPassthroughSse::new(user_id, data.topic, sub_ids, last_event_id, &ctx);
+    source.start();
+
+    Ok(
+        HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header((header::CACHE_CONTROL, "no-cache"))
+            // keeps nginx and the like from holding events back
+            .insert_header(("X-Accel-Buffering", "no"))
+            .streaming(events)
+    )
+}
\ No newline at end of file
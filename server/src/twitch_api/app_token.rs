@@ -1,11 +1,17 @@
+use std::sync::Arc;
+
 use anyhow::Result;
+use auto_delegate::delegate;
 use thiserror::Error;
+use tokio::sync::Mutex;
 
 use crate::{http_client::twitch_client::{GetTokenError, TwitchHttpClient}, my_redis::{token_cache::TokenCache, RedisError}};
 
 #[async_trait::async_trait(?Send)]
 pub trait TwitchTokenManager {
     async fn get_app_token(&self) -> Result<String, TwitchTokenError>;
+    /// Drops the token Twitch rejected, so the next [`TwitchTokenManager::get_app_token`] gets a new one.
+    async fn invalidate_app_token(&self, token: &str) -> Result<(), TwitchTokenError>;
 }
 
 #[derive(Debug, Error)]
@@ -16,16 +22,43 @@ pub enum TwitchTokenError {
     RedisFail(#[from] RedisError)
 }
 
+#[delegate]
+pub trait AppTokenGuard {
+    fn refresh_lock(&self) -> &Mutex<()>;
+}
+
+/// Makes concurrent token refreshes wait for the one in progress, instead of each requesting a new token.
+/// Only works within one server process.
+#[derive(Clone, Default)]
+pub struct AppTokenLock(Arc<Mutex<()>>);
+
+impl AppTokenGuard for AppTokenLock {
+    fn refresh_lock(&self) -> &Mutex<()> {
+        &self.0
+    }
+}
+
 #[async_trait::async_trait(?Send)]
-impl<T: TokenCache + TwitchHttpClient> TwitchTokenManager for T {
+impl<T: TokenCache + TwitchHttpClient + AppTokenGuard> TwitchTokenManager for T {
     async fn get_app_token(&self) -> Result<String, TwitchTokenError> {
         if let Some(token) = self.try_get_existing_token().await? {
             return Ok(token);
         }
 
+        let _guard = self.refresh_lock().lock().await;
+
+        // whoever held the lock before might've just refreshed it
+        if let Some(token) = self.try_get_existing_token().await? {
+            return Ok(token);
+        }
+
         let token = self.get_new_token().await?;
-        self.update_token(&token).await?;
+        self.update_token(&token.access_token, token.expires_in.into()).await?;
 
-        Ok(token)
+        Ok(token.access_token)
     }
-}
\ No newline at end of file
+
+    async fn invalidate_app_token(&self, token: &str) -> Result<(), TwitchTokenError> {
+        Ok(self.remove_token(token).await?)
+    }
+}
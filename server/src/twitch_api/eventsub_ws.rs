@@ -0,0 +1,269 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use actix_codec::Framed;
+use auto_delegate::delegate;
+use awc::{BoxedSocket, error::{WsClientError, WsProtocolError}, ws::{Codec, Frame, Message, CloseReason}};
+use futures_util::{SinkExt, StreamExt};
+use thiserror::Error;
+use tokio::sync::{watch, Notify};
+use tokio::time::{sleep as async_sleep, timeout};
+use twitch_sources_rework::common_data::eventsub_msgs::EventSubMessage;
+
+use crate::{domain::subscription::Subscription, my_redis::message_dedup::MessageDeduplicator, util::{Context, get_eventsub_ws_url}};
+
+use super::message_handler::EventMessageHandler;
+
+// Twitch closes the connection if nothing subscribes in 10 seconds after the welcome
+const SESSION_WAIT: Duration = Duration::from_secs(10);
+// on top of keepalive_timeout_seconds, before the connection is considered dead
+const KEEPALIVE_GRACE: Duration = Duration::from_secs(5);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+type EventSubConnection = Framed<BoxedSocket, Codec>;
+
+mod ws_messages {
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    use crate::http_client::twitch_client::SubData;
+
+    #[derive(Deserialize)]
+    pub struct WsMessage {
+        pub metadata: WsMetadata,
+        pub payload: Value,
+    }
+
+    #[derive(Deserialize)]
+    pub struct WsMetadata {
+        pub message_id: String,
+        pub message_type: String,
+        pub message_timestamp: String,
+    }
+
+    #[derive(Deserialize)]
+    pub struct SessionPayload {
+        pub session: SessionData,
+    }
+
+    #[derive(Deserialize)]
+    pub struct SessionData {
+        pub id: String,
+        pub keepalive_timeout_seconds: Option<u64>,
+        pub reconnect_url: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct NotificationPayload {
+        pub subscription: SubData,
+        pub event: Value,
+    }
+
+    #[derive(Deserialize)]
+    pub struct RevocationPayload {
+        pub subscription: SubData,
+    }
+}
+
+use ws_messages::*;
+
+#[derive(Debug, Error)]
+pub enum EventSubSessionError {
+    #[error("EventSub websocket session didn't start in time")]
+    Timeout,
+}
+
+#[async_trait::async_trait(?Send)]
+#[delegate]
+pub trait EventSubSessionManager {
+    /// Gets the id of the current websocket session, connecting first if there's none.
+    async fn get_session_id(&self) -> Result<String, EventSubSessionError>;
+}
+
+#[derive(Clone)]
+pub struct EventSubSession {
+    session_id: Arc<watch::Sender<Option<String>>>,
+    // the connection is only kept while there's something subscribed
+    needed: Arc<Notify>,
+    // counts the sessions that were lost along with their subscriptions
+    lost_sessions: Arc<watch::Sender<u64>>,
+}
+
+impl EventSubSession {
+    pub fn new() -> Self {
+        Self {
+            session_id: Arc::new(watch::channel(None).0),
+            needed: Arc::new(Notify::new()),
+            lost_sessions: Arc::new(watch::channel(0).0),
+        }
+    }
+
+    fn set_session_id(&self, session_id: Option<String>) {
+        self.session_id.send_replace(session_id);
+    }
+
+    /// Resolves once the current session is lost, the subscriptions made on it are gone too.
+    /// Sources have to subscribe again, on the next session.
+    pub fn subscriptions_lost(&self) -> impl Future<Output = ()> + 'static {
+        let mut receiver = self.lost_sessions.subscribe();
+
+        async move {
+            // the sender lives as long as the context, which outlives the sources
+            _ = receiver.changed().await;
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl EventSubSessionManager for EventSubSession {
+    async fn get_session_id(&self) -> Result<String, EventSubSessionError> {
+        let mut receiver = self.session_id.subscribe();
+        self.needed.notify_one();
+
+        timeout(SESSION_WAIT, async move {
+            loop {
+                if let Some(session_id) = receiver.borrow_and_update().clone() {
+                    return session_id;
+                }
+
+                // sender lives in self, can't be dropped while we wait
+                _ = receiver.changed().await;
+            }
+        })
+            .await
+            .map_err(|_| EventSubSessionError::Timeout)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EventSubClientError {
+    #[error("Connecting to EventSub failed: {0}")]
+    ConnectFail(#[from] WsClientError),
+    #[error("Websocket protocol error: {0}")]
+    ProtocolFail(#[from] WsProtocolError),
+    #[error("Message deserialization failed: {0}")]
+    DeserializeFail(#[from] serde_json::Error),
+    #[error("No message in keepalive time")]
+    KeepaliveTimeout,
+    #[error("Connection was closed: {0:?}")]
+    Closed(Option<CloseReason>),
+    #[error("Expected a session_welcome, got {0}")]
+    UnexpectedMessage(String),
+    #[error("session_reconnect had no reconnect_url")]
+    MissingReconnectUrl,
+}
+
+/// Keeps an EventSub websocket connection and feeds its notifications
+/// to [`EventMessageHandler`], same as the webhook does.
+pub async fn run_eventsub_client(ctx: Context) {
+    loop {
+        ctx.eventsub_session.set_session_id(None);
+
+        // websocket subscriptions don't outlive their session, the saved ones are stale now.
+        // webhook ones are left alone, they don't depend on it
+        if let Err(err) = Subscription::remove_websocket_subscriptions(&ctx).await {
+            log::error!("Removing stale subscriptions failed: {}", err);
+        }
+
+        // sources still connected reconnect, which subscribes them again on the next session
+        ctx.eventsub_session.lost_sessions.send_modify(|lost| *lost += 1);
+
+        ctx.eventsub_session.needed.notified().await;
+
+        if let Err(err) = run_session(&ctx).await {
+            log::error!("EventSub websocket session ended: {}", err);
+            async_sleep(RECONNECT_DELAY).await;
+        }
+    }
+}
+
+async fn run_session(ctx: &Context) -> Result<(), EventSubClientError> {
+    let (mut conn, mut session) = connect(&get_eventsub_ws_url()).await?;
+
+    loop {
+        ctx.eventsub_session.set_session_id(Some(session.id.clone()));
+        let keepalive = Duration::from_secs(session.keepalive_timeout_seconds.unwrap_or(10)) + KEEPALIVE_GRACE;
+
+        loop {
+            let msg = next_message(&mut conn, keepalive).await?;
+
+            match msg.metadata.message_type.as_str() {
+                "session_keepalive" => {},
+                "notification" => {
+                    // websocket delivery is at least once too
+                    match ctx.mark_message_seen(&msg.metadata.message_id).await {
+                        Ok(false) => continue,
+                        Ok(true) => {},
+                        Err(err) => log::error!("Checking notification {} for duplicates failed: {}", msg.metadata.message_id, err),
+                    }
+
+                    let payload = serde_json::from_value::<NotificationPayload>(msg.payload)?;
+
+                    let message = match EventSubMessage::new(
+                        &payload.subscription.type_,
+                        &msg.metadata.message_timestamp,
+                        payload.event
+                    ) {
+                        Ok(message) => message,
+                        Err(err) => {
+                            log::error!("Notification {} didn't parse: {}", msg.metadata.message_id, err);
+                            continue;
+                        }
+                    };
+
+                    if let Err(err) = ctx.handle_message(message).await {
+                        log::error!("Handling notification {} failed: {}", msg.metadata.message_id, err);
+                    }
+                },
+                "revocation" => {
+                    let payload = serde_json::from_value::<RevocationPayload>(msg.payload)?;
+
+                    if let Err(err) = Subscription::remove_subscription(&ctx.repository, &payload.subscription.id).await {
+                        log::error!("Removing revoked subscription failed: {}", err);
+                    }
+                },
+                "session_reconnect" => {
+                    let reconnect_url = serde_json::from_value::<SessionPayload>(msg.payload)?
+                        .session
+                        .reconnect_url
+                        .ok_or(EventSubClientError::MissingReconnectUrl)?;
+
+                    // subscriptions carry over, and the old connection is dropped only after the new welcome
+                    (conn, session) = connect(&reconnect_url).await?;
+                    break;
+                },
+                other => log::debug!("Unknown EventSub message type: {}", other),
+            }
+        }
+    }
+}
+
+async fn connect(url: &str) -> Result<(EventSubConnection, SessionData), EventSubClientError> {
+    let (_, mut conn) = awc::Client::new().ws(url).connect().await?;
+
+    // first message on a new connection is always the welcome
+    let msg = next_message(&mut conn, SESSION_WAIT).await?;
+    if msg.metadata.message_type != "session_welcome" {
+        return Err(EventSubClientError::UnexpectedMessage(msg.metadata.message_type));
+    }
+
+    let session = serde_json::from_value::<SessionPayload>(msg.payload)?.session;
+
+    Ok((conn, session))
+}
+
+// skips to the next text frame, answering pings on the way
+async fn next_message(conn: &mut EventSubConnection, wait: Duration) -> Result<WsMessage, EventSubClientError> {
+    loop {
+        let frame = timeout(wait, conn.next())
+            .await
+            .map_err(|_| EventSubClientError::KeepaliveTimeout)?
+            .ok_or(EventSubClientError::Closed(None))??;
+
+        match frame {
+            Frame::Text(text) => return Ok(serde_json::from_slice(&text)?),
+            Frame::Ping(data) => conn.send(Message::Pong(data)).await?,
+            Frame::Close(reason) => return Err(EventSubClientError::Closed(reason)),
+            _ => {},
+        }
+    }
+}
@@ -2,7 +2,9 @@ use anyhow::Result;
 use thiserror::Error;
 use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData};
 
-use crate::{db::TwitchUserDb, domain::users::{TwitchUser, DeleteUserError}, my_redis::{RedisError, publisher::MessagePublisher}, websockets};
+use crate::{db::TwitchUserDb, domain::users::{TwitchUser, DeleteUserError}, my_redis::{RedisError, publisher::MessagePublisher, last_message::LastMessageStore, event_backlog::{EventBacklog, with_seq}}, websockets};
+
+use super::replay::replay_ttl;
 
 
 #[async_trait::async_trait(?Send)]
@@ -11,7 +13,7 @@ pub trait EventMessageHandler {
 }
 
 #[async_trait::async_trait(?Send)]
-impl<T: TwitchUserDb + MessagePublisher> EventMessageHandler for T {
+impl<T: TwitchUserDb + MessagePublisher + LastMessageStore + EventBacklog> EventMessageHandler for T {
     async fn handle_message(&self, msg: EventSubMessage) -> Result<(), HandleMessageError> {
         if let EventSubData::UserAuthorizationRevoke(data) = &msg.data {
             TwitchUser::delete_user(self, data.user_id.parse()?).await?;
@@ -20,9 +22,27 @@ impl<T: TwitchUserDb + MessagePublisher> EventMessageHandler for T {
         // Dispatch to all websockets, that declared this message as it's sub_type
         for websocket_data in websockets::WEBSOCKET_DATA_TYPES {
             if websocket_data.sub_types.contains(&msg.data.sub_type()) {
-                let data = serde_json::ser::to_vec(&msg)?;
+                let data = serde_json::ser::to_string(&msg)?;
+
+                // in the backlog before anyone hears of it, so reconnecting sockets can't miss it
+                let seq = self.append_event(msg.data.get_target(), websocket_data.topic, &data).await
+                    .map_err(HandleMessageError::StoreFail)?;
     
-                self.publish_message(msg.data.get_target(), websocket_data.topic, &data).await?;
+                self.publish_message(msg.data.get_target(), websocket_data.topic, with_seq(seq, &data).as_bytes()).await?;
+
+                // kept so overlays that connect later can show what's going on right away
+                match replay_ttl(&msg) {
+                    Some(ttl) if ttl.is_positive() => {
+                        self.save_last_message(msg.data.get_target(), websocket_data.topic, &data, ttl.unsigned_abs()).await
+                            .map_err(HandleMessageError::StoreFail)?;
+                    },
+                    // already over, so whatever came before it is outdated too
+                    Some(_) => {
+                        self.remove_last_message(msg.data.get_target(), websocket_data.topic).await
+                            .map_err(HandleMessageError::StoreFail)?;
+                    },
+                    None => (),
+                }
             }
         }
     
@@ -40,4 +60,97 @@ pub enum HandleMessageError {
     MessageSerializationFail(#[from] serde_json::Error),
     #[error("Publishing message failed: {0}")]
     PublishFail(#[from] RedisError),
+    #[error("Storing last message failed: {0}")]
+    StoreFail(RedisError),
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, SubType};
+
+    use super::EventMessageHandler;
+    use crate::db::{NewTwitchUser, TwitchUserDb};
+    use crate::my_redis::{last_message::LastMessageStore, event_backlog::split_seq};
+    use crate::util::Context;
+
+    fn new_user(id: i64) -> NewTwitchUser {
+        NewTwitchUser {
+            id,
+            username: "cool_user".to_string(),
+            access_token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            expires_in: 3600,
+            scopes: Vec::new(),
+            broadcaster_type: String::new(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn publishes_to_topic_of_the_event() {
+        let ctx = Context::in_memory();
+        let mut stream_status = ctx.bus.subscribe(1337, "stream_status").await.expect("Subscribe to work");
+
+        let msg = EventSubMessage::new(&SubType::StreamOnline, "2020-10-11T10:11:12.123Z", serde_json::json!({
+            "id": "9001",
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User",
+            "type": "live",
+            "started_at": "2020-10-11T10:11:12.123Z"
+        })).expect("Event to parse");
+
+        ctx.handle_message(msg).await.expect("Message to be handled");
+
+        let message = stream_status.next().await.expect("Message to arrive").expect("Message to be read");
+        let (seq, payload) = split_seq(&message);
+        assert_eq!(seq, Some(1));
+
+        let published: EventSubMessage = serde_json::from_str(payload).expect("Message to deserialize");
+        assert_eq!(published.data.get_target(), "1337");
+    }
+
+    #[actix_web::test]
+    async fn keeps_last_message_until_it_is_outdated() {
+        let ctx = Context::in_memory();
+
+        let poll = |ends_at: &str| EventSubMessage::new(&SubType::ChannelPollProgress, "2020-10-11T10:11:12.123Z", serde_json::json!({
+            "id": "1243456",
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User",
+            "title": "Aren't shoes just really hard socks?",
+            "choices": [],
+            "bits_voting": { "is_enabled": false, "amount_per_vote": 0 },
+            "channel_points_voting": { "is_enabled": false, "amount_per_vote": 0 },
+            "started_at": "2020-07-15T17:16:03.17106713Z",
+            "ends_at": ends_at
+        })).expect("Event to parse");
+
+        ctx.handle_message(poll("2100-07-15T17:16:08.17106713Z")).await.expect("Message to be handled");
+        let stored = ctx.get_last_message("1337", "poll").await.expect("Last message to be read");
+        assert!(stored.is_some());
+
+        // the poll is over, overlays shouldn't get it anymore
+        ctx.handle_message(poll("2020-07-15T17:16:08.17106713Z")).await.expect("Message to be handled");
+        let stored = ctx.get_last_message("1337", "poll").await.expect("Last message to be read");
+        assert!(stored.is_none());
+    }
+
+    #[actix_web::test]
+    async fn authorization_revoke_deletes_user() {
+        let ctx = Context::in_memory();
+        ctx.insert_user(new_user(1337)).await.expect("User to be inserted");
+
+        let msg = EventSubMessage::new(&SubType::UserAuthorizationRevoke, "2020-10-11T10:11:12.123Z", serde_json::json!({
+            "client_id": "crq72vsaoijkc83xx42hz6i37",
+            "user_id": "1337",
+            "user_login": "cool_user",
+            "user_name": "Cool_User"
+        })).expect("Event to parse");
+
+        ctx.handle_message(msg).await.expect("Message to be handled");
+
+        assert!(ctx.get_user(1337).await.expect("User to be read").is_none());
+    }
 }
\ No newline at end of file
@@ -1,3 +1,7 @@
 pub mod app_token;
 pub mod subscribe;
-pub mod message_handler;
\ No newline at end of file
+pub mod message_handler;
+pub mod replay;
+pub mod eventsub_ws;
+pub mod reconciler;
+pub mod user_tokens;
\ No newline at end of file
@@ -0,0 +1,19 @@
+use tokio::time::sleep as async_sleep;
+
+use crate::{domain::subscription::Subscription, util::{Context, get_subscription_idle_window, get_subscription_reconcile_interval}};
+
+/// Periodically reconciles saved subscriptions with the ones Twitch has,
+/// keeps the EventSub cost down by dropping the idle ones.
+pub async fn run_reconciler(ctx: Context) {
+    let idle_window = get_subscription_idle_window();
+    let interval = get_subscription_reconcile_interval();
+
+    loop {
+        match Subscription::reconcile_subscriptions(&ctx, idle_window).await {
+            Ok(report) => log::info!("Subscriptions reconciled: {:?}", report),
+            Err(err) => log::error!("Reconciling subscriptions failed: {}", err),
+        }
+
+        async_sleep(interval).await;
+    }
+}
@@ -0,0 +1,59 @@
+use time::{OffsetDateTime, Duration, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData};
+
+// how long overlays that connect late still get to show how something ended
+const RESULT_TTL: Duration = Duration::minutes(1);
+// a locked prediction waits for the streamer to pick the outcome, there's no deadline for that
+const LOCKED_TTL: Duration = Duration::hours(1);
+// goals, charity campaigns and stream status last until twitch says otherwise
+const ONGOING_TTL: Duration = Duration::days(1);
+
+// time left until the timestamp, anything we can't parse counts as already passed
+fn until(timestamp: &str) -> Duration {
+    match OffsetDateTime::parse(timestamp, &Rfc3339) {
+        Ok(timestamp) => timestamp - OffsetDateTime::now_utc(),
+        Err(_) => Duration::ZERO,
+    }
+}
+
+/// How long the message is worth replaying to newly connected overlays.
+/// `None` for events that only matter as they happen, like alerts, those aren't stored at all.
+/// Zero or less means whatever was stored for the topic is outdated now.
+pub fn replay_ttl(msg: &EventSubMessage) -> Option<Duration> {
+    let ttl = match &msg.data {
+        EventSubData::ChannelPredictionBegin(data) => until(&data.locks_at),
+        EventSubData::ChannelPredictionProgress(data) => until(&data.locks_at),
+        EventSubData::ChannelPredictionLock(_) => LOCKED_TTL,
+        EventSubData::ChannelPredictionEnd(_) => RESULT_TTL,
+        EventSubData::HypeTrainBegin(data) => until(&data.expires_at),
+        EventSubData::HypeTrainProgress(data) => until(&data.expires_at),
+        // overlays show the cooldown until the next train
+        EventSubData::HypeTrainEnd(data) => until(&data.cooldown_ends_at),
+        EventSubData::ChannelPollBegin(data) => until(&data.ends_at),
+        EventSubData::ChannelPollProgress(data) => until(&data.ends_at),
+        EventSubData::ChannelPollEnd(_) => RESULT_TTL,
+        EventSubData::ChannelGoalBegin(_) => ONGOING_TTL,
+        EventSubData::ChannelGoalProgress(_) => ONGOING_TTL,
+        EventSubData::ChannelGoalEnd(_) => RESULT_TTL,
+        EventSubData::CharityCampaignStart(_) => ONGOING_TTL,
+        EventSubData::CharityCampaignProgress(_) => ONGOING_TTL,
+        EventSubData::CharityCampaignStop(_) => RESULT_TTL,
+        EventSubData::StreamOnline(_) => ONGOING_TTL,
+        EventSubData::StreamOffline(_) => ONGOING_TTL,
+        EventSubData::ChannelAdBreakBegin(data) => until(&data.started_at) + Duration::seconds(data.duration_seconds),
+        // a title change would replace whether the stream is live, which is what overlays care about
+        EventSubData::ChannelUpdate(_)
+        | EventSubData::UserAuthorizationRevoke(_)
+        | EventSubData::ChannelFollow(_)
+        | EventSubData::ChannelSubscribe(_)
+        | EventSubData::ChannelSubscriptionMessage(_)
+        | EventSubData::ChannelSubscriptionGift(_)
+        | EventSubData::ChannelCheer(_)
+        | EventSubData::ChannelRaid(_)
+        | EventSubData::ChannelPointsCustomRewardRedemptionAdd(_)
+        | EventSubData::ChannelPointsCustomRewardRedemptionUpdate(_)
+        | EventSubData::CharityDonation(_) => return None,
+    };
+
+    Some(ttl)
+}
\ No newline at end of file
@@ -1,12 +1,20 @@
+use std::future::Future;
+
 use rand::Rng;
 use thiserror::Error;
 use twitch_sources_rework::common_data::eventsub_msgs::SubType;
 
 #[cfg(not(debug_assertions))]
 use crate::PROD_BASE_URL;
-use crate::http_client::twitch_client::{SubCondition, SubData, SubscribeRequestError, TwitchHttpClient};
+use crate::db::{TwitchUserDb, DbError};
+use crate::domain::users::{MaintainTokenError, TwitchUser};
+use crate::my_redis::cost_cache::SubscriptionCostCache;
+use crate::http_client::twitch_client::{SubCondition, SubData, SubTransport, SubscribeRequestError, ListSubscriptionsError, DeleteSubscriptionError, TwitchHttpClient};
+use crate::WEBHOOK_URL;
+use crate::util::use_websocket_transport;
 
 use super::app_token::{TwitchTokenError, TwitchTokenManager};
+use super::eventsub_ws::{EventSubSessionError, EventSubSessionManager};
 
 
 #[cfg(debug_assertions)]
@@ -45,29 +53,154 @@ pub enum TwitchSubscriptionError {
     #[error("Token fetching/request failed: {0}")]
     TokenError(#[from] TwitchTokenError),
     #[error("Subscription request failed: {0}")]
-    RequestError(#[from] SubscribeRequestError)
+    RequestError(#[from] SubscribeRequestError),
+    #[error("Listing subscriptions failed: {0}")]
+    ListError(#[from] ListSubscriptionsError),
+    #[error("Deleting subscription failed: {0}")]
+    DeleteError(#[from] DeleteSubscriptionError),
+    #[error("EventSub websocket session failed: {0}")]
+    SessionError(#[from] EventSubSessionError),
+    #[error("Websocket transport only supports user conditions")]
+    UserConditionRequired,
+    #[error("Getting user data from DB failed: {0}")]
+    UserGetError(DbError),
+    #[error("User to subscribe for does not exist")]
+    UserNotFound,
+    #[error("Getting a fresh user token failed: {0}")]
+    UserTokenError(#[from] MaintainTokenError),
 }
 
 #[async_trait::async_trait(?Send)]
 pub trait TwitchSubscriptionManager {
     async fn subscribe(&self, sub_cond: SubCondition, sub_type: SubType) -> Result<SubData, TwitchSubscriptionError>;
+    /// All subscriptions of the app on Twitch's side, webhook ones only
+    async fn get_twitch_subscriptions(&self) -> Result<Vec<SubData>, TwitchSubscriptionError>;
+    async fn unsubscribe(&self, sub_id: &str) -> Result<(), TwitchSubscriptionError>;
 }
 
 #[async_trait::async_trait(?Send)]
-impl<T: TwitchTokenManager + TwitchHttpClient> TwitchSubscriptionManager for T {
+impl<T: TwitchTokenManager + TwitchHttpClient + TwitchUserDb + EventSubSessionManager + SubscriptionCostCache> TwitchSubscriptionManager for T {
     async fn subscribe(&self, sub_cond: SubCondition, sub_type: SubType) -> Result<SubData, TwitchSubscriptionError> {
-        #[cfg(debug_assertions)]
-        let callback_url = ngrok::get_https_link().await + "/webhook/";
-        #[cfg(not(debug_assertions))]
-        let callback_url = PROD_BASE_URL.to_string() + "/webhook/";
+        if use_websocket_transport() {
+            return subscribe_websocket(self, sub_cond, sub_type).await;
+        }
+
+        let callback_url = match self.webhook_base_url() {
+            Some(base_url) => base_url + WEBHOOK_URL,
+            #[cfg(debug_assertions)]
+            None => ngrok::get_https_link().await + WEBHOOK_URL,
+            #[cfg(not(debug_assertions))]
+            None => PROD_BASE_URL.to_string() + WEBHOOK_URL,
+        };
 
         let mut rng = rand::thread_rng();
         let secret: String = (0..SECRET_LENGTH).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect();
 
-        let token = self.get_app_token().await?;
-        let mut sub_data = self.create_subscription(sub_cond, sub_type, &callback_url, &secret, &token).await?;
+        let transport = SubTransport::webhook(&callback_url, &secret);
+        let (mut sub_data, cost) = with_app_token(self, |token| {
+            let (sub_cond, sub_type, transport) = (sub_cond.clone(), sub_type.clone(), transport.clone());
+            async move { self.create_subscription(sub_cond, sub_type, transport, &token).await }
+        }).await?;
         sub_data.transport.secret = Some(secret);
 
+        // the subscription exists on twitch's side by now, failing here would leave it orphaned
+        if let Err(err) = self.update_subscription_cost(None, &cost).await {
+            log::warn!("Caching subscription cost failed: {}", err);
+        }
+
         Ok(sub_data)
     }
+
+    async fn get_twitch_subscriptions(&self) -> Result<Vec<SubData>, TwitchSubscriptionError> {
+        let (subs, cost) = with_app_token(self, |token| async move {
+            self.list_subscriptions(&token).await
+        }).await?;
+
+        if let Err(err) = self.update_subscription_cost(None, &cost).await {
+            log::warn!("Caching subscription cost failed: {}", err);
+        }
+
+        Ok(subs)
+    }
+
+    async fn unsubscribe(&self, sub_id: &str) -> Result<(), TwitchSubscriptionError> {
+        with_app_token(self, |token| async move {
+            self.delete_subscription(sub_id, &token).await
+        }).await?;
+
+        // deletes don't come with totals, the cached ones are too high now
+        if let Err(err) = self.clear_subscription_cost(None).await {
+            log::warn!("Clearing subscription cost failed: {}", err);
+        }
+
+        Ok(())
+    }
+}
+
+trait RejectedToken {
+    fn is_unauthorized(&self) -> bool;
+}
+
+impl RejectedToken for SubscribeRequestError {
+    fn is_unauthorized(&self) -> bool {
+        matches!(self, SubscribeRequestError::Unauthorized)
+    }
+}
+
+impl RejectedToken for ListSubscriptionsError {
+    fn is_unauthorized(&self) -> bool {
+        matches!(self, ListSubscriptionsError::Unauthorized)
+    }
+}
+
+impl RejectedToken for DeleteSubscriptionError {
+    fn is_unauthorized(&self) -> bool {
+        matches!(self, DeleteSubscriptionError::Unauthorized)
+    }
+}
+
+// twitch can invalidate the app token before it expires, so a rejected one is dropped and the request retried once
+async fn with_app_token<Ctx, R, E, F, Fut>(ctx: &Ctx, request: F) -> Result<R, TwitchSubscriptionError>
+where
+    Ctx: TwitchTokenManager,
+    E: RejectedToken + Into<TwitchSubscriptionError>,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<R, E>>,
+{
+    let token = ctx.get_app_token().await?;
+
+    match request(token.clone()).await {
+        Err(err) if err.is_unauthorized() => {
+            ctx.invalidate_app_token(&token).await?;
+            let token = ctx.get_app_token().await?;
+
+            request(token).await.map_err(Into::into)
+        },
+        res => res.map_err(Into::into),
+    }
+}
+
+// websocket subscriptions need a token of the user from the condition, app tokens are not accepted
+async fn subscribe_websocket<Ctx: TwitchHttpClient + TwitchUserDb + EventSubSessionManager + SubscriptionCostCache>(
+    ctx: &Ctx,
+    sub_cond: SubCondition,
+    sub_type: SubType
+) -> Result<SubData, TwitchSubscriptionError> {
+    let user_id = sub_cond.clone().into_user_id().ok_or(TwitchSubscriptionError::UserConditionRequired)?;
+    let user = ctx.get_user(user_id).await
+        .map_err(TwitchSubscriptionError::UserGetError)?
+        .ok_or(TwitchSubscriptionError::UserNotFound)?;
+
+    let access_token = TwitchUser::fresh_access_token(ctx, user).await?;
+
+    let session_id = ctx.get_session_id().await?;
+
+    let (sub_data, cost) = ctx.create_subscription(sub_cond, sub_type, SubTransport::websocket(&session_id), &access_token).await?;
+
+    // the subscription exists on twitch's side by now, failing here would leave it orphaned
+    if let Err(err) = ctx.update_subscription_cost(Some(user_id), &cost).await {
+        log::warn!("Caching subscription cost of user {} failed: {}", user_id, err);
+    }
+
+    Ok(sub_data)
 }
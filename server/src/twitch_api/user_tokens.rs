@@ -0,0 +1,21 @@
+use tokio::time::sleep as async_sleep;
+
+use crate::{domain::users::TwitchUser, util::{Context, get_subscription_idle_window}};
+
+// Twitch wants tokens validated every hour
+const MAINTENANCE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+// a bit over the interval, so no token expires between the runs
+const REFRESH_MARGIN: time::Duration = time::Duration::minutes(75);
+
+/// Hourly validates tokens of active users and refreshes the ones close to expiry.
+pub async fn run_token_maintainer(ctx: Context) {
+    loop {
+        // users without overlays for that long have no subscriptions left to use their tokens
+        match TwitchUser::maintain_tokens(&ctx, REFRESH_MARGIN, get_subscription_idle_window()).await {
+            Ok(report) => log::info!("User tokens maintained: {:?}", report),
+            Err(err) => log::error!("User token maintenance failed: {}", err),
+        }
+
+        async_sleep(MAINTENANCE_INTERVAL).await;
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use actix::{Actor, Context, WrapFuture, AsyncContext};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, ChannelAdBreakBegin};
+use tokio::time::sleep as async_sleep;
+
+use crate::{RunningTests, my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
+
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
+    let msg = EventSubMessage {
+        data: EventSubData::ChannelAdBreakBegin(ChannelAdBreakBegin {
+            duration_seconds: 30,
+            started_at: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+            is_automatic: false,
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_user".to_string(),
+            broadcaster_user_name: "Cool_User".to_string(),
+            requester_user_id: user_id.to_string(),
+            requester_user_login: "cool_user".to_string(),
+            requester_user_name: "Cool_User".to_string()
+        }),
+        msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+    };
+    let data = serde_json::ser::to_vec(&msg).expect("No way we fail serialization");
+    conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[8].topic, &data).await?;
+
+    // keeps the test "running" until the ad break is over
+    async_sleep(std::time::Duration::from_secs(30)).await;
+
+    Ok(())
+}
+
+pub struct AdBreakTestActor {
+    running_tests: Arc<RunningTests>,
+    key: String,
+    user_id: i64,
+    bus: MessageBus
+}
+
+impl AdBreakTestActor {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
+        let key = user_id.to_string() + ":tests:ad_break";
+        if running_tests.contains(&key) {
+            return Err(anyhow!("Test is still running"));
+        }
+
+        running_tests.insert(key.clone());
+        Ok(Self { running_tests, key, user_id, bus })
+    }
+}
+
+impl Actor for AdBreakTestActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let user_id = self.user_id;
+        let bus = self.bus.clone();
+        let key = self.key.clone();
+        let running_tests = self.running_tests.clone();
+
+        let fut = Box::pin(
+
+            async move {
+                // silently ignore the error
+                _ = execute_test(bus, &user_id.to_string()).await;
+                running_tests.remove(&key);
+            }
+
+        );
+
+        ctx.spawn(fut.into_actor(self));
+    }
+}
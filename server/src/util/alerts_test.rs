@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use actix::{Actor, Context, WrapFuture, AsyncContext};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, ChannelFollow, ChannelSubscribe, ChannelSubscriptionMessage, SubscriptionMessage, ChannelSubscriptionGift, ChannelCheer, ChannelRaid};
+use tokio::time::sleep as async_sleep;
+
+use crate::{RunningTests, my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
+
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
+    let messages = [
+        EventSubData::ChannelFollow(ChannelFollow {
+            user_id: "1234".to_string(),
+            user_login: "cool_user".to_string(),
+            user_name: "Cool_User".to_string(),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_streamer".to_string(),
+            broadcaster_user_name: "Cool_Streamer".to_string(),
+            followed_at: OffsetDateTime::now_utc().format(&Rfc3339).unwrap()
+        }),
+        EventSubData::ChannelSubscribe(ChannelSubscribe {
+            user_id: "12345".to_string(),
+            user_login: "cooler_user".to_string(),
+            user_name: "Cooler_User".to_string(),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_streamer".to_string(),
+            broadcaster_user_name: "Cool_Streamer".to_string(),
+            tier: "1000".to_string(),
+            is_gift: false
+        }),
+        EventSubData::ChannelSubscriptionMessage(ChannelSubscriptionMessage {
+            user_id: "123456".to_string(),
+            user_login: "coolest_user".to_string(),
+            user_name: "Coolest_User".to_string(),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_streamer".to_string(),
+            broadcaster_user_name: "Cool_Streamer".to_string(),
+            tier: "2000".to_string(),
+            message: SubscriptionMessage {
+                text: "Love the stream!".to_string(),
+                emotes: None
+            },
+            cumulative_months: 15,
+            streak_months: Some(3),
+            duration_months: 1
+        }),
+        EventSubData::ChannelSubscriptionGift(ChannelSubscriptionGift {
+            user_id: None,
+            user_login: None,
+            user_name: None,
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_streamer".to_string(),
+            broadcaster_user_name: "Cool_Streamer".to_string(),
+            total: 5,
+            tier: "1000".to_string(),
+            cumulative_total: None,
+            is_anonymous: true
+        }),
+        EventSubData::ChannelCheer(ChannelCheer {
+            is_anonymous: false,
+            user_id: Some("1234567".to_string()),
+            user_login: Some("coolio_user".to_string()),
+            user_name: Some("Coolio_User".to_string()),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_streamer".to_string(),
+            broadcaster_user_name: "Cool_Streamer".to_string(),
+            message: "Cheer500 good luck!".to_string(),
+            bits: 500
+        }),
+        EventSubData::ChannelRaid(ChannelRaid {
+            from_broadcaster_user_id: "7654321".to_string(),
+            from_broadcaster_user_login: "other_streamer".to_string(),
+            from_broadcaster_user_name: "Other_Streamer".to_string(),
+            to_broadcaster_user_id: user_id.to_string(),
+            to_broadcaster_user_login: "cool_streamer".to_string(),
+            to_broadcaster_user_name: "Cool_Streamer".to_string(),
+            viewers: 42
+        }),
+    ];
+
+    // the source queues them up, so there's no need to wait for each one to finish
+    for data in messages {
+        let msg = EventSubMessage {
+            data,
+            msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        };
+        let data = serde_json::ser::to_vec(&msg).expect("No way we fail serialization");
+        conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[4].topic, &data).await?;
+        async_sleep(std::time::Duration::from_secs(1)).await;
+    }
+
+    Ok(())
+}
+
+pub struct AlertsTestActor {
+    running_tests: Arc<RunningTests>,
+    key: String,
+    user_id: i64,
+    bus: MessageBus
+}
+
+impl AlertsTestActor {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
+        let key = user_id.to_string() + ":tests:alerts";
+        if running_tests.contains(&key) {
+            return Err(anyhow!("Test is still running"));
+        }
+
+        running_tests.insert(key.clone());
+        Ok(Self { running_tests, key, user_id, bus })
+    }
+}
+
+impl Actor for AlertsTestActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let user_id = self.user_id;
+        let bus = self.bus.clone();
+        let key = self.key.clone();
+        let running_tests = self.running_tests.clone();
+
+        let fut = Box::pin(
+
+            async move {
+                // silently ignore the error
+                _ = execute_test(bus, &user_id.to_string()).await;
+                running_tests.remove(&key);
+            }
+
+        );
+
+        ctx.spawn(fut.into_actor(self));
+    }
+}
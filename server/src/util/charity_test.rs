@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use actix::{Actor, Context, WrapFuture, AsyncContext};
+use rand::Rng;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, CharityAmount, CharityCampaignStart, CharityCampaignProgress, CharityCampaignStop, CharityDonation};
+use tokio::time::sleep as async_sleep;
+
+use crate::{RunningTests, my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
+
+const CHARITY_NAME: &str = "Some Cool Charity";
+const TARGET_AMOUNT: i64 = 50_000;
+
+fn usd(value: i64) -> CharityAmount {
+    CharityAmount { value, decimal_places: 2, currency: "USD".to_string() }
+}
+
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
+    let mut rng = rand::thread_rng();
+    // same as with predictions, a random id makes each test run a separate campaign
+    let id: String = (0..10).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect();
+
+    let mut current_amount = 10_000;
+
+    let start_msg = EventSubMessage {
+        data: EventSubData::CharityCampaignStart(CharityCampaignStart {
+            id: id.clone(),
+            broadcaster_id: user_id.to_string(),
+            broadcaster_login: "cool_user".to_string(),
+            broadcaster_name: "Cool_User".to_string(),
+            charity_name: CHARITY_NAME.to_string(),
+            charity_description: "".to_string(),
+            charity_logo: "".to_string(),
+            charity_website: "".to_string(),
+            current_amount: usd(current_amount),
+            target_amount: usd(TARGET_AMOUNT),
+            started_at: OffsetDateTime::now_utc().format(&Rfc3339).unwrap()
+        }),
+        msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+    };
+    let data = serde_json::ser::to_vec(&start_msg).expect("No way we fail serialization");
+    conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[6].topic, &data).await?;
+    async_sleep(std::time::Duration::from_secs(1)).await;
+
+    for (user_name, amount) in [("Cool_User", 500), ("Cooler_User", 2_550), ("Coolest_User", 10_000), ("Coolio_User", 27_000)] {
+        current_amount += amount;
+
+        let donation_msg = EventSubMessage {
+            data: EventSubData::CharityDonation(CharityDonation {
+                id: (0..10).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect(),
+                campaign_id: id.clone(),
+                broadcaster_user_id: user_id.to_string(),
+                broadcaster_user_login: "cool_user".to_string(),
+                broadcaster_user_name: "Cool_User".to_string(),
+                user_id: "1234".to_string(),
+                user_login: user_name.to_lowercase(),
+                user_name: user_name.to_string(),
+                charity_name: CHARITY_NAME.to_string(),
+                charity_description: "".to_string(),
+                charity_logo: "".to_string(),
+                charity_website: "".to_string(),
+                amount: usd(amount)
+            }),
+            msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        };
+        let data = serde_json::ser::to_vec(&donation_msg).expect("No way we fail serialization");
+        conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[6].topic, &data).await?;
+
+        let progress_msg = EventSubMessage {
+            data: EventSubData::CharityCampaignProgress(CharityCampaignProgress {
+                id: id.clone(),
+                broadcaster_id: user_id.to_string(),
+                broadcaster_login: "cool_user".to_string(),
+                broadcaster_name: "Cool_User".to_string(),
+                charity_name: CHARITY_NAME.to_string(),
+                charity_description: "".to_string(),
+                charity_logo: "".to_string(),
+                charity_website: "".to_string(),
+                current_amount: usd(current_amount),
+                target_amount: usd(TARGET_AMOUNT)
+            }),
+            msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        };
+        let data = serde_json::ser::to_vec(&progress_msg).expect("No way we fail serialization");
+        conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[6].topic, &data).await?;
+        async_sleep(std::time::Duration::from_secs(2)).await;
+    }
+
+    let stop_msg = EventSubMessage {
+        data: EventSubData::CharityCampaignStop(CharityCampaignStop {
+            id: id.clone(),
+            broadcaster_id: user_id.to_string(),
+            broadcaster_login: "cool_user".to_string(),
+            broadcaster_name: "Cool_User".to_string(),
+            charity_name: CHARITY_NAME.to_string(),
+            charity_description: "".to_string(),
+            charity_logo: "".to_string(),
+            charity_website: "".to_string(),
+            current_amount: usd(current_amount),
+            target_amount: usd(TARGET_AMOUNT),
+            stopped_at: OffsetDateTime::now_utc().format(&Rfc3339).unwrap()
+        }),
+        msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+    };
+    let data = serde_json::ser::to_vec(&stop_msg).expect("No way we fail serialization");
+    conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[6].topic, &data).await?;
+
+    Ok(())
+}
+
+pub struct CharityTestActor {
+    running_tests: Arc<RunningTests>,
+    key: String,
+    user_id: i64,
+    bus: MessageBus
+}
+
+impl CharityTestActor {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
+        let key = user_id.to_string() + ":tests:charity";
+        if running_tests.contains(&key) {
+            return Err(anyhow!("Test is still running"));
+        }
+
+        running_tests.insert(key.clone());
+        Ok(Self { running_tests, key, user_id, bus })
+    }
+}
+
+impl Actor for CharityTestActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let user_id = self.user_id;
+        let bus = self.bus.clone();
+        let key = self.key.clone();
+        let running_tests = self.running_tests.clone();
+
+        let fut = Box::pin(
+
+            async move {
+                // silently ignore the error
+                _ = execute_test(bus, &user_id.to_string()).await;
+                running_tests.remove(&key);
+            }
+
+        );
+
+        ctx.spawn(fut.into_actor(self));
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubData, EventSubMessage};
+
+use crate::websockets::WEBSOCKET_DATA_TYPES;
+
+use super::pubsub_dispatcher::TopicMessage;
+
+// progress events say where things are at, so a newer one makes the older ones useless
+fn progress_id(data: &EventSubData) -> Option<&str> {
+    match data {
+        EventSubData::ChannelPredictionProgress(data) => Some(&data.id),
+        EventSubData::HypeTrainProgress(data) => Some(&data.data.id),
+        EventSubData::ChannelPollProgress(data) => Some(&data.id),
+        EventSubData::ChannelGoalProgress(data) => Some(&data.id),
+        EventSubData::CharityCampaignProgress(data) => Some(&data.id),
+        _ => None,
+    }
+}
+
+/// Holds back progress events of a socket for their topic's `coalesce_window`,
+/// only the newest one of each event is sent when it's over. Everything else
+/// goes out right away, after whatever was held, so the order stays the same.
+pub struct Coalescer {
+    windows: HashMap<String, Duration>,
+    // (topic, event id) and the newest progress of it, in the order they first came in
+    held: Vec<((String, String), TopicMessage)>,
+}
+
+impl Coalescer {
+    pub fn new(topics: &[String]) -> Self {
+        let windows = WEBSOCKET_DATA_TYPES.iter()
+            .filter(|data| topics.iter().any(|topic| topic == data.topic))
+            .map(|data| (data.topic.to_string(), data.coalesce_window))
+            .collect();
+
+        Self { windows, held: Vec::new() }
+    }
+
+    /// What can be sent now, and how long until [`Coalescer::take_held`] should be called
+    /// if the message started a new window.
+    pub fn push(&mut self, msg: TopicMessage) -> (Vec<TopicMessage>, Option<Duration>) {
+        let window = self.windows.get(&msg.topic).copied().unwrap_or(Duration::ZERO);
+
+        let id = match serde_json::from_str::<EventSubMessage>(&msg.payload) {
+            Ok(event) if !window.is_zero() => progress_id(&event.data).map(str::to_string),
+            _ => None,
+        };
+
+        let key = match id {
+            Some(id) => (msg.topic.clone(), id),
+            None => {
+                let mut ready = self.take_held();
+                ready.push(msg);
+
+                return (ready, None);
+            },
+        };
+
+        let starts_window = self.held.is_empty();
+
+        match self.held.iter_mut().find(|(held_key, _)| *held_key == key) {
+            Some((_, held)) => *held = msg,
+            None => self.held.push((key, msg)),
+        }
+
+        (Vec::new(), starts_window.then_some(window))
+    }
+
+    /// Whether a window is running, the held messages are sent along with the next one that can't wait.
+    pub fn is_holding(&self) -> bool {
+        !self.held.is_empty()
+    }
+
+    pub fn take_held(&mut self) -> Vec<TopicMessage> {
+        self.held.drain(..).map(|(_, msg)| msg).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, SubType};
+
+    use super::Coalescer;
+    use crate::util::pubsub_dispatcher::TopicMessage;
+
+    fn poll_message(event: EventSubMessage, seq: u64) -> TopicMessage {
+        TopicMessage {
+            topic: "poll".to_string(),
+            seq: Some(seq),
+            payload: serde_json::to_string(&event).expect("Event to serialize"),
+        }
+    }
+
+    fn poll_begin(id: &str, seq: u64) -> TopicMessage {
+        let event = EventSubMessage::new(&SubType::ChannelPollBegin, "2020-10-11T10:11:12.123Z", json!({
+            "id": id,
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User",
+            "title": "Aren't shoes just really hard socks?",
+            "choices": [],
+            "bits_voting": { "is_enabled": false, "amount_per_vote": 0 },
+            "channel_points_voting": { "is_enabled": false, "amount_per_vote": 0 },
+            "started_at": "2020-07-15T17:16:03.17106713Z",
+            "ends_at": "2020-07-15T17:16:08.17106713Z"
+        })).expect("Event to parse");
+
+        poll_message(event, seq)
+    }
+
+    fn poll_progress(id: &str, seq: u64) -> TopicMessage {
+        let event = EventSubMessage::new(&SubType::ChannelPollProgress, "2020-10-11T10:11:12.123Z", json!({
+            "id": id,
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User",
+            "title": "Aren't shoes just really hard socks?",
+            "choices": [
+                { "id": "123", "title": "Yeah!", "bits_votes": 5, "channel_points_votes": 7, "votes": 12 }
+            ],
+            "bits_voting": { "is_enabled": true, "amount_per_vote": 10 },
+            "channel_points_voting": { "is_enabled": true, "amount_per_vote": 10 },
+            "started_at": "2020-07-15T17:16:03.17106713Z",
+            "ends_at": "2020-07-15T17:16:08.17106713Z"
+        })).expect("Event to parse");
+
+        poll_message(event, seq)
+    }
+
+    fn poll_end(id: &str, seq: u64) -> TopicMessage {
+        let event = EventSubMessage::new(&SubType::ChannelPollEnd, "2020-10-11T10:11:12.123Z", json!({
+            "id": id,
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User",
+            "title": "Aren't shoes just really hard socks?",
+            "choices": [
+                { "id": "123", "title": "Yeah!", "bits_votes": 50, "channel_points_votes": 70, "votes": 120 }
+            ],
+            "bits_voting": { "is_enabled": true, "amount_per_vote": 10 },
+            "channel_points_voting": { "is_enabled": true, "amount_per_vote": 10 },
+            "status": "completed",
+            "started_at": "2020-07-15T17:16:03.17106713Z",
+            "ended_at": "2020-07-15T17:16:08.17106713Z"
+        })).expect("Event to parse");
+
+        poll_message(event, seq)
+    }
+
+    fn seqs(messages: &[TopicMessage]) -> Vec<u64> {
+        messages.iter().filter_map(|msg| msg.seq).collect()
+    }
+
+    #[test]
+    fn keeps_newest_progress_of_each_event() {
+        let mut coalescer = Coalescer::new(&["poll".to_string()]);
+
+        let (ready, window) = coalescer.push(poll_begin("1", 1));
+        assert_eq!(seqs(&ready), [1]);
+        assert_eq!(window, None);
+
+        let (ready, window) = coalescer.push(poll_progress("1", 2));
+        assert!(ready.is_empty());
+        assert_eq!(window, Some(Duration::from_millis(500)));
+
+        // the window is already running
+        let (_, window) = coalescer.push(poll_progress("1", 3));
+        assert_eq!(window, None);
+
+        coalescer.push(poll_progress("2", 4));
+        coalescer.push(poll_progress("1", 5));
+        assert!(coalescer.is_holding());
+        assert_eq!(seqs(&coalescer.take_held()), [5, 4]);
+        assert!(!coalescer.is_holding());
+
+        // the end never waits, but the progress before it goes first
+        coalescer.push(poll_progress("1", 6));
+        let (ready, _) = coalescer.push(poll_end("1", 7));
+        assert_eq!(seqs(&ready), [6, 7]);
+        assert!(!coalescer.is_holding());
+    }
+
+    #[test]
+    fn sends_everything_without_a_window() {
+        let mut coalescer = Coalescer::new(&[]);
+
+        let (ready, window) = coalescer.push(poll_progress("1", 1));
+        assert_eq!(seqs(&ready), [1]);
+        assert_eq!(window, None);
+    }
+}
\ No newline at end of file
@@ -68,4 +68,75 @@ pub fn get_twitch_key() -> String {
 /// If the environment variable is not set
 pub fn get_twitch_secret() -> String {
     std::env::var("TWITCH_SECRET").expect("TWITCH_SECRET is not set")
+}
+
+/// Gets the Helix API base URL from the environment variable,
+/// or the Twitch one if it's not set (useful for a local mock Twitch API).
+pub fn get_twitch_api_url() -> String {
+    std::env::var("TWITCH_API_URL").unwrap_or_else(|_| "https://api.twitch.tv/helix".to_string())
+}
+
+/// Gets the Twitch auth base URL from the environment variable,
+/// or the Twitch one if it's not set.
+pub fn get_twitch_auth_url() -> String {
+    std::env::var("TWITCH_AUTH_URL").unwrap_or_else(|_| "https://id.twitch.tv".to_string())
+}
+
+/// Gets the public URL webhooks are sent to from `WEBHOOK_BASE_URL`, if it's set.
+/// Otherwise ngrok is used in debug, and the production URL in release.
+pub fn get_webhook_base_url() -> Option<String> {
+    std::env::var("WEBHOOK_BASE_URL").ok()
+}
+
+/// Checks if everything should be kept in memory instead of Postgres and Redis.
+/// Set `STORAGE=memory` to run a single server without them, nothing is kept between restarts.
+pub fn use_in_memory_storage() -> bool {
+    std::env::var("STORAGE").map(|storage| storage == "memory").unwrap_or(false)
+}
+
+/// Checks if events should go to the source websockets through this process instead of Redis pubsub.
+/// Set `MESSAGE_BUS=broadcast` when there's only one server, Redis is needed for several.
+pub fn use_broadcast_message_bus() -> bool {
+    std::env::var("MESSAGE_BUS").map(|bus| bus == "broadcast").unwrap_or(false)
+}
+
+/// Checks if EventSub should use the websocket transport instead of webhooks.
+/// Set `EVENTSUB_TRANSPORT=websocket` to use it.
+pub fn use_websocket_transport() -> bool {
+    std::env::var("EVENTSUB_TRANSPORT").map(|transport| transport == "websocket").unwrap_or(false)
+}
+
+/// Gets the EventSub websocket URL from the environment variable,
+/// or the Twitch one if it's not set (useful for a local fake EventSub server).
+pub fn get_eventsub_ws_url() -> String {
+    std::env::var("EVENTSUB_WS_URL").unwrap_or_else(|_| "wss://eventsub.wss.twitch.tv/ws".to_string())
+}
+
+/// Gets how long a subscription can go without overlays connected before it's unsubscribed,
+/// `SUBSCRIPTION_IDLE_DAYS` or a week by default.
+pub fn get_subscription_idle_window() -> time::Duration {
+    std::env::var("SUBSCRIPTION_IDLE_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .map(time::Duration::days)
+        .unwrap_or(time::Duration::weeks(1))
+}
+
+/// Gets how often subscriptions are reconciled with Twitch,
+/// `SUBSCRIPTION_RECONCILE_MINUTES` or an hour by default.
+pub fn get_subscription_reconcile_interval() -> std::time::Duration {
+    std::env::var("SUBSCRIPTION_RECONCILE_MINUTES")
+        .ok()
+        .and_then(|minutes| minutes.parse::<u64>().ok())
+        .map(|minutes| std::time::Duration::from_secs(minutes * 60))
+        .unwrap_or(std::time::Duration::from_secs(60 * 60))
+}
+
+/// Gets ids of users allowed to see admin endpoints, from comma separated `ADMIN_USER_IDS`.
+pub fn get_admin_user_ids() -> Vec<i64> {
+    std::env::var("ADMIN_USER_IDS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|id| id.trim().parse().ok())
+        .collect()
 }
\ No newline at end of file
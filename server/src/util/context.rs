@@ -1,27 +1,62 @@
 use std::future::{Ready, ready};
 
+use actix::{Actor, Addr};
 use actix_web::FromRequest;
 use auto_delegate::Delegate;
 use paperclip::{actix::OperationModifier, v2::schema::TypedData};
 
-use crate::{errors::MyErrors, db::Repository, http_client::HttpClient, my_redis::RedisClient};
+use crate::{errors::MyErrors, db::{MemoryRepository, Repository}, http_client::HttpClient, my_redis::{BroadcastBus, MemoryRedis, MessageBus, RedisClient}, twitch_api::{eventsub_ws::EventSubSession, app_token::AppTokenLock}};
+
+use super::pubsub_dispatcher::PubsubDispatcher;
 
 #[derive(Clone, Delegate)]
 pub struct Context {
     #[to(AuthStateDb, LoginTokenDb, SubscriptionDb, TwitchUserDb)]
     pub repository: Repository,
-    #[to(TokenCache, MessagePublisher)]
+    #[to(TokenCache, MessageDeduplicator, SubscriptionCostCache, LastMessageStore, EventBacklog, ConnectionHeartbeat)]
     pub redis: RedisClient,
+    #[to(MessagePublisher)]
+    pub bus: MessageBus,
     #[to(TwitchHttpClient)]
     pub http_client: HttpClient,
+    #[to(EventSubSessionManager)]
+    pub eventsub_session: EventSubSession,
+    #[to(AppTokenGuard)]
+    pub app_token_lock: AppTokenLock,
+    pub pubsub: Addr<PubsubDispatcher>,
 }
 
 impl Context {
     pub fn new() -> Self {
+        let redis_pool = super::get_redis_client_pool().expect("Unable to connect to Redis");
+
+        let bus = if super::use_broadcast_message_bus() {
+            BroadcastBus::new().into()
+        } else {
+            redis_pool.clone().into()
+        };
+
+        Self::with_storage(
+            super::create_connection_pool().expect("Unable to create DB pool").into(),
+            redis_pool.into(),
+            bus,
+        )
+    }
+
+    /// Context that keeps everything in memory, for tests and running without Postgres and Redis.
+    pub fn in_memory() -> Self {
+        Self::with_storage(MemoryRepository::new().into(), MemoryRedis::new().into(), BroadcastBus::new().into())
+    }
+
+    pub fn with_storage(repository: Repository, redis: RedisClient, bus: MessageBus) -> Self {
         Self {
-            repository: super::create_connection_pool().expect("Unable to create DB pool").into(),
-            redis: super::get_redis_client_pool().expect("Unable to connect to Redis").into(),
-            http_client: HttpClient(reqwest::Client::new()),
+            repository,
+            redis,
+            pubsub: PubsubDispatcher::new(bus.clone()).start(),
+            bus,
+            http_client: HttpClient::new(reqwest::Client::new()),
+            eventsub_session: EventSubSession::new(),
+            app_token_lock: AppTokenLock::default(),
         }
     }
 }
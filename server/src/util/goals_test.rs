@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use actix::{Actor, Context, WrapFuture, AsyncContext};
+use rand::Rng;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, GoalType, ChannelGoalBegin, ChannelGoalProgress, ChannelGoalEnd};
+use tokio::time::sleep as async_sleep;
+
+use crate::{RunningTests, my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
+
+const TARGET_AMOUNT: i64 = 100;
+
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
+    let description = "Some decently long goal description, just to see it fits".to_string();
+
+    let mut rng = rand::thread_rng();
+    // same as with predictions, a random id makes each test run a separate goal
+    let id: String = (0..10).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect();
+
+    let start_time = OffsetDateTime::now_utc();
+
+    let begin_msg = EventSubMessage {
+        data: EventSubData::ChannelGoalBegin(ChannelGoalBegin {
+            id: id.clone(),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_user".to_string(),
+            broadcaster_user_name: "Cool_User".to_string(),
+            type_: GoalType::Follow,
+            description: description.clone(),
+            current_amount: 40,
+            target_amount: TARGET_AMOUNT,
+            started_at: start_time.format(&Rfc3339).unwrap()
+        }),
+        msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+    };
+    let data = serde_json::ser::to_vec(&begin_msg).expect("No way we fail serialization");
+    conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[3].topic, &data).await?;
+    async_sleep(std::time::Duration::from_secs(1)).await;
+
+    for current_amount in [55, 80, TARGET_AMOUNT] {
+        let progress_msg = EventSubMessage {
+            data: EventSubData::ChannelGoalProgress(ChannelGoalProgress {
+                id: id.clone(),
+                broadcaster_user_id: user_id.to_string(),
+                broadcaster_user_login: "cool_user".to_string(),
+                broadcaster_user_name: "Cool_User".to_string(),
+                type_: GoalType::Follow,
+                description: description.clone(),
+                current_amount,
+                target_amount: TARGET_AMOUNT,
+                started_at: start_time.format(&Rfc3339).unwrap()
+            }),
+            msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        };
+        let data = serde_json::ser::to_vec(&progress_msg).expect("No way we fail serialization");
+        conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[3].topic, &data).await?;
+        async_sleep(std::time::Duration::from_secs(2)).await;
+    }
+
+    let end_msg = EventSubMessage {
+        data: EventSubData::ChannelGoalEnd(ChannelGoalEnd {
+            id: id.clone(),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_user".to_string(),
+            broadcaster_user_name: "Cool_User".to_string(),
+            type_: GoalType::Follow,
+            description: description.clone(),
+            is_achieved: true,
+            current_amount: TARGET_AMOUNT,
+            target_amount: TARGET_AMOUNT,
+            started_at: start_time.format(&Rfc3339).unwrap(),
+            ended_at: OffsetDateTime::now_utc().format(&Rfc3339).unwrap()
+        }),
+        msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+    };
+    let data = serde_json::ser::to_vec(&end_msg).expect("No way we fail serialization");
+    conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[3].topic, &data).await?;
+
+    Ok(())
+}
+
+pub struct GoalsTestActor {
+    running_tests: Arc<RunningTests>,
+    key: String,
+    user_id: i64,
+    bus: MessageBus
+}
+
+impl GoalsTestActor {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
+        let key = user_id.to_string() + ":tests:goals";
+        if running_tests.contains(&key) {
+            return Err(anyhow!("Test is still running"));
+        }
+
+        running_tests.insert(key.clone());
+        Ok(Self { running_tests, key, user_id, bus })
+    }
+}
+
+impl Actor for GoalsTestActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let user_id = self.user_id;
+        let bus = self.bus.clone();
+        let key = self.key.clone();
+        let running_tests = self.running_tests.clone();
+
+        let fut = Box::pin(
+
+            async move {
+                // silently ignore the error
+                _ = execute_test(bus, &user_id.to_string()).await;
+                running_tests.remove(&key);
+            }
+
+        );
+
+        ctx.spawn(fut.into_actor(self));
+    }
+}
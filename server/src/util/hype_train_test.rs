@@ -7,15 +7,11 @@ use time::{OffsetDateTime, Duration, format_description::well_known::Rfc3339};
 use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, HypeTrainBegin, HypeTrainData, Contribution, ContributionType, HypeTrainProgress, HypeTrainEnd};
 use tokio::time::sleep as async_sleep;
 
-use crate::{my_redis::{RedisClient, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES, RunningTests};
-
-use super::get_redis_client_pool;
+use crate::{my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES, RunningTests};
 
 const TEST_TIME_SECONDS: i64 = 9;
 
-async fn execute_test(user_id: &str) -> Result<()> {
-    let conn = RedisClient::new(get_redis_client_pool()?);
-
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
     let mut rng = rand::thread_rng();
     // random id to make different tests count as different events
     let id: String = (0..10).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect();
@@ -228,18 +224,19 @@ async fn execute_test(user_id: &str) -> Result<()> {
 pub struct HypetrainTestActor {
     running_tests: Arc<RunningTests>,
     key: String,
-    user_id: i64
+    user_id: i64,
+    bus: MessageBus
 }
 
 impl HypetrainTestActor {
-    pub fn new(running_tests: Arc<RunningTests>, user_id: i64) -> Result<Self> {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
         let key = user_id.to_string() + ":tests:hype_train";
         if running_tests.contains(&key) {
             return Err(anyhow!("Test is still running"));
         }
 
         running_tests.insert(key.clone());
-        Ok(Self { running_tests, key, user_id })
+        Ok(Self { running_tests, key, user_id, bus })
     }
 }
 
@@ -248,6 +245,7 @@ impl Actor for HypetrainTestActor {
 
     fn started(&mut self, ctx: &mut Self::Context) {
         let user_id = self.user_id;
+        let bus = self.bus.clone();
         let key = self.key.clone();
         let running_tests = self.running_tests.clone();
 
@@ -255,7 +253,7 @@ impl Actor for HypetrainTestActor {
 
             async move {
                 // silently ignore the error
-                _ = execute_test(&user_id.to_string()).await;
+                _ = execute_test(bus, &user_id.to_string()).await;
                 running_tests.remove(&key);
             }
 
@@ -1,37 +1,84 @@
-use actix::AsyncContext;
-use futures::Stream;
-use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use actix::{ActorFutureExt, AsyncContext, SpawnHandle};
 use futures_util::future::join_all;
-use redis::Msg;
 
 use actix::{Actor, StreamHandler, WrapFuture, Message, Handler};
-use futures::StreamExt;
 use actix_web_actors::ws;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::{envelope::{Envelope, EnvelopeMessage, PROTOCOL_VERSION}, eventsub_msgs::EventSubMessage};
 
-use crate::{util::get_redis_connection, db::Repository, domain::subscription::Subscription};
-
-// all the stuff for websocket
-async fn subscribe(user_id: i64, topic: &str) -> Result<impl Stream<Item = Msg>> {
-    let conn = get_redis_connection().await?;
-    let mut pub_sub = conn.into_pubsub();
+use crate::{db::Repository, domain::{subscription::Subscription, live_event::get_live_event}, my_redis::{RedisClient, last_message::LastMessageStore, event_backlog::EventBacklog, connection_heartbeat::{ConnectionHeartbeat, SUBSCRIPTION_BEAT_INTERVAL}}};
 
-    pub_sub.subscribe(user_id.to_string() + ":" + topic).await?;
+use super::{Context, use_websocket_transport};
+use super::coalescer::Coalescer;
+use super::pubsub_dispatcher::{Listen, ListenerId, StopListening, TopicMessage};
 
-    Ok(pub_sub.into_on_message())
-}
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
 
-async fn pre_start_ws(db: Repository, sub_ids: Vec<String>) {
+// all the stuff for websocket, sse sources do the same bookkeeping
+pub(super) async fn pre_start_ws(db: Repository, sub_ids: Vec<String>) {
     join_all(sub_ids.iter().map(|sub_id| Subscription::update_connect_time_by_id(&db, sub_id))).await;
 }
 
-async fn pre_end_ws(db: Repository, sub_ids: Vec<String>) {
+pub(super) async fn pre_end_ws(db: Repository, sub_ids: Vec<String>) {
     join_all(sub_ids.iter().map(|sub_id| Subscription::update_disconnect_time_by_id(&db, sub_id))).await;
 }
 
-#[derive(Message)]
-#[rtype(result="()")]
-pub struct PubsubMsg {
-    payload: String
+// tells the reconciler the subscriptions are in use, while the socket is there
+pub(super) async fn beat_subscriptions(redis: RedisClient, sub_ids: Vec<String>) {
+    if let Err(err) = redis.beat_subscriptions(&sub_ids).await {
+        log::warn!("Saving subscription heartbeat failed: {}", err);
+    }
+}
+
+// covers what nothing was cached for, like events that started before the server did
+async fn load_live_event(ctx: &Context, user_id: i64, topic: &str) -> Option<String> {
+    match get_live_event(ctx, user_id, topic).await {
+        Ok(event) => event.map(|event| serde_json::to_string(&event).expect("No way we fail serialization")),
+        Err(err) => {
+            log::warn!("Getting the live {} event for user {} failed: {}", topic, user_id, err);
+            None
+        },
+    }
+}
+
+// the last events go first, so the overlay doesn't sit empty until the next one.
+// read them after listening starts, so nothing published in between gets lost
+pub(super) async fn load_snapshots(ctx: &Context, user_id: i64, topics: Vec<String>) -> Vec<(String, String)> {
+    let mut snapshots = Vec::new();
+    for topic in topics {
+        let last_message = match ctx.get_last_message(&user_id.to_string(), &topic).await {
+            Ok(last_message) => last_message,
+            Err(err) => {
+                log::warn!("Reading the last message for {}:{} failed: {}", user_id, topic, err);
+                None
+            },
+        };
+
+        let payload = match last_message {
+            Some(payload) => Some(payload),
+            None => load_live_event(ctx, user_id, &topic).await,
+        };
+
+        if let Some(payload) = payload {
+            snapshots.push((topic, payload));
+        }
+    }
+
+    snapshots
+}
+
+// what the source missed since it was last connected, `None` if it has to start over from a snapshot
+pub(super) async fn load_missed(redis: &RedisClient, user_id: i64, topic: &str, since: u64) -> Option<Vec<(u64, String)>> {
+    match redis.get_events_since(&user_id.to_string(), topic, since).await {
+        Ok(missed) => missed,
+        Err(err) => {
+            log::warn!("Reading the backlog for {}:{} failed: {}", user_id, topic, err);
+            None
+        },
+    }
 }
 
 #[derive(Message)]
@@ -42,32 +89,166 @@ pub struct PubsubErr {
 
 pub struct GenericPassthroughWs {
     user_id: i64,
-    pubsub_topic: String,
+    topics: Vec<String>,
+    // with more than one topic messages have to say which one they're for
+    tag_topics: bool,
     sub_ids: Vec<String>,
-    db: Repository
+    // what the source asked for, it gets the newest we have if it's ahead of us
+    requested_version: u32,
+    // seq of the last event the source got before reconnecting
+    since: Option<u64>,
+    coalescer: Coalescer,
+    // sends what the coalescer holds once its window is over
+    flush_timer: Option<SpawnHandle>,
+    ctx: Context,
+    listener_id: Option<ListenerId>,
+    // live messages wait here until the last events are sent, so those don't come after newer ones
+    pending: Option<Vec<TopicMessage>>
 }
 
 impl GenericPassthroughWs {
-    pub fn new(user_id: i64, topic: &str, sub_ids: Vec<String>, db: Repository) -> Self {
+    pub fn new(
+        user_id: i64,
+        topic: &str,
+        sub_ids: Vec<String>,
+        since: Option<u64>,
+        requested_version: u32,
+        ctx: &Context
+    ) -> Self {
+        // resuming only works for a single topic
+        Self { since, ..Self::with_topics(user_id, vec![topic.to_string()], false, sub_ids, requested_version, ctx) }
+    }
+
+    /// One socket for all of the topics, every message says which topic it's for.
+    pub fn multiplexed(
+        user_id: i64,
+        topics: Vec<String>,
+        sub_ids: Vec<String>,
+        requested_version: u32,
+        ctx: &Context
+    ) -> Self {
+        Self::with_topics(user_id, topics, true, sub_ids, requested_version, ctx)
+    }
+
+    fn with_topics(
+        user_id: i64,
+        topics: Vec<String>,
+        tag_topics: bool,
+        sub_ids: Vec<String>,
+        requested_version: u32,
+        ctx: &Context
+    ) -> Self {
+        let coalescer = Coalescer::new(&topics);
+
         Self {
             user_id,
-            pubsub_topic: topic.to_string(),
+            topics,
+            tag_topics,
             sub_ids,
-            db
+            requested_version,
+            since: None,
+            coalescer,
+            flush_timer: None,
+            ctx: ctx.clone(),
+            listener_id: None,
+            pending: Some(Vec::new())
+        }
+    }
+
+    fn protocol_version(&self) -> u32 {
+        let version = self.requested_version.min(PROTOCOL_VERSION);
+
+        // the topic is only in the envelope, so there's no going without one
+        if self.tag_topics { version.max(1) } else { version }
+    }
+
+    // version 0 sources only understand bare events, so they don't get anything else
+    fn send_envelope(&self, message: EnvelopeMessage, topic: Option<&str>, seq: Option<u64>, ctx: &mut ws::WebsocketContext<Self>) {
+        if self.protocol_version() == 0 {
+            return;
+        }
+
+        let server_time = OffsetDateTime::now_utc().format(&Rfc3339).expect("Current time is always formattable");
+        let mut envelope = Envelope::new(self.protocol_version(), server_time, message);
+
+        if let Some(topic) = topic.filter(|_| self.tag_topics) {
+            envelope = envelope.with_topic(topic);
+        }
+
+        if let Some(seq) = seq {
+            envelope = envelope.with_seq(seq);
+        }
+
+        ctx.text(serde_json::to_string(&envelope).expect("No way we fail serialization"));
+    }
+
+    fn send_event(&self, topic: &str, payload: String, seq: Option<u64>, is_snapshot: bool, ctx: &mut ws::WebsocketContext<Self>) {
+        if self.protocol_version() == 0 {
+            // pass it through the websocket
+            ctx.text(payload);
+            return;
+        }
+
+        let event = match serde_json::from_str::<EventSubMessage>(&payload) {
+            Ok(event) => event,
+            Err(err) => {
+                log::warn!("Message for {}:{} isn't an event: {}", self.user_id, topic, err);
+                return;
+            },
+        };
+
+        let message = if is_snapshot { EnvelopeMessage::Snapshot(event) } else { EnvelopeMessage::Event(event) };
+        self.send_envelope(message, Some(topic), seq, ctx);
+    }
+
+    fn forward(&mut self, msg: TopicMessage, ctx: &mut ws::WebsocketContext<Self>) {
+        let (ready, window) = self.coalescer.push(msg);
+
+        // the held ones went out with this message, the timer would cut the next window short
+        if !self.coalescer.is_holding() {
+            if let Some(flush_timer) = self.flush_timer.take() {
+                ctx.cancel_future(flush_timer);
+            }
+        }
+
+        for msg in ready {
+            self.send_event(&msg.topic, msg.payload, msg.seq, false, ctx);
+        }
+
+        if let Some(window) = window {
+            self.flush_timer = Some(ctx.run_later(window, |act, ctx| {
+                act.flush_timer = None;
+
+                for msg in act.coalescer.take_held() {
+                    act.send_event(&msg.topic, msg.payload, msg.seq, false, ctx);
+                }
+            }));
         }
     }
 }
 
 impl Actor for GenericPassthroughWs {
     type Context = ws::WebsocketContext<Self>;
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        if let Some(listener_id) = self.listener_id.take() {
+            self.ctx.pubsub.do_send(StopListening(listener_id));
+        }
+
+        // sockets that drop without a close frame end up here too.
+        // the actor is gone by the time this runs, so it can't wait for it
+        actix_web::rt::spawn(pre_end_ws(self.ctx.repository.clone(), self.sub_ids.clone()));
+    }
 }
 
-impl Handler<PubsubMsg> for GenericPassthroughWs {
+impl Handler<TopicMessage> for GenericPassthroughWs {
     type Result = ();
 
-    fn handle(&mut self, msg: PubsubMsg, ctx: &mut Self::Context) -> Self::Result {
-        // pass it through the websocket
-        ctx.text(msg.payload);
+    fn handle(&mut self, msg: TopicMessage, ctx: &mut Self::Context) -> Self::Result {
+        match &mut self.pending {
+            Some(pending) => pending.push(msg),
+            None => self.forward(msg, ctx),
+        }
     }
 }
 
@@ -75,8 +256,8 @@ impl Handler<PubsubErr> for GenericPassthroughWs {
     type Result = ();
 
     fn handle(&mut self, msg: PubsubErr, ctx: &mut Self::Context) -> Self::Result {
-        ctx.wait(pre_end_ws(self.db.clone(), self.sub_ids.clone()).into_actor(self));
-        
+        self.send_envelope(EnvelopeMessage::Error(msg.err.to_string()), None, None, ctx);
+
         ctx.close(Some((
             ws::CloseCode::Error,
             msg.err.to_string()
@@ -88,48 +269,196 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for GenericPassthroug
     fn handle(&mut self, item: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
         match item {
             Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
-            Ok(ws::Message::Close(close)) => {
-                ctx.wait(pre_end_ws(self.db.clone(), self.sub_ids.clone()).into_actor(self));
-                
-                ctx.close(close)
-            },
+            Ok(ws::Message::Close(close)) => ctx.close(close),
             _ => ()
         }
     }
 
     fn started(&mut self, ctx: &mut Self::Context) {
-        // makes a pubsub listen
+        if self.requested_version > PROTOCOL_VERSION {
+            let notice = format!("Protocol version {} isn't supported, using {}", self.requested_version, PROTOCOL_VERSION);
+            self.send_envelope(EnvelopeMessage::Notice(notice), None, None, ctx);
+        }
+
+        if self.protocol_version() > 0 {
+            ctx.run_interval(HEARTBEAT_INTERVAL, |act, ctx| act.send_envelope(EnvelopeMessage::Heartbeat, None, None, ctx));
+        }
+
+        // websocket transport subscriptions go away with the EventSub session, reconnecting makes new ones.
+        // the disconnect is written by `stopped`, like for any other way the socket ends
+        if use_websocket_transport() {
+            ctx.spawn(self.ctx.eventsub_session.subscriptions_lost().into_actor(self).map(|_, _act, ctx| {
+                ctx.close(Some((ws::CloseCode::Restart, "EventSub session was lost, reconnect").into()));
+                ctx.stop();
+            }));
+        }
+
+        ctx.run_interval(SUBSCRIPTION_BEAT_INTERVAL, |act, ctx| {
+            ctx.spawn(beat_subscriptions(act.ctx.redis.clone(), act.sub_ids.clone()).into_actor(act));
+        });
+
+        // makes the pubsub dispatcher send our topics here
+        let listen = Listen {
+            user_id: self.user_id,
+            topics: self.topics.clone(),
+            recipient: ctx.address().recipient(),
+        };
+
         let user_id = self.user_id;
-        let topic = self.pubsub_topic.clone();
-        let addr = ctx.address();
+        let topics = self.topics.clone();
 
-        let db = self.db.clone();
+        let app_ctx = self.ctx.clone();
         let sub_ids = self.sub_ids.clone();
+        let since = self.since;
+
+        let fut = async move {
+            beat_subscriptions(app_ctx.redis.clone(), sub_ids.clone()).await;
+            pre_start_ws(app_ctx.repository.clone(), sub_ids).await;
+
+            let listener_id = app_ctx.pubsub.send(listen).await?;
+
+            let missed = match (since, topics.as_slice()) {
+                (Some(since), [topic]) => load_missed(&app_ctx.redis, user_id, topic, since).await
+                    .map(|missed| (topic.clone(), missed)),
+                _ => None,
+            };
+
+            // everything that was missed is better than a snapshot
+            let snapshots = match missed {
+                Some(_) => Vec::new(),
+                None => load_snapshots(&app_ctx, user_id, topics).await,
+            };
+
+            Ok((listener_id, snapshots, missed))
+        };
 
-        let fut = Box::pin(
-            
-            async move {
-                pre_start_ws(db, sub_ids).await;
-
-                match subscribe(user_id, &topic).await {
-                    Ok(mut sub) => {
-                        while let Some(msg) = sub.next().await {
-                            match msg.get_payload::<String>() {
-                                Ok(payload) => {
-                                    addr.do_send(PubsubMsg { payload })
-                                },
-                                Err(err) => addr.do_send(PubsubErr { err: err.into() }),
-                            }
-                        }
-                    },
-                    Err(err) => {
-                        addr.do_send(PubsubErr { err })
-                    },
+        ctx.spawn(fut.into_actor(self).map(|result: Result<_, actix::MailboxError>, act, ctx| match result {
+            Ok((listener_id, snapshots, missed)) => {
+                act.listener_id = Some(listener_id);
+
+                for (topic, payload) in snapshots {
+                    act.send_event(&topic, payload, None, true, ctx);
                 }
-            }
 
-        );
+                // topic -> seq of the last event sent from the backlog
+                let mut replayed = HashMap::new();
+
+                if let Some((topic, missed)) = missed {
+                    for (seq, payload) in missed {
+                        replayed.insert(topic.clone(), seq);
+                        act.forward(TopicMessage { topic: topic.clone(), seq: Some(seq), payload }, ctx);
+                    }
+                }
+
+                // whatever came in while reading the backlog may be in it too. seqs are only
+                // counted within a topic and can arrive out of order, so nothing else is skipped
+                for msg in act.pending.take().unwrap_or_default() {
+                    let already_sent = match (msg.seq, replayed.get(&msg.topic)) {
+                        (Some(seq), Some(last_replayed)) => seq <= *last_replayed,
+                        _ => false,
+                    };
 
-        ctx.spawn(fut.into_actor(self));
-    }    
+                    if !already_sent {
+                        act.forward(msg, ctx);
+                    }
+                }
+            },
+            Err(err) => ctx.notify(PubsubErr { err: err.into() }),
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use actix_codec::Framed;
+    use actix_web::{web, App, HttpRequest, HttpServer};
+    use actix_web_actors::ws;
+    use awc::{BoxedSocket, ws::{Codec, Frame}};
+    use futures::StreamExt;
+    use serde_json::json;
+    use twitch_sources_rework::common_data::envelope::{Envelope, EnvelopeMessage};
+    use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, SubType};
+
+    use super::GenericPassthroughWs;
+    use crate::my_redis::{last_message::LastMessageStore, publisher::MessagePublisher};
+    use crate::util::Context;
+
+    async fn multi_source(req: HttpRequest, stream: web::Payload, ctx: Context) -> Result<actix_web::HttpResponse, actix_web::Error> {
+        let topics = vec!["poll".to_string(), "stream_status".to_string()];
+
+        ws::start(GenericPassthroughWs::multiplexed(1337, topics, Vec::new(), 1, &ctx), &req, stream)
+    }
+
+    fn event(sub_type: &SubType, data: serde_json::Value) -> String {
+        let event = EventSubMessage::new(sub_type, "2020-10-11T10:11:12.123Z", data).expect("Event to parse");
+
+        serde_json::to_string(&event).expect("Event to serialize")
+    }
+
+    async fn next_envelope(socket: &mut Framed<BoxedSocket, Codec>) -> Envelope {
+        let frame = actix_web::rt::time::timeout(Duration::from_secs(5), socket.next()).await
+            .expect("Message in time")
+            .expect("Socket to stay open")
+            .expect("Frame to be read");
+
+        match frame {
+            Frame::Text(text) => serde_json::from_slice(&text).expect("Message to be an envelope"),
+            other => panic!("Expected a text frame, got {:?}", other),
+        }
+    }
+
+    #[actix_web::test]
+    async fn multi_socket_tags_messages_with_their_topic() {
+        let ctx = Context::in_memory();
+
+        let offline = event(&SubType::StreamOffline, json!({
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User"
+        }));
+        ctx.save_last_message("1337", "stream_status", &offline, Duration::from_secs(60)).await.expect("Save to work");
+
+        let server = {
+            let ctx = ctx.clone();
+
+            HttpServer::new(move || App::new().app_data(ctx.clone()).route("/multi", web::get().to(multi_source)))
+                .workers(1)
+                .disable_signals()
+                .bind(("127.0.0.1", 0))
+                .expect("Server to bind")
+        };
+        let addr = server.addrs()[0];
+        let server = server.run();
+        let server_handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        let (_, mut socket) = awc::Client::new().ws(format!("ws://{}/multi", addr)).connect().await.expect("Socket to connect");
+
+        // snapshots are read once the socket listens, so nothing published after this is missed
+        let snapshot = next_envelope(&mut socket).await;
+        assert_eq!(snapshot.topic.as_deref(), Some("stream_status"));
+        assert!(matches!(snapshot.message, EnvelopeMessage::Snapshot(_)));
+
+        let poll = event(&SubType::ChannelPollBegin, json!({
+            "id": "1",
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User",
+            "title": "Aren't shoes just really hard socks?",
+            "choices": [],
+            "bits_voting": { "is_enabled": false, "amount_per_vote": 0 },
+            "channel_points_voting": { "is_enabled": false, "amount_per_vote": 0 },
+            "started_at": "2020-07-15T17:16:03.17106713Z",
+            "ends_at": "2020-07-15T17:16:08.17106713Z"
+        }));
+        ctx.publish_message("1337", "poll", poll.as_bytes()).await.expect("Publish to work");
+
+        let envelope = next_envelope(&mut socket).await;
+        assert_eq!(envelope.topic.as_deref(), Some("poll"));
+        assert!(matches!(envelope.message, EnvelopeMessage::Event(_)));
+
+        server_handle.stop(false).await;
+    }
 }
\ No newline at end of file
@@ -2,9 +2,21 @@ mod common;
 mod context;
 mod prediction_test;
 mod hype_train_test;
+mod poll_test;
+mod goals_test;
+mod alerts_test;
+mod redemptions_test;
+mod charity_test;
+mod stream_status_test;
+mod ad_break_test;
+#[cfg(test)]
+pub(crate) mod testing;
 
+pub mod coalescer;
 pub mod message_manager;
+pub mod pubsub_dispatcher;
 pub mod session_state;
+pub mod sse_manager;
 
 pub use common::create_connection_pool;
 pub use common::init_dotenv;
@@ -14,8 +26,25 @@ pub use common::get_redis_client_pool;
 pub use common::get_redis_connection;
 pub use common::get_twitch_key;
 pub use common::get_twitch_secret;
+pub use common::get_twitch_api_url;
+pub use common::get_twitch_auth_url;
+pub use common::get_webhook_base_url;
+pub use common::use_in_memory_storage;
+pub use common::use_broadcast_message_bus;
+pub use common::use_websocket_transport;
+pub use common::get_eventsub_ws_url;
+pub use common::get_subscription_idle_window;
+pub use common::get_subscription_reconcile_interval;
+pub use common::get_admin_user_ids;
 pub use common::RedisPool;
 pub use common::DbPool;
 pub use context::Context;
 pub use prediction_test::PredictionsTestActor;
-pub use hype_train_test::HypetrainTestActor;
\ No newline at end of file
+pub use hype_train_test::HypetrainTestActor;
+pub use poll_test::PollTestActor;
+pub use goals_test::GoalsTestActor;
+pub use alerts_test::AlertsTestActor;
+pub use redemptions_test::RedemptionsTestActor;
+pub use charity_test::CharityTestActor;
+pub use stream_status_test::StreamStatusTestActor;
+pub use ad_break_test::AdBreakTestActor;
\ No newline at end of file
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use actix::{Actor, Context, WrapFuture, AsyncContext};
+use rand::Rng;
+use time::{OffsetDateTime, Duration, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, PollChoice, PollVotingSettings, PollEndStatus, ChannelPollBegin, ChannelPollProgress, ChannelPollEnd};
+use tokio::time::sleep as async_sleep;
+
+use crate::{RunningTests, my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
+
+const TEST_TIME_SECONDS: i64 = 8;
+
+const CHOICES: [(&str, &str); 3] = [
+    ("1243456", "Somewhat a long choice"),
+    ("2243456", "Short choice"),
+    ("3243456", "But there's more!"),
+];
+
+fn choices(votes: [i64; 3]) -> Vec<PollChoice> {
+    CHOICES.iter().zip(votes).map(|((id, title), votes)| PollChoice {
+        id: id.to_string(),
+        title: title.to_string(),
+        bits_votes: 0,
+        channel_points_votes: 0,
+        votes
+    }).collect()
+}
+
+fn voting_disabled() -> PollVotingSettings {
+    PollVotingSettings { is_enabled: false, amount_per_vote: 0 }
+}
+
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
+    let title = "Some decently long title, just to make sure nothing breaks".to_string();
+
+    let mut rng = rand::thread_rng();
+    // same as with predictions, a random id makes each test run a separate poll
+    let id: String = (0..10).map(|_| rng.sample(rand::distributions::Alphanumeric) as char).collect();
+
+    let start_time = OffsetDateTime::now_utc();
+    let end_time = OffsetDateTime::now_utc() + Duration::seconds(TEST_TIME_SECONDS);
+
+    let begin_msg = EventSubMessage {
+        data: EventSubData::ChannelPollBegin(ChannelPollBegin {
+            id: id.clone(),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_user".to_string(),
+            broadcaster_user_name: "Cool_User".to_string(),
+            title: title.clone(),
+            choices: choices([0, 0, 0]),
+            bits_voting: voting_disabled(),
+            channel_points_voting: voting_disabled(),
+            started_at: start_time.format(&Rfc3339).unwrap(),
+            ends_at: end_time.format(&Rfc3339).unwrap()
+        }),
+        msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+    };
+    let data = serde_json::ser::to_vec(&begin_msg).expect("No way we fail serialization");
+    conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[2].topic, &data).await?;
+    async_sleep(std::time::Duration::from_secs(1)).await;
+
+    for votes in [[1, 0, 0], [1, 3, 0], [2, 5, 4]] {
+        let progress_msg = EventSubMessage {
+            data: EventSubData::ChannelPollProgress(ChannelPollProgress {
+                id: id.clone(),
+                broadcaster_user_id: user_id.to_string(),
+                broadcaster_user_login: "cool_user".to_string(),
+                broadcaster_user_name: "Cool_User".to_string(),
+                title: title.clone(),
+                choices: choices(votes),
+                bits_voting: voting_disabled(),
+                channel_points_voting: voting_disabled(),
+                started_at: start_time.format(&Rfc3339).unwrap(),
+                ends_at: end_time.format(&Rfc3339).unwrap()
+            }),
+            msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        };
+        let data = serde_json::ser::to_vec(&progress_msg).expect("No way we fail serialization");
+        conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[2].topic, &data).await?;
+        async_sleep(std::time::Duration::from_secs(2)).await;
+    }
+
+    async_sleep((end_time - OffsetDateTime::now_utc()).try_into().unwrap_or_default()).await;
+
+    let end_msg = EventSubMessage {
+        data: EventSubData::ChannelPollEnd(ChannelPollEnd {
+            id: id.clone(),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_user".to_string(),
+            broadcaster_user_name: "Cool_User".to_string(),
+            title: title.clone(),
+            choices: choices([2, 5, 4]),
+            bits_voting: voting_disabled(),
+            channel_points_voting: voting_disabled(),
+            status: PollEndStatus::Completed,
+            started_at: start_time.format(&Rfc3339).unwrap(),
+            ended_at: end_time.format(&Rfc3339).unwrap()
+        }),
+        msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+    };
+    let data = serde_json::ser::to_vec(&end_msg).expect("No way we fail serialization");
+    conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[2].topic, &data).await?;
+
+    Ok(())
+}
+
+pub struct PollTestActor {
+    running_tests: Arc<RunningTests>,
+    key: String,
+    user_id: i64,
+    bus: MessageBus
+}
+
+impl PollTestActor {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
+        let key = user_id.to_string() + ":tests:poll";
+        if running_tests.contains(&key) {
+            return Err(anyhow!("Test is still running"));
+        }
+
+        running_tests.insert(key.clone());
+        Ok(Self { running_tests, key, user_id, bus })
+    }
+}
+
+impl Actor for PollTestActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let user_id = self.user_id;
+        let bus = self.bus.clone();
+        let key = self.key.clone();
+        let running_tests = self.running_tests.clone();
+
+        let fut = Box::pin(
+
+            async move {
+                // silently ignore the error
+                _ = execute_test(bus, &user_id.to_string()).await;
+                running_tests.remove(&key);
+            }
+
+        );
+
+        ctx.spawn(fut.into_actor(self));
+    }
+}
@@ -7,15 +7,11 @@ use time::{OffsetDateTime, Duration, format_description::well_known::Rfc3339};
 use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, PredictionsOutcome, TopPredictior, ChannelPredictionBegin, ChannelPredictionProgress, ChannelPredictionLock, ChannelPredictionEnd};
 use tokio::time::sleep as async_sleep;
 
-use crate::{RunningTests, my_redis::{RedisClient, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
-
-use super::get_redis_client_pool;
+use crate::{RunningTests, my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
 
 const TEST_TIME_SECONDS: i64 = 13;
 
-async fn execute_test(user_id: &str) -> Result<()> {
-    let conn = RedisClient::new(get_redis_client_pool()?);
-
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
     let title = "Some decently long title, just to make sure nothing breaks and stuff, and just a bit more".to_string();
     let option1 = "Somewhat a long option".to_string();
     let option2 = "Short option".to_string();
@@ -533,18 +529,19 @@ async fn execute_test(user_id: &str) -> Result<()> {
 pub struct PredictionsTestActor {
     running_tests: Arc<RunningTests>,
     key: String,
-    user_id: i64
+    user_id: i64,
+    bus: MessageBus
 }
 
 impl PredictionsTestActor {
-    pub fn new(running_tests: Arc<RunningTests>, user_id: i64) -> Result<Self> {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
         let key = user_id.to_string() + ":tests:predictions";
         if running_tests.contains(&key) {
             return Err(anyhow!("Test is still running"));
         }
 
         running_tests.insert(key.clone());
-        Ok(Self { running_tests, key, user_id })
+        Ok(Self { running_tests, key, user_id, bus })
     }
 }
 
@@ -553,6 +550,7 @@ impl Actor for PredictionsTestActor {
 
     fn started(&mut self, ctx: &mut Self::Context) {
         let user_id = self.user_id;
+        let bus = self.bus.clone();
         let key = self.key.clone();
         let running_tests = self.running_tests.clone();
 
@@ -560,7 +558,7 @@ impl Actor for PredictionsTestActor {
 
             async move {
                 // silently ignore the error
-                _ = execute_test(&user_id.to_string()).await;
+                _ = execute_test(bus, &user_id.to_string()).await;
                 running_tests.remove(&key);
             }
 
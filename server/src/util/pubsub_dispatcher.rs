@@ -0,0 +1,277 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use actix::{Actor, ActorFutureExt, AsyncContext, Context, Handler, Message, Recipient, ResponseFuture, StreamHandler, WrapFuture};
+use futures_util::future::join_all;
+use tokio::sync::oneshot;
+
+use crate::my_redis::{event_backlog::split_seq, MessageBus, PubsubCommand, PubsubControl, RedisError, channel_topic, topic_channel};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A message published to one of the topics a listener asked for
+#[derive(Message)]
+#[rtype(result="()")]
+pub struct TopicMessage {
+    pub topic: String,
+    /// Set for events from twitch, see [`crate::my_redis::event_backlog`]
+    pub seq: Option<u64>,
+    pub payload: String
+}
+
+/// Starts sending the user's topics to the recipient, answers once they're subscribed to.
+/// Give the id back with [`StopListening`] when done.
+#[derive(Message)]
+#[rtype(result="ListenerId")]
+pub struct Listen {
+    pub user_id: i64,
+    pub topics: Vec<String>,
+    pub recipient: Recipient<TopicMessage>
+}
+
+#[derive(Message)]
+#[rtype(result="()")]
+pub struct StopListening(pub ListenerId);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+struct Listener {
+    channels: Vec<String>,
+    recipient: Recipient<TopicMessage>
+}
+
+/// Shares one pubsub connection between every socket. Channels are subscribed to while
+/// at least one listener wants them, and everything is subscribed to again after a reconnect.
+pub struct PubsubDispatcher {
+    bus: MessageBus,
+    // none while (re)connecting
+    control: Option<PubsubControl>,
+    next_id: u64,
+    listeners: HashMap<ListenerId, Listener>,
+    // channel -> who's listening to it
+    channels: HashMap<String, HashSet<ListenerId>>
+}
+
+impl PubsubDispatcher {
+    pub fn new(bus: MessageBus) -> Self {
+        Self {
+            bus,
+            control: None,
+            next_id: 0,
+            listeners: HashMap::new(),
+            channels: HashMap::new()
+        }
+    }
+
+    fn connect(&mut self, ctx: &mut Context<Self>) {
+        let bus = self.bus.clone();
+
+        // listeners wait until it's done, so their channels aren't subscribed to twice
+        ctx.wait(
+            async move { bus.pubsub().await }
+                .into_actor(self)
+                .map(|result, act, ctx| match result {
+                    Ok((control, messages)) => {
+                        for channel in act.channels.keys() {
+                            _ = control.send(PubsubCommand::Subscribe(channel.clone(), None));
+                        }
+
+                        act.control = Some(control);
+                        ctx.add_stream(messages);
+                    },
+                    Err(err) => {
+                        log::error!("Connecting to pubsub failed, trying again in {:?}: {}", RECONNECT_DELAY, err);
+                        ctx.run_later(RECONNECT_DELAY, |act, ctx| act.connect(ctx));
+                    },
+                })
+        );
+    }
+
+    fn remove_listener(&mut self, id: ListenerId) {
+        let listener = match self.listeners.remove(&id) {
+            Some(listener) => listener,
+            None => return,
+        };
+
+        for channel in listener.channels {
+            let listeners = match self.channels.get_mut(&channel) {
+                Some(listeners) => listeners,
+                None => continue,
+            };
+
+            listeners.remove(&id);
+
+            if listeners.is_empty() {
+                self.channels.remove(&channel);
+
+                if let Some(control) = &self.control {
+                    _ = control.send(PubsubCommand::Unsubscribe(channel));
+                }
+            }
+        }
+    }
+}
+
+impl Actor for PubsubDispatcher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.connect(ctx);
+    }
+}
+
+impl Handler<Listen> for PubsubDispatcher {
+    type Result = ResponseFuture<ListenerId>;
+
+    fn handle(&mut self, msg: Listen, _ctx: &mut Self::Context) -> Self::Result {
+        let id = ListenerId(self.next_id);
+        self.next_id += 1;
+
+        let channels: Vec<String> = msg.topics.iter()
+            .map(|topic| topic_channel(&msg.user_id.to_string(), topic))
+            .collect();
+
+        let mut subscribed = Vec::new();
+        for channel in &channels {
+            let listeners = self.channels.entry(channel.clone()).or_default();
+
+            // the first one to ask for a channel subscribes to it
+            if listeners.is_empty() {
+                if let Some(control) = &self.control {
+                    let (done, subscribed_rx) = oneshot::channel();
+
+                    if control.send(PubsubCommand::Subscribe(channel.clone(), Some(done))).is_ok() {
+                        subscribed.push(subscribed_rx);
+                    }
+                }
+            }
+
+            listeners.insert(id);
+        }
+
+        self.listeners.insert(id, Listener { channels, recipient: msg.recipient });
+
+        Box::pin(async move {
+            // a lost connection drops these too, the channels get subscribed to on reconnect anyway
+            join_all(subscribed).await;
+            id
+        })
+    }
+}
+
+impl Handler<StopListening> for PubsubDispatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: StopListening, _ctx: &mut Self::Context) -> Self::Result {
+        self.remove_listener(msg.0);
+    }
+}
+
+impl StreamHandler<Result<(String, String), RedisError>> for PubsubDispatcher {
+    fn handle(&mut self, item: Result<(String, String), RedisError>, _ctx: &mut Self::Context) {
+        let (channel, payload) = match item {
+            Ok(message) => message,
+            Err(err) => {
+                log::warn!("Reading a pubsub message failed: {}", err);
+                return;
+            },
+        };
+
+        let listeners = match self.channels.get(&channel) {
+            Some(listeners) => listeners.clone(),
+            None => return,
+        };
+
+        let (seq, payload) = split_seq(&payload);
+
+        for id in listeners {
+            let recipient = match self.listeners.get(&id) {
+                Some(listener) => &listener.recipient,
+                None => continue,
+            };
+
+            // sockets that went away without saying so
+            if !recipient.connected() {
+                self.remove_listener(id);
+                continue;
+            }
+
+            recipient.do_send(TopicMessage {
+                topic: channel_topic(&channel).to_string(),
+                seq,
+                payload: payload.to_string()
+            });
+        }
+    }
+
+    fn finished(&mut self, ctx: &mut Self::Context) {
+        log::warn!("Pubsub connection was lost, reconnecting");
+
+        self.control = None;
+        self.connect(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix::{Actor, Context, Handler};
+    use tokio::sync::mpsc;
+
+    use super::{Listen, StopListening, TopicMessage};
+    use crate::my_redis::publisher::MessagePublisher;
+    use crate::util::Context as AppContext;
+
+    // stands in for a socket, hands everything it gets to the test
+    struct Collector(mpsc::UnboundedSender<(String, String)>);
+
+    impl Actor for Collector {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<TopicMessage> for Collector {
+        type Result = ();
+
+        fn handle(&mut self, msg: TopicMessage, _ctx: &mut Self::Context) -> Self::Result {
+            _ = self.0.send((msg.topic, msg.payload));
+        }
+    }
+
+    fn listen(user_id: i64, topics: &[&str]) -> (Listen, mpsc::UnboundedReceiver<(String, String)>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let listen = Listen {
+            user_id,
+            topics: topics.iter().map(|topic| topic.to_string()).collect(),
+            recipient: Collector(sender).start().recipient(),
+        };
+
+        (listen, receiver)
+    }
+
+    #[actix_web::test]
+    async fn routes_messages_to_everyone_listening() {
+        let ctx = AppContext::in_memory();
+
+        let (first, mut first_rx) = listen(1337, &["polls"]);
+        let (second, mut second_rx) = listen(1337, &["polls", "goals"]);
+
+        let first_id = ctx.pubsub.send(first).await.expect("Listen to work");
+        ctx.pubsub.send(second).await.expect("Listen to work");
+
+        // someone else's polls don't go anywhere
+        ctx.publish_message("42", "polls", b"other").await.expect("Publish to work");
+        ctx.publish_message("1337", "polls", b"one").await.expect("Publish to work");
+
+        assert_eq!(first_rx.recv().await, Some(("polls".to_string(), "one".to_string())));
+        assert_eq!(second_rx.recv().await, Some(("polls".to_string(), "one".to_string())));
+
+        // the channel stays subscribed to while the second one still wants it
+        ctx.pubsub.send(StopListening(first_id)).await.expect("StopListening to work");
+        ctx.publish_message("1337", "goals", b"two").await.expect("Publish to work");
+        ctx.publish_message("1337", "polls", b"three").await.expect("Publish to work");
+
+        assert_eq!(second_rx.recv().await, Some(("goals".to_string(), "two".to_string())));
+        assert_eq!(second_rx.recv().await, Some(("polls".to_string(), "three".to_string())));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use actix::{Actor, Context, WrapFuture, AsyncContext};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, ChannelPointsCustomRewardRedemptionAdd, ChannelPointsCustomRewardRedemptionUpdate, RedemptionReward, RedemptionStatus};
+use tokio::time::sleep as async_sleep;
+
+use crate::{RunningTests, my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
+
+fn redemption(user_id: &str, redemption_id: &str, user_name: &str, reward: (&str, &str, i64), user_input: &str) -> ChannelPointsCustomRewardRedemptionAdd {
+    let (reward_id, reward_title, cost) = reward;
+
+    ChannelPointsCustomRewardRedemptionAdd {
+        id: redemption_id.to_string(),
+        broadcaster_user_id: user_id.to_string(),
+        broadcaster_user_login: "cool_streamer".to_string(),
+        broadcaster_user_name: "Cool_Streamer".to_string(),
+        user_id: "1234".to_string(),
+        user_login: user_name.to_lowercase(),
+        user_name: user_name.to_string(),
+        user_input: user_input.to_string(),
+        status: RedemptionStatus::Unfulfilled,
+        reward: RedemptionReward {
+            id: reward_id.to_string(),
+            title: reward_title.to_string(),
+            cost,
+            prompt: "".to_string()
+        },
+        redeemed_at: OffsetDateTime::now_utc().format(&Rfc3339).unwrap()
+    }
+}
+
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
+    let hydrate = ("test-hydrate", "Hydrate!", 500);
+    let song = ("test-song", "Request a song", 2000);
+
+    let canceled = redemption(user_id, "test-redemption-3", "Coolest_User", song, "Never Gonna Give You Up");
+    let canceled = ChannelPointsCustomRewardRedemptionUpdate {
+        id: canceled.id,
+        broadcaster_user_id: canceled.broadcaster_user_id,
+        broadcaster_user_login: canceled.broadcaster_user_login,
+        broadcaster_user_name: canceled.broadcaster_user_name,
+        user_id: canceled.user_id,
+        user_login: canceled.user_login,
+        user_name: canceled.user_name,
+        user_input: canceled.user_input,
+        status: RedemptionStatus::Canceled,
+        reward: canceled.reward,
+        redeemed_at: canceled.redeemed_at
+    };
+
+    let messages = [
+        EventSubData::ChannelPointsCustomRewardRedemptionAdd(redemption(user_id, "test-redemption-1", "Cool_User", hydrate, "")),
+        EventSubData::ChannelPointsCustomRewardRedemptionAdd(redemption(user_id, "test-redemption-2", "Cooler_User", hydrate, "")),
+        EventSubData::ChannelPointsCustomRewardRedemptionAdd(redemption(user_id, "test-redemption-3", "Coolest_User", song, "Never Gonna Give You Up")),
+        EventSubData::ChannelPointsCustomRewardRedemptionAdd(redemption(user_id, "test-redemption-4", "Coolio_User", hydrate, "")),
+        // the song request gets refunded, so it should disappear from the feed
+        EventSubData::ChannelPointsCustomRewardRedemptionUpdate(canceled),
+    ];
+
+    for data in messages {
+        let msg = EventSubMessage {
+            data,
+            msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        };
+        let data = serde_json::ser::to_vec(&msg).expect("No way we fail serialization");
+        conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[5].topic, &data).await?;
+        async_sleep(std::time::Duration::from_secs(1)).await;
+    }
+
+    Ok(())
+}
+
+pub struct RedemptionsTestActor {
+    running_tests: Arc<RunningTests>,
+    key: String,
+    user_id: i64,
+    bus: MessageBus
+}
+
+impl RedemptionsTestActor {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
+        let key = user_id.to_string() + ":tests:redemptions";
+        if running_tests.contains(&key) {
+            return Err(anyhow!("Test is still running"));
+        }
+
+        running_tests.insert(key.clone());
+        Ok(Self { running_tests, key, user_id, bus })
+    }
+}
+
+impl Actor for RedemptionsTestActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let user_id = self.user_id;
+        let bus = self.bus.clone();
+        let key = self.key.clone();
+        let running_tests = self.running_tests.clone();
+
+        let fut = Box::pin(
+
+            async move {
+                // silently ignore the error
+                _ = execute_test(bus, &user_id.to_string()).await;
+                running_tests.remove(&key);
+            }
+
+        );
+
+        ctx.spawn(fut.into_actor(self));
+    }
+}
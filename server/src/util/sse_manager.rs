@@ -0,0 +1,225 @@
+use std::time::Duration;
+
+use actix::{Actor, ActorFutureExt, AsyncContext, Context as ActorContext, Handler, SpawnHandle, WrapFuture};
+use actix_web::web::Bytes;
+use futures::Stream;
+use tokio::sync::mpsc;
+use twitch_sources_rework::common_data::eventsub_msgs::EventSubMessage;
+
+use crate::my_redis::connection_heartbeat::SUBSCRIPTION_BEAT_INTERVAL;
+
+use super::{Context, use_websocket_transport};
+use super::coalescer::Coalescer;
+use super::message_manager::{pre_start_ws, pre_end_ws, beat_subscriptions, load_snapshots, load_missed};
+use super::pubsub_dispatcher::{Listen, ListenerId, StopListening, TopicMessage};
+
+// also how long it takes to notice the source is gone, nothing's written to the response otherwise
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Same as [`super::message_manager::GenericPassthroughWs`], but the events are written
+/// to a `text/event-stream` response. Events from twitch have their sequence number as the `id`,
+/// so a source reconnecting with `Last-Event-ID` gets what it missed instead of a snapshot.
+pub struct GenericPassthroughSse {
+    user_id: i64,
+    topic: String,
+    sub_ids: Vec<String>,
+    // seq of the last event the source got before reconnecting
+    since: Option<u64>,
+    coalescer: Coalescer,
+    // sends what the coalescer holds once its window is over
+    flush_timer: Option<SpawnHandle>,
+    sender: mpsc::UnboundedSender<Bytes>,
+    ctx: Context,
+    listener_id: Option<ListenerId>,
+    // live messages wait here until the last event is sent, so it doesn't come after newer ones
+    pending: Option<Vec<TopicMessage>>
+}
+
+impl GenericPassthroughSse {
+    /// The actor along with the body of the response, which ends when the actor stops.
+    pub fn new(
+        user_id: i64,
+        topic: &str,
+        sub_ids: Vec<String>,
+        last_event_id: Option<&str>,
+        ctx: &Context
+    ) -> (Self, impl Stream<Item = Result<Bytes, actix_web::Error>> + 'static) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let events = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|frame| (Ok(frame), receiver))
+        });
+
+        let source = Self {
+            user_id,
+            topic: topic.to_string(),
+            sub_ids,
+            since: last_event_id.and_then(|id| id.parse().ok()),
+            coalescer: Coalescer::new(&[topic.to_string()]),
+            flush_timer: None,
+            sender,
+            ctx: ctx.clone(),
+            listener_id: None,
+            pending: Some(Vec::new())
+        };
+
+        (source, events)
+    }
+
+    // a closed response means the source is gone
+    fn write(&self, frame: String, ctx: &mut ActorContext<Self>) {
+        if self.sender.send(Bytes::from(frame)).is_err() {
+            ctx.stop();
+        }
+    }
+
+    fn send_event(&self, payload: String, seq: Option<u64>, is_snapshot: bool, ctx: &mut ActorContext<Self>) {
+        // the payload goes out as it is, so only events get through
+        if let Err(err) = serde_json::from_str::<EventSubMessage>(&payload) {
+            log::warn!("Message for {}:{} isn't an event: {}", self.user_id, self.topic, err);
+            return;
+        }
+
+        let kind = if is_snapshot { "snapshot" } else { "event" };
+
+        // EventSource keeps the last id it got when an event has none
+        let frame = match seq {
+            Some(seq) => format!("id: {}\nevent: {}\ndata: {}\n\n", seq, kind, payload),
+            None => format!("event: {}\ndata: {}\n\n", kind, payload),
+        };
+
+        self.write(frame, ctx);
+    }
+
+    fn forward(&mut self, seq: Option<u64>, payload: String, ctx: &mut ActorContext<Self>) {
+        let (ready, window) = self.coalescer.push(TopicMessage { topic: self.topic.clone(), seq, payload });
+
+        // the held ones went out with this message, the timer would cut the next window short
+        if !self.coalescer.is_holding() {
+            if let Some(flush_timer) = self.flush_timer.take() {
+                ctx.cancel_future(flush_timer);
+            }
+        }
+
+        for msg in ready {
+            self.send_event(msg.payload, msg.seq, false, ctx);
+        }
+
+        if let Some(window) = window {
+            self.flush_timer = Some(ctx.run_later(window, |act, ctx| {
+                act.flush_timer = None;
+
+                for msg in act.coalescer.take_held() {
+                    act.send_event(msg.payload, msg.seq, false, ctx);
+                }
+            }));
+        }
+    }
+}
+
+impl Actor for GenericPassthroughSse {
+    type Context = ActorContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // comments are ignored by EventSource
+        ctx.run_interval(HEARTBEAT_INTERVAL, |act, ctx| act.write(": heartbeat\n\n".to_string(), ctx));
+
+        // websocket transport subscriptions go away with the EventSub session, EventSource
+        // reconnects by itself once the response ends, which makes new ones. `stopped` writes the disconnect
+        if use_websocket_transport() {
+            ctx.spawn(self.ctx.eventsub_session.subscriptions_lost().into_actor(self).map(|_, _act, ctx| ctx.stop()));
+        }
+
+        ctx.run_interval(SUBSCRIPTION_BEAT_INTERVAL, |act, ctx| {
+            ctx.spawn(beat_subscriptions(act.ctx.redis.clone(), act.sub_ids.clone()).into_actor(act));
+        });
+
+        // makes the pubsub dispatcher send our topic here
+        let listen = Listen {
+            user_id: self.user_id,
+            topics: vec![self.topic.clone()],
+            recipient: ctx.address().recipient(),
+        };
+
+        let user_id = self.user_id;
+        let topics = vec![self.topic.clone()];
+
+        let app_ctx = self.ctx.clone();
+        let sub_ids = self.sub_ids.clone();
+        let topic = self.topic.clone();
+        let since = self.since;
+
+        let fut = async move {
+            beat_subscriptions(app_ctx.redis.clone(), sub_ids.clone()).await;
+            pre_start_ws(app_ctx.repository.clone(), sub_ids).await;
+
+            let listener_id = app_ctx.pubsub.send(listen).await?;
+
+            let missed = match since {
+                Some(since) => load_missed(&app_ctx.redis, user_id, &topic, since).await,
+                None => None,
+            };
+
+            // everything that was missed is better than a snapshot
+            let snapshots = match missed {
+                Some(_) => Vec::new(),
+                None => load_snapshots(&app_ctx, user_id, topics).await,
+            };
+
+            Ok((listener_id, snapshots, missed.unwrap_or_default()))
+        };
+
+        ctx.spawn(fut.into_actor(self).map(|result: Result<_, actix::MailboxError>, act, ctx| match result {
+            Ok((listener_id, snapshots, missed)) => {
+                act.listener_id = Some(listener_id);
+
+                for (_, payload) in snapshots {
+                    act.send_event(payload, None, true, ctx);
+                }
+
+                let last_replayed = missed.last().map(|(seq, _)| *seq);
+
+                for (seq, payload) in missed {
+                    act.forward(Some(seq), payload, ctx);
+                }
+
+                // whatever came in while reading the backlog may be in it too,
+                // later ones can arrive out of order so they aren't skipped
+                for msg in act.pending.take().unwrap_or_default() {
+                    let already_sent = match (msg.seq, last_replayed) {
+                        (Some(seq), Some(last_replayed)) => seq <= last_replayed,
+                        _ => false,
+                    };
+
+                    if !already_sent {
+                        act.forward(msg.seq, msg.payload, ctx);
+                    }
+                }
+            },
+            Err(err) => {
+                act.write(format!("event: error\ndata: {}\n\n", err), ctx);
+                ctx.stop();
+            },
+        }));
+    }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        if let Some(listener_id) = self.listener_id.take() {
+            self.ctx.pubsub.do_send(StopListening(listener_id));
+        }
+
+        // the actor is gone by the time this runs, so it can't wait for it
+        actix_web::rt::spawn(pre_end_ws(self.ctx.repository.clone(), self.sub_ids.clone()));
+    }
+}
+
+impl Handler<TopicMessage> for GenericPassthroughSse {
+    type Result = ();
+
+    fn handle(&mut self, msg: TopicMessage, ctx: &mut Self::Context) -> Self::Result {
+        match &mut self.pending {
+            Some(pending) => pending.push(msg),
+            None => self.forward(msg.seq, msg.payload, ctx),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use actix::{Actor, Context, WrapFuture, AsyncContext};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubMessage, EventSubData, ChannelUpdate, StreamOnline, StreamOffline, StreamType};
+use tokio::time::sleep as async_sleep;
+
+use crate::{RunningTests, my_redis::{MessageBus, publisher::MessagePublisher}, websockets::WEBSOCKET_DATA_TYPES};
+
+async fn execute_test(conn: MessageBus, user_id: &str) -> Result<()> {
+    // pretend the stream has been going for a while, so the hours show up
+    let started_at = OffsetDateTime::now_utc() - time::Duration::minutes(59);
+
+    let messages = [
+        (EventSubData::ChannelUpdate(ChannelUpdate {
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_user".to_string(),
+            broadcaster_user_name: "Cool_User".to_string(),
+            title: "Some decently long stream title, just to see it fits".to_string(),
+            language: "en".to_string(),
+            category_id: "509658".to_string(),
+            category_name: "Just Chatting".to_string(),
+            content_classification_labels: vec![]
+        }), 1),
+        (EventSubData::StreamOnline(StreamOnline {
+            id: "9001".to_string(),
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_user".to_string(),
+            broadcaster_user_name: "Cool_User".to_string(),
+            type_: StreamType::Live,
+            started_at: started_at.format(&Rfc3339).unwrap()
+        }), 6),
+        (EventSubData::StreamOffline(StreamOffline {
+            broadcaster_user_id: user_id.to_string(),
+            broadcaster_user_login: "cool_user".to_string(),
+            broadcaster_user_name: "Cool_User".to_string()
+        }), 0),
+    ];
+
+    for (data, wait_secs) in messages {
+        let msg = EventSubMessage {
+            data,
+            msg_time: OffsetDateTime::now_utc().format(&Rfc3339).unwrap(),
+        };
+        let data = serde_json::ser::to_vec(&msg).expect("No way we fail serialization");
+        conn.publish_message(user_id, WEBSOCKET_DATA_TYPES[7].topic, &data).await?;
+        async_sleep(std::time::Duration::from_secs(wait_secs)).await;
+    }
+
+    Ok(())
+}
+
+pub struct StreamStatusTestActor {
+    running_tests: Arc<RunningTests>,
+    key: String,
+    user_id: i64,
+    bus: MessageBus
+}
+
+impl StreamStatusTestActor {
+    pub fn new(running_tests: Arc<RunningTests>, user_id: i64, bus: MessageBus) -> Result<Self> {
+        let key = user_id.to_string() + ":tests:stream_status";
+        if running_tests.contains(&key) {
+            return Err(anyhow!("Test is still running"));
+        }
+
+        running_tests.insert(key.clone());
+        Ok(Self { running_tests, key, user_id, bus })
+    }
+}
+
+impl Actor for StreamStatusTestActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let user_id = self.user_id;
+        let bus = self.bus.clone();
+        let key = self.key.clone();
+        let running_tests = self.running_tests.clone();
+
+        let fut = Box::pin(
+
+            async move {
+                // silently ignore the error
+                _ = execute_test(bus, &user_id.to_string()).await;
+                running_tests.remove(&key);
+            }
+
+        );
+
+        ctx.spawn(fut.into_actor(self));
+    }
+}
@@ -0,0 +1,23 @@
+use twitch_mock::MockTwitch;
+
+use crate::http_client::HttpClient;
+
+use super::Context;
+
+pub const CLIENT_ID: &str = "mock_client_id";
+pub const CLIENT_SECRET: &str = "mock_client_secret";
+
+/// In memory context that talks to a freshly started mock Twitch.
+pub async fn mock_context() -> (MockTwitch, Context) {
+    let mock = MockTwitch::start(CLIENT_ID, CLIENT_SECRET).await.expect("Mock Twitch to start");
+
+    let ctx = Context {
+        http_client: HttpClient::with_base_urls(reqwest::Client::new(), mock.api_url(), mock.auth_url())
+            .with_credentials(CLIENT_ID, CLIENT_SECRET)
+            // only has to be set so ngrok isn't asked, nothing is delivered to it here
+            .with_webhook_base_url("http://127.0.0.1:1"),
+        ..Context::in_memory()
+    };
+
+    (mock, ctx)
+}
\ No newline at end of file
@@ -1,7 +1,10 @@
+use std::time::Duration;
+
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use actix_web::web;
 use actix_web_actors::ws;
+use serde::Deserialize;
 use twitch_sources_rework::common_data::eventsub_msgs::SubType;
 
 use crate::domain::subscription::Subscription;
@@ -15,7 +18,10 @@ use crate::util::session_state::TypedSession;
 pub struct WebsocketData {
     pub topic: &'static str,
     pub sub_types: &'static [SubType],
-    pub scopes: &'static [&'static str]
+    pub scopes: &'static [&'static str],
+    // progress events wait this long for newer ones of the same event, so bursts don't pile up
+    // in overlays. Every socket waits, not only ones that fall behind. Zero sends every one of them
+    pub coalesce_window: Duration
 }
 
 pub const WEBSOCKET_DATA_TYPES: &[WebsocketData] = &[
@@ -27,7 +33,8 @@ pub const WEBSOCKET_DATA_TYPES: &[WebsocketData] = &[
             SubType::ChannelPredictionLock,
             SubType::ChannelPredictionEnd,
         ],
-        scopes: &["channel:read:predictions"]
+        scopes: &["channel:read:predictions"],
+        coalesce_window: Duration::from_millis(500)
     },
 
     WebsocketData {
@@ -37,35 +44,206 @@ pub const WEBSOCKET_DATA_TYPES: &[WebsocketData] = &[
             SubType::HypeTrainProgress,
             SubType::HypeTrainEnd,
         ],
-        scopes: &["channel:read:hype_train"]
+        scopes: &["channel:read:hype_train"],
+        coalesce_window: Duration::from_secs(1)
+    },
+
+    WebsocketData {
+        topic: "poll",
+        sub_types: &[
+            SubType::ChannelPollBegin,
+            SubType::ChannelPollProgress,
+            SubType::ChannelPollEnd,
+        ],
+        scopes: &["channel:read:polls"],
+        coalesce_window: Duration::from_millis(500)
+    },
+
+    WebsocketData {
+        topic: "goals",
+        sub_types: &[
+            SubType::ChannelGoalBegin,
+            SubType::ChannelGoalProgress,
+            SubType::ChannelGoalEnd,
+        ],
+        scopes: &["channel:read:goals"],
+        coalesce_window: Duration::from_millis(500)
+    },
+
+    WebsocketData {
+        topic: "alerts",
+        sub_types: &[
+            SubType::ChannelFollow,
+            SubType::ChannelSubscribe,
+            SubType::ChannelSubscriptionMessage,
+            SubType::ChannelSubscriptionGift,
+            SubType::ChannelCheer,
+            SubType::ChannelRaid,
+        ],
+        scopes: &["moderator:read:followers", "channel:read:subscriptions", "bits:read"],
+        coalesce_window: Duration::ZERO
+    },
+
+    WebsocketData {
+        topic: "redemptions",
+        sub_types: &[
+            SubType::ChannelPointsCustomRewardRedemptionAdd,
+            SubType::ChannelPointsCustomRewardRedemptionUpdate,
+        ],
+        // also covers listing the rewards for the settings page
+        scopes: &["channel:read:redemptions"],
+        coalesce_window: Duration::ZERO
+    },
+
+    WebsocketData {
+        topic: "charity",
+        sub_types: &[
+            SubType::CharityCampaignStart,
+            SubType::CharityCampaignProgress,
+            SubType::CharityCampaignStop,
+            SubType::CharityDonation,
+        ],
+        scopes: &["channel:read:charity"],
+        coalesce_window: Duration::from_millis(500)
+    },
+
+    WebsocketData {
+        topic: "stream_status",
+        sub_types: &[
+            SubType::StreamOnline,
+            SubType::StreamOffline,
+            SubType::ChannelUpdate,
+        ],
+        // these are public events, no scopes needed
+        scopes: &[],
+        coalesce_window: Duration::ZERO
+    },
+
+    WebsocketData {
+        topic: "ad_break",
+        sub_types: &[
+            SubType::ChannelAdBreakBegin,
+        ],
+        scopes: &["channel:read:ads"],
+        coalesce_window: Duration::ZERO
     }
 ];
 
-pub async fn websocket_starter(
-    data: &WebsocketData,
-    req: HttpRequest,
-    session: TypedSession,
-    stream: web::Payload,
-    ctx: Context
-) -> Result<HttpResponse, MyErrors> {
-    let user_id = session.get_user_id()?.ok_or(MyErrors::AccessDenied)?;
+#[derive(Deserialize)]
+pub struct SourceQuery {
+    // sources from before the envelope don't send it
+    #[serde(default)]
+    protocol: u32,
+    // seq of the last event the source got, to get what it missed while reconnecting
+    since: Option<u64>
+}
 
-    let user = TwitchUser::get_user(&ctx, user_id).await?.ok_or(MyErrors::AccessDenied)?;
+fn check_scopes(data: &WebsocketData, user: &TwitchUser) -> Result<(), MyErrors> {
     if !data.scopes.iter().all(|scope| user.scopes.contains(&scope.to_string())) {
         return Err(MyErrors::AccessDenied);
     }
 
+    Ok(())
+}
+
+// checks the user can use the topic and makes sure twitch sends its events, gives the subscription ids
+pub(crate) async fn prepare_topic(
+    data: &WebsocketData,
+    user: &TwitchUser,
+    ctx: &Context
+) -> Result<Vec<String>, MyErrors> {
+    check_scopes(data, user)?;
+
+    subscribe_topic(data, user, ctx).await
+}
+
+async fn subscribe_topic(
+    data: &WebsocketData,
+    user: &TwitchUser,
+    ctx: &Context
+) -> Result<Vec<String>, MyErrors> {
     let subs = Subscription::get_or_create_subscriptions(
-        &ctx,
+        ctx,
         data.sub_types,
-        SubCondition::BroadcasterUserId(user_id.to_string()),
+        SubCondition::BroadcasterUserId(user.id.to_string()),
     )
         .await
         .map_err(|err| MyErrors::InternalServerError(err.to_string()))?;
 
-    let sub_ids = subs.into_iter().map(|sub| sub.sub_id().to_string()).collect();
+    Ok(subs.into_iter().map(|sub| sub.sub_id().to_string()).collect())
+}
+
+pub async fn websocket_starter(
+    data: &WebsocketData,
+    query: web::Query<SourceQuery>,
+    req: HttpRequest,
+    session: TypedSession,
+    stream: web::Payload,
+    ctx: Context
+) -> Result<HttpResponse, MyErrors> {
+    let user_id = session.get_user_id()?.ok_or(MyErrors::AccessDenied)?;
+
+    let user = TwitchUser::get_user(&ctx, user_id).await?.ok_or(MyErrors::AccessDenied)?;
+    let sub_ids = prepare_topic(data, &user, &ctx).await?;
     
-    let resp = ws::start(GenericPassthroughWs::new(user_id, data.topic, sub_ids, ctx.repository.clone()), &req, stream)?;
+    let resp = ws::start(
+        GenericPassthroughWs::new(user_id, data.topic, sub_ids, query.since, query.protocol, &ctx),
+        &req,
+        stream
+    )?;
     
+    Ok(resp)
+}
+
+#[derive(Deserialize)]
+pub struct MultiSourceQuery {
+    // comma separated, like `predictions,hype_train`
+    topics: String,
+    #[serde(default)]
+    protocol: u32
+}
+
+pub async fn multi_websocket_starter(
+    query: web::Query<MultiSourceQuery>,
+    req: HttpRequest,
+    session: TypedSession,
+    stream: web::Payload,
+    ctx: Context
+) -> Result<HttpResponse, MyErrors> {
+    let user_id = session.get_user_id()?.ok_or(MyErrors::AccessDenied)?;
+
+    let mut websocket_datas: Vec<&WebsocketData> = Vec::new();
+    for topic in query.topics.split(',').map(str::trim).filter(|topic| !topic.is_empty()) {
+        let data = WEBSOCKET_DATA_TYPES.iter().find(|data| data.topic == topic).ok_or(MyErrors::NotFound)?;
+
+        if !websocket_datas.iter().any(|added| added.topic == data.topic) {
+            websocket_datas.push(data);
+        }
+    }
+
+    if websocket_datas.is_empty() {
+        return Err(MyErrors::NotFound);
+    }
+
+    let user = TwitchUser::get_user(&ctx, user_id).await?.ok_or(MyErrors::AccessDenied)?;
+
+    // one topic missing a scope fails the socket, so nothing gets subscribed to before that's known
+    for data in &websocket_datas {
+        check_scopes(data, &user)?;
+    }
+
+    let mut sub_ids = Vec::new();
+    for data in &websocket_datas {
+        sub_ids.extend(subscribe_topic(data, &user, &ctx).await?);
+    }
+
+    let topics = websocket_datas.iter().map(|data| data.topic.to_string()).collect();
+
+    let resp = ws::start(
+        GenericPassthroughWs::multiplexed(user_id, topics, sub_ids, query.protocol, &ctx),
+        &req,
+        stream
+    )?;
+
     Ok(resp)
 }
\ No newline at end of file
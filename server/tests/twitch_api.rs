@@ -0,0 +1,296 @@
+use std::net::TcpListener;
+use std::time::Duration;
+
+use actix_session::SessionMiddleware;
+use actix_session::storage::CookieSessionStore;
+use actix_web::cookie::Key;
+use actix_web::dev::ServerHandle;
+use actix_web::web as web_ax;
+use actix_web::{App, HttpServer};
+use awc::ws::Frame;
+use futures::StreamExt;
+use paperclip::actix::OpenApiExt;
+use paperclip::actix::web;
+use serde_json::{json, Value};
+use server::{routes, websockets, REDIRECT_URL, WEBHOOK_URL};
+use server::http_client::HttpClient;
+use server::http_client::twitch_client::{
+    TwitchHttpClient, SubCondition, SubStatus, SubTransport, SubscribeRequestError, RefreshUserTokenError
+};
+use server::util::Context;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use twitch_mock::{MockTwitch, MockUser, WebhookMessage};
+use twitch_sources_rework::common_data::envelope::Envelope;
+use twitch_sources_rework::common_data::eventsub_msgs::{EventSubData, SubType};
+
+const CLIENT_ID: &str = "mock_client_id";
+const CLIENT_SECRET: &str = "mock_client_secret";
+
+async fn start_mock() -> (MockTwitch, HttpClient) {
+    let mock = MockTwitch::start(CLIENT_ID, CLIENT_SECRET).await.expect("Mock Twitch to start");
+    let client = HttpClient::with_base_urls(reqwest::Client::new(), mock.api_url(), mock.auth_url())
+        .with_credentials(CLIENT_ID, CLIENT_SECRET);
+
+    (mock, client)
+}
+
+// the routes a source goes through, on an in memory context that talks to the mock.
+// gives the base URL, twitch sends webhooks there
+async fn start_server(client: HttpClient) -> (String, ServerHandle) {
+    // the address has to be known before the context is made
+    let listener = TcpListener::bind(("127.0.0.1", 0)).expect("Local port to bind");
+    let base_url = format!("http://{}", listener.local_addr().expect("Listener to have an address"));
+
+    let ctx = Context {
+        http_client: client.with_webhook_base_url(&base_url),
+        ..Context::in_memory()
+    };
+
+    let poll = websockets::WEBSOCKET_DATA_TYPES.iter().find(|data| data.topic == "poll").expect("Poll topic to exist");
+    let secret_key = Key::generate();
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .wrap_api()
+            .wrap(SessionMiddleware::new(CookieSessionStore::default(), secret_key.clone()))
+            .app_data(ctx.clone())
+            .service(web::scope("/api").route("/request_login", web::get().to(routes::login_url)))
+            .route(REDIRECT_URL, web::get().to(routes::twitch_login_end))
+            .build()
+            .route(
+                "/ws/sources/poll",
+                web_ax::get().to(
+                    |query, req, session, stream, ctx| websockets::websocket_starter(poll, query, req, session, stream, ctx)
+                )
+            )
+            .route(WEBHOOK_URL, web_ax::post().to(routes::webhook))
+    })
+        .workers(1)
+        .disable_signals()
+        .listen(listener)
+        .expect("Server to listen")
+        .run();
+
+    let handle = server.handle();
+    actix_web::rt::spawn(server);
+
+    (base_url, handle)
+}
+
+#[actix_web::test]
+async fn subscriptions_lifecycle() {
+    let (mock, client) = start_mock().await;
+    let user = mock.add_user("Streamer");
+
+    let token = client.get_new_token().await.expect("App token").access_token;
+
+    let (sub, cost) = client.create_subscription(
+        SubCondition::BroadcasterUserId(user.id.clone()),
+        SubType::ChannelRaid,
+        SubTransport::webhook("http://127.0.0.1:1/webhook/", "some_long_secret"),
+        &token
+    ).await.expect("Subscription to be created");
+
+    assert!(sub.status == SubStatus::Enabled);
+    assert_eq!(sub.condition.to_broadcaster_user_id.as_deref(), Some(user.id.as_str()));
+    assert_eq!(cost.total, 1);
+    assert_eq!(mock.subscription(&sub.id).and_then(|sub| sub.transport.secret).as_deref(), Some("some_long_secret"));
+
+    let (subs, _) = client.list_subscriptions(&token).await.expect("Subscriptions list");
+    assert_eq!(subs.len(), 1);
+
+    client.delete_subscription(&sub.id, &token).await.expect("Subscription to be deleted");
+    // already deleted counts as deleted
+    client.delete_subscription(&sub.id, &token).await.expect("Second delete to be fine");
+    assert!(mock.subscriptions().is_empty());
+
+    mock.expire_tokens();
+    let result = client.create_subscription(
+        SubCondition::BroadcasterUserId(user.id.clone()),
+        SubType::ChannelRaid,
+        SubTransport::webhook("http://127.0.0.1:1/webhook/", "some_long_secret"),
+        &token
+    ).await;
+    assert!(matches!(result, Err(SubscribeRequestError::Unauthorized)));
+
+    mock.stop().await;
+}
+
+#[actix_web::test]
+async fn user_login_and_refresh() {
+    let (mock, client) = start_mock().await;
+    let user = mock.add_user("Streamer");
+
+    let code = mock.authorize(&user.id, &["channel:read:polls", "bits:read"]);
+    let tokens = client.get_user_token(&code, "http://localhost").await.expect("User token");
+    assert_eq!(tokens.scope, vec!["bits:read", "channel:read:polls"]);
+
+    let user_data = client.get_user_data(&tokens.access_token).await.expect("User data");
+    assert_eq!(user_data.id, user.id);
+    assert_eq!(user_data.login, "streamer");
+
+    // other users can be looked up by id, unknown ones are left out
+    let gifter = mock.add_user("Gifter");
+    let users = client.get_users(&tokens.access_token, &[gifter.id.clone(), "0".to_string()]).await.expect("Users");
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].display_name, "Gifter");
+
+    let validation = client.validate_token(&tokens.access_token).await.expect("Token to be valid");
+    assert_eq!(validation.user_id.as_deref(), Some(user.id.as_str()));
+
+    let refreshed = client.refresh_user_token(&tokens.refresh_token).await.expect("Token refresh");
+    assert_ne!(refreshed.access_token, tokens.access_token);
+
+    // refresh tokens are single use
+    let result = client.refresh_user_token(&tokens.refresh_token).await;
+    assert!(matches!(result, Err(RefreshUserTokenError::InvalidToken)));
+
+    mock.stop().await;
+}
+
+// logs in the way a browser does, the session cookie it gives is all a socket needs
+async fn log_in(mock: &MockTwitch, base_url: &str, user: &MockUser) -> String {
+    let browser = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Client to build");
+
+    let login: Value = browser.get(base_url.to_string() + "/api/request_login")
+        .query(&[("callback_url", "http://localhost/")])
+        .send().await.expect("Login request")
+        .json().await.expect("Login url");
+    let state = login["state"].as_str().expect("State to be there");
+
+    let code = mock.authorize(&user.id, &["channel:read:polls"]);
+    let response = browser.get(base_url.to_string() + REDIRECT_URL)
+        .query(&[("state", state), ("code", &code)])
+        .send().await.expect("Login end");
+    assert_eq!(response.status(), reqwest::StatusCode::SEE_OTHER);
+
+    response.headers().get(reqwest::header::SET_COOKIE)
+        .and_then(|cookie| cookie.to_str().ok())
+        .and_then(|cookie| cookie.split(';').next())
+        .expect("Session cookie")
+        .to_string()
+}
+
+fn poll_begin(user: &MockUser, poll_id: &str) -> Value {
+    let now = OffsetDateTime::now_utc();
+
+    json!({
+        "id": poll_id,
+        "broadcaster_user_id": user.id,
+        "broadcaster_user_login": user.login,
+        "broadcaster_user_name": user.display_name,
+        "title": "Aren't shoes just really hard socks?",
+        "choices": [],
+        "bits_voting": { "is_enabled": false, "amount_per_vote": 0 },
+        "channel_points_voting": { "is_enabled": false, "amount_per_vote": 0 },
+        "started_at": now.format(&Rfc3339).unwrap(),
+        "ends_at": (now + time::Duration::minutes(5)).format(&Rfc3339).unwrap()
+    })
+}
+
+#[actix_web::test]
+async fn webhook_notification_reaches_source_socket() {
+    let (mock, client) = start_mock().await;
+    let (base_url, server) = start_server(client).await;
+    let user = mock.add_user("Streamer");
+    let cookie = log_in(&mock, &base_url, &user).await;
+
+    let (_, mut socket) = awc::Client::new()
+        .ws(base_url.replacen("http", "ws", 1) + "/ws/sources/poll?protocol=1")
+        .header("Cookie", cookie)
+        .connect().await
+        .expect("Socket to connect");
+
+    // opening the socket subscribed to the topic
+    let sub = mock.find_subscription("channel.poll.begin").expect("Poll subscription");
+
+    let (status, _) = mock.send_notification(&sub.id, poll_begin(&user, "1243456")).await
+        .expect("Notification to be delivered");
+    assert!(status.is_success());
+
+    // comes as a snapshot if it was published before the socket listened
+    let frame = actix_web::rt::time::timeout(Duration::from_secs(5), socket.next()).await
+        .expect("Event in time")
+        .expect("Socket to stay open")
+        .expect("Frame to be read");
+
+    let envelope: Envelope = match frame {
+        Frame::Text(text) => serde_json::from_slice(&text).expect("Message to be an envelope"),
+        other => panic!("Expected a text frame, got {:?}", other),
+    };
+
+    match envelope.into_event().map(|event| event.data) {
+        Some(EventSubData::ChannelPollBegin(data)) => assert_eq!(data.id, "1243456"),
+        other => panic!("Expected the poll, got {:?}", other),
+    }
+
+    server.stop(false).await;
+    mock.stop().await;
+}
+
+#[actix_web::test]
+async fn webhook_rejects_forged_and_replayed_messages() {
+    let (mock, client) = start_mock().await;
+    let (base_url, server) = start_server(client).await;
+    let user = mock.add_user("Streamer");
+    let cookie = log_in(&mock, &base_url, &user).await;
+
+    let (_, mut socket) = awc::Client::new()
+        .ws(base_url.replacen("http", "ws", 1) + "/ws/sources/poll?protocol=1")
+        .header("Cookie", cookie)
+        .connect().await
+        .expect("Socket to connect");
+
+    let sub = mock.find_subscription("channel.poll.begin").expect("Poll subscription");
+    let now = OffsetDateTime::now_utc();
+
+    let mut forged = WebhookMessage::notification(&sub, poll_begin(&user, "forged"));
+    forged.signing_secret = Some("not_the_secret".to_string());
+    let (status, _) = mock.deliver(&sub.id, &forged).await.expect("Forged message to be delivered");
+    assert_eq!(status, reqwest::StatusCode::FORBIDDEN);
+
+    let mut stale = WebhookMessage::notification(&sub, poll_begin(&user, "stale"));
+    stale.timestamp = (now - time::Duration::minutes(11)).format(&Rfc3339).unwrap();
+    let (status, _) = mock.deliver(&sub.id, &stale).await.expect("Stale message to be delivered");
+    assert_eq!(status, reqwest::StatusCode::FORBIDDEN);
+
+    let mut future = WebhookMessage::notification(&sub, poll_begin(&user, "future"));
+    future.timestamp = (now + time::Duration::minutes(5)).format(&Rfc3339).unwrap();
+    let (status, _) = mock.deliver(&sub.id, &future).await.expect("Future message to be delivered");
+    assert_eq!(status, reqwest::StatusCode::FORBIDDEN);
+
+    // a little ahead is only the clocks not agreeing
+    let mut message = WebhookMessage::notification(&sub, poll_begin(&user, "1243456"));
+    message.timestamp = (now + time::Duration::seconds(10)).format(&Rfc3339).unwrap();
+    let (status, _) = mock.deliver(&sub.id, &message).await.expect("Message to be delivered");
+    assert!(status.is_success());
+
+    // redeliveries are acknowledged too, so twitch stops retrying
+    let (status, _) = mock.deliver(&sub.id, &message).await.expect("Message to be delivered again");
+    assert!(status.is_success());
+
+    // only the real message got through, and only once
+    let frame = actix_web::rt::time::timeout(Duration::from_secs(5), socket.next()).await
+        .expect("Event in time")
+        .expect("Socket to stay open")
+        .expect("Frame to be read");
+
+    let envelope: Envelope = match frame {
+        Frame::Text(text) => serde_json::from_slice(&text).expect("Message to be an envelope"),
+        other => panic!("Expected a text frame, got {:?}", other),
+    };
+
+    match envelope.into_event().map(|event| event.data) {
+        Some(EventSubData::ChannelPollBegin(data)) => assert_eq!(data.id, "1243456"),
+        other => panic!("Expected the poll, got {:?}", other),
+    }
+
+    let next = actix_web::rt::time::timeout(Duration::from_millis(500), socket.next()).await;
+    assert!(next.is_err(), "Expected nothing else, got {:?}", next);
+
+    server.stop(false).await;
+    mock.stop().await;
+}
\ No newline at end of file
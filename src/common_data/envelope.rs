@@ -0,0 +1,93 @@
+use serde::{Serialize, Deserialize};
+
+use super::eventsub_msgs::EventSubMessage;
+
+/// Newest protocol the sources speak, asked for with `?protocol=` on `/ws/sources/*`.
+/// Version 0 is the old one, where the socket sends bare events without an envelope.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum EnvelopeMessage {
+    /// An event as it happens
+    Event(EventSubMessage),
+    /// What was going on before the socket connected, sent right after connecting
+    Snapshot(EventSubMessage),
+    /// Something the server wants the source to know, nothing's wrong
+    Notice(String),
+    /// The server ran into a problem, usually followed by the socket closing
+    Error(String),
+    /// Sent periodically so sources can tell the connection is still alive
+    Heartbeat,
+}
+
+/// Every message on `/ws/sources/*` from protocol version 1 on.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Envelope {
+    pub version: u32,
+    /// RFC3339 time the server sent the message at
+    pub server_time: String,
+    /// Which topic the message is for, only set on `/ws/sources/multi`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// Number of the event within its topic, pass the last one as `?since=` when reconnecting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    #[serde(flatten)]
+    pub message: EnvelopeMessage,
+}
+
+impl Envelope {
+    pub fn new(version: u32, server_time: String, message: EnvelopeMessage) -> Self {
+        Self { version, server_time, topic: None, seq: None, message }
+    }
+
+    pub fn with_topic(self, topic: &str) -> Self {
+        Self { topic: Some(topic.to_string()), ..self }
+    }
+
+    pub fn with_seq(self, seq: u64) -> Self {
+        Self { seq: Some(seq), ..self }
+    }
+
+    /// The event the envelope carries, if it's about one.
+    pub fn into_event(self) -> Option<EventSubMessage> {
+        match self.message {
+            EnvelopeMessage::Event(event) | EnvelopeMessage::Snapshot(event) => Some(event),
+            EnvelopeMessage::Notice(_) | EnvelopeMessage::Error(_) | EnvelopeMessage::Heartbeat => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Envelope, EnvelopeMessage, PROTOCOL_VERSION};
+    use crate::common_data::eventsub_msgs::{EventSubMessage, SubType};
+
+    #[test]
+    fn event_round_trips() {
+        let event = EventSubMessage::new(&SubType::StreamOffline, "2020-10-11T10:11:12.123Z", serde_json::json!({
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User"
+        })).expect("Event to parse");
+
+        let envelope = Envelope::new(PROTOCOL_VERSION, "2020-10-11T10:11:13Z".to_string(), EnvelopeMessage::Snapshot(event));
+        let json = serde_json::to_value(&envelope).expect("Envelope to serialize");
+
+        assert_eq!(json["kind"], "snapshot");
+        assert!(json.get("topic").is_none());
+        assert_eq!(json["payload"]["data"]["StreamOffline"]["broadcaster_user_id"], "1337");
+
+        let parsed: Envelope = serde_json::from_value(json).expect("Envelope to deserialize");
+        assert!(parsed.into_event().is_some());
+    }
+
+    #[test]
+    fn heartbeat_has_no_payload() {
+        let json = serde_json::json!({ "version": 1, "server_time": "2020-10-11T10:11:13Z", "kind": "heartbeat" });
+
+        let parsed: Envelope = serde_json::from_value(json).expect("Heartbeat to deserialize");
+        assert!(matches!(parsed.message, EnvelopeMessage::Heartbeat));
+    }
+}
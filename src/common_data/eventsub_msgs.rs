@@ -6,6 +6,13 @@ use anyhow::Result;
 pub use auth_revoke::*;
 pub use channel_predictions::*;
 pub use hype_train::*;
+pub use channel_polls::*;
+pub use channel_goals::*;
+pub use alerts::*;
+pub use channel_points::*;
+pub use charity::*;
+pub use stream_status::*;
+pub use ad_break::*;
 
 mod auth_revoke {
     use serde::{Serialize, Deserialize};
@@ -171,6 +178,487 @@ mod hype_train {
     }
 }
 
+mod channel_polls {
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct PollChoice {
+        pub id: String,
+        pub title: String,
+        #[serde(default)]
+        pub bits_votes: i64,
+        #[serde(default)]
+        pub channel_points_votes: i64,
+        #[serde(default)]
+        pub votes: i64
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct PollVotingSettings {
+        pub is_enabled: bool,
+        pub amount_per_vote: i64
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all="snake_case")]
+    pub enum PollEndStatus {
+        Completed,
+        Archived,
+        Terminated
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelPollBegin {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub title: String,
+        pub choices: Vec<PollChoice>,
+        pub bits_voting: PollVotingSettings,
+        pub channel_points_voting: PollVotingSettings,
+        pub started_at: String,
+        pub ends_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelPollProgress {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub title: String,
+        pub choices: Vec<PollChoice>,
+        pub bits_voting: PollVotingSettings,
+        pub channel_points_voting: PollVotingSettings,
+        pub started_at: String,
+        pub ends_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelPollEnd {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub title: String,
+        pub choices: Vec<PollChoice>,
+        pub bits_voting: PollVotingSettings,
+        pub channel_points_voting: PollVotingSettings,
+        pub status: PollEndStatus,
+        pub started_at: String,
+        pub ended_at: String
+    }
+}
+
+mod channel_goals {
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    #[serde(rename_all="snake_case")]
+    pub enum GoalType {
+        Follow,
+        Subscription,
+        SubscriptionCount,
+        NewSubscription,
+        NewSubscriptionCount,
+        NewBit,
+        NewCheerer,
+        #[serde(other)]
+        Other
+    }
+
+    impl GoalType {
+        pub fn name(&self) -> &'static str {
+            match self {
+                GoalType::Follow => "followers",
+                GoalType::Subscription => "sub points",
+                GoalType::SubscriptionCount => "subs",
+                GoalType::NewSubscription => "new sub points",
+                GoalType::NewSubscriptionCount => "new subs",
+                GoalType::NewBit => "bits",
+                GoalType::NewCheerer => "cheerers",
+                GoalType::Other => "",
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelGoalBegin {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        #[serde(rename="type")]
+        pub type_: GoalType,
+        pub description: String,
+        pub current_amount: i64,
+        pub target_amount: i64,
+        pub started_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelGoalProgress {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        #[serde(rename="type")]
+        pub type_: GoalType,
+        pub description: String,
+        pub current_amount: i64,
+        pub target_amount: i64,
+        pub started_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelGoalEnd {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        #[serde(rename="type")]
+        pub type_: GoalType,
+        pub description: String,
+        pub is_achieved: bool,
+        pub current_amount: i64,
+        pub target_amount: i64,
+        pub started_at: String,
+        pub ended_at: String
+    }
+}
+
+mod alerts {
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelFollow {
+        pub user_id: String,
+        pub user_login: String,
+        pub user_name: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub followed_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelSubscribe {
+        pub user_id: String,
+        pub user_login: String,
+        pub user_name: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub tier: String,
+        pub is_gift: bool
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct SubscriptionMessageEmote {
+        pub begin: i64,
+        pub end: i64,
+        pub id: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct SubscriptionMessage {
+        pub text: String,
+        #[serde(default)]
+        pub emotes: Option<Vec<SubscriptionMessageEmote>>
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelSubscriptionMessage {
+        pub user_id: String,
+        pub user_login: String,
+        pub user_name: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub tier: String,
+        pub message: SubscriptionMessage,
+        pub cumulative_months: i64,
+        pub streak_months: Option<i64>,
+        pub duration_months: i64
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelSubscriptionGift {
+        // user fields are null for anonymous gifts
+        pub user_id: Option<String>,
+        pub user_login: Option<String>,
+        pub user_name: Option<String>,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub total: i64,
+        pub tier: String,
+        pub cumulative_total: Option<i64>,
+        pub is_anonymous: bool
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelCheer {
+        pub is_anonymous: bool,
+        pub user_id: Option<String>,
+        pub user_login: Option<String>,
+        pub user_name: Option<String>,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub message: String,
+        pub bits: i64
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelRaid {
+        pub from_broadcaster_user_id: String,
+        pub from_broadcaster_user_login: String,
+        pub from_broadcaster_user_name: String,
+        pub to_broadcaster_user_id: String,
+        pub to_broadcaster_user_login: String,
+        pub to_broadcaster_user_name: String,
+        pub viewers: i64
+    }
+}
+
+mod channel_points {
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    #[serde(rename_all="snake_case")]
+    pub enum RedemptionStatus {
+        Unfulfilled,
+        Fulfilled,
+        Canceled,
+        #[serde(other)]
+        Unknown
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct RedemptionReward {
+        pub id: String,
+        pub title: String,
+        pub cost: i64,
+        pub prompt: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelPointsCustomRewardRedemptionAdd {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub user_id: String,
+        pub user_login: String,
+        pub user_name: String,
+        pub user_input: String,
+        pub status: RedemptionStatus,
+        pub reward: RedemptionReward,
+        pub redeemed_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelPointsCustomRewardRedemptionUpdate {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub user_id: String,
+        pub user_login: String,
+        pub user_name: String,
+        pub user_input: String,
+        pub status: RedemptionStatus,
+        pub reward: RedemptionReward,
+        pub redeemed_at: String
+    }
+}
+
+mod charity {
+    use serde::{Serialize, Deserialize};
+
+    // amounts are sent as an integer with the count of decimal places, $5.50 is 550 with 2 decimal places
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct CharityAmount {
+        pub value: i64,
+        pub decimal_places: u32,
+        pub currency: String
+    }
+
+    impl CharityAmount {
+        pub fn as_f64(&self) -> f64 {
+            self.value as f64 / 10f64.powi(self.decimal_places as i32)
+        }
+
+        pub fn formatted(&self) -> String {
+            let divisor = 10i64.pow(self.decimal_places);
+            let whole = self.value / divisor;
+            let fraction = (self.value % divisor).abs();
+
+            let number = if self.decimal_places == 0 {
+                whole.to_string()
+            } else {
+                format!("{}.{:0width$}", whole, fraction, width = self.decimal_places as usize)
+            };
+
+            let symbol = match self.currency.as_str() {
+                "USD" | "CAD" | "AUD" | "NZD" => Some("$"),
+                "EUR" => Some("€"),
+                "GBP" => Some("£"),
+                "JPY" => Some("¥"),
+                _ => None
+            };
+
+            match symbol {
+                Some(symbol) => format!("{}{}", symbol, number),
+                None => format!("{} {}", number, self.currency),
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct CharityCampaignStart {
+        pub id: String,
+        pub broadcaster_id: String,
+        pub broadcaster_login: String,
+        pub broadcaster_name: String,
+        pub charity_name: String,
+        pub charity_description: String,
+        pub charity_logo: String,
+        pub charity_website: String,
+        pub current_amount: CharityAmount,
+        pub target_amount: CharityAmount,
+        pub started_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct CharityCampaignProgress {
+        pub id: String,
+        pub broadcaster_id: String,
+        pub broadcaster_login: String,
+        pub broadcaster_name: String,
+        pub charity_name: String,
+        pub charity_description: String,
+        pub charity_logo: String,
+        pub charity_website: String,
+        pub current_amount: CharityAmount,
+        pub target_amount: CharityAmount
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct CharityCampaignStop {
+        pub id: String,
+        pub broadcaster_id: String,
+        pub broadcaster_login: String,
+        pub broadcaster_name: String,
+        pub charity_name: String,
+        pub charity_description: String,
+        pub charity_logo: String,
+        pub charity_website: String,
+        pub current_amount: CharityAmount,
+        pub target_amount: CharityAmount,
+        pub stopped_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct CharityDonation {
+        pub id: String,
+        pub campaign_id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub user_id: String,
+        pub user_login: String,
+        pub user_name: String,
+        pub charity_name: String,
+        pub charity_description: String,
+        pub charity_logo: String,
+        pub charity_website: String,
+        pub amount: CharityAmount
+    }
+}
+
+mod stream_status {
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    #[serde(rename_all="snake_case")]
+    pub enum StreamType {
+        Live,
+        Playlist,
+        WatchParty,
+        Premiere,
+        Rerun,
+        #[serde(other)]
+        Other
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct StreamOnline {
+        pub id: String,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        #[serde(rename="type")]
+        pub type_: StreamType,
+        pub started_at: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct StreamOffline {
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelUpdate {
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub title: String,
+        pub language: String,
+        pub category_id: String,
+        pub category_name: String,
+        #[serde(default)]
+        pub content_classification_labels: Vec<String>
+    }
+}
+
+mod ad_break {
+    use serde::{Serialize, Deserialize, Deserializer};
+
+    // twitch documents this as a number, but the examples send it as a string, so take both
+    fn number_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum NumberOrString {
+            Number(i64),
+            String(String)
+        }
+
+        match NumberOrString::deserialize(deserializer)? {
+            NumberOrString::Number(number) => Ok(number),
+            NumberOrString::String(string) => string.parse().map_err(serde::de::Error::custom),
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ChannelAdBreakBegin {
+        #[serde(deserialize_with = "number_or_string")]
+        pub duration_seconds: i64,
+        pub started_at: String,
+        pub is_automatic: bool,
+        pub broadcaster_user_id: String,
+        pub broadcaster_user_login: String,
+        pub broadcaster_user_name: String,
+        pub requester_user_id: String,
+        pub requester_user_login: String,
+        pub requester_user_name: String
+    }
+}
+
 // the wrapper for the actual data
 #[derive(Debug, Serialize, Deserialize)]
 pub enum EventSubData {
@@ -182,6 +670,28 @@ pub enum EventSubData {
     HypeTrainBegin(HypeTrainBegin),
     HypeTrainProgress(HypeTrainProgress),
     HypeTrainEnd(HypeTrainEnd),
+    ChannelPollBegin(ChannelPollBegin),
+    ChannelPollProgress(ChannelPollProgress),
+    ChannelPollEnd(ChannelPollEnd),
+    ChannelGoalBegin(ChannelGoalBegin),
+    ChannelGoalProgress(ChannelGoalProgress),
+    ChannelGoalEnd(ChannelGoalEnd),
+    ChannelFollow(ChannelFollow),
+    ChannelSubscribe(ChannelSubscribe),
+    ChannelSubscriptionMessage(ChannelSubscriptionMessage),
+    ChannelSubscriptionGift(ChannelSubscriptionGift),
+    ChannelCheer(ChannelCheer),
+    ChannelRaid(ChannelRaid),
+    ChannelPointsCustomRewardRedemptionAdd(ChannelPointsCustomRewardRedemptionAdd),
+    ChannelPointsCustomRewardRedemptionUpdate(ChannelPointsCustomRewardRedemptionUpdate),
+    CharityCampaignStart(CharityCampaignStart),
+    CharityCampaignProgress(CharityCampaignProgress),
+    CharityCampaignStop(CharityCampaignStop),
+    CharityDonation(CharityDonation),
+    StreamOnline(StreamOnline),
+    StreamOffline(StreamOffline),
+    ChannelUpdate(ChannelUpdate),
+    ChannelAdBreakBegin(ChannelAdBreakBegin),
 }
 
 impl EventSubData {
@@ -195,6 +705,28 @@ impl EventSubData {
             EventSubData::HypeTrainBegin(data) => &data.data.broadcaster_user_id,
             EventSubData::HypeTrainProgress(data) => &data.data.broadcaster_user_id,
             EventSubData::HypeTrainEnd(data) => &data.data.broadcaster_user_id,
+            EventSubData::ChannelPollBegin(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelPollProgress(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelPollEnd(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelGoalBegin(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelGoalProgress(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelGoalEnd(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelFollow(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelSubscribe(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelSubscriptionMessage(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelSubscriptionGift(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelCheer(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelRaid(data) => &data.to_broadcaster_user_id,
+            EventSubData::ChannelPointsCustomRewardRedemptionAdd(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelPointsCustomRewardRedemptionUpdate(data) => &data.broadcaster_user_id,
+            EventSubData::CharityCampaignStart(data) => &data.broadcaster_id,
+            EventSubData::CharityCampaignProgress(data) => &data.broadcaster_id,
+            EventSubData::CharityCampaignStop(data) => &data.broadcaster_id,
+            EventSubData::CharityDonation(data) => &data.broadcaster_user_id,
+            EventSubData::StreamOnline(data) => &data.broadcaster_user_id,
+            EventSubData::StreamOffline(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelUpdate(data) => &data.broadcaster_user_id,
+            EventSubData::ChannelAdBreakBegin(data) => &data.broadcaster_user_id,
         };
 
         target
@@ -210,6 +742,28 @@ impl EventSubData {
             EventSubData::HypeTrainBegin(_) => SubType::HypeTrainBegin,
             EventSubData::HypeTrainProgress(_) => SubType::HypeTrainProgress,
             EventSubData::HypeTrainEnd(_) => SubType::HypeTrainEnd,
+            EventSubData::ChannelPollBegin(_) => SubType::ChannelPollBegin,
+            EventSubData::ChannelPollProgress(_) => SubType::ChannelPollProgress,
+            EventSubData::ChannelPollEnd(_) => SubType::ChannelPollEnd,
+            EventSubData::ChannelGoalBegin(_) => SubType::ChannelGoalBegin,
+            EventSubData::ChannelGoalProgress(_) => SubType::ChannelGoalProgress,
+            EventSubData::ChannelGoalEnd(_) => SubType::ChannelGoalEnd,
+            EventSubData::ChannelFollow(_) => SubType::ChannelFollow,
+            EventSubData::ChannelSubscribe(_) => SubType::ChannelSubscribe,
+            EventSubData::ChannelSubscriptionMessage(_) => SubType::ChannelSubscriptionMessage,
+            EventSubData::ChannelSubscriptionGift(_) => SubType::ChannelSubscriptionGift,
+            EventSubData::ChannelCheer(_) => SubType::ChannelCheer,
+            EventSubData::ChannelRaid(_) => SubType::ChannelRaid,
+            EventSubData::ChannelPointsCustomRewardRedemptionAdd(_) => SubType::ChannelPointsCustomRewardRedemptionAdd,
+            EventSubData::ChannelPointsCustomRewardRedemptionUpdate(_) => SubType::ChannelPointsCustomRewardRedemptionUpdate,
+            EventSubData::CharityCampaignStart(_) => SubType::CharityCampaignStart,
+            EventSubData::CharityCampaignProgress(_) => SubType::CharityCampaignProgress,
+            EventSubData::CharityCampaignStop(_) => SubType::CharityCampaignStop,
+            EventSubData::CharityDonation(_) => SubType::CharityDonation,
+            EventSubData::StreamOnline(_) => SubType::StreamOnline,
+            EventSubData::StreamOffline(_) => SubType::StreamOffline,
+            EventSubData::ChannelUpdate(_) => SubType::ChannelUpdate,
+            EventSubData::ChannelAdBreakBegin(_) => SubType::ChannelAdBreakBegin,
         };
 
         sub_type
@@ -234,11 +788,66 @@ pub enum SubType {
     #[serde(rename="channel.hype_train.progress")]
     HypeTrainProgress,
     #[serde(rename="channel.hype_train.end")]
-    HypeTrainEnd
+    HypeTrainEnd,
+    #[serde(rename="channel.poll.begin")]
+    ChannelPollBegin,
+    #[serde(rename="channel.poll.progress")]
+    ChannelPollProgress,
+    #[serde(rename="channel.poll.end")]
+    ChannelPollEnd,
+    #[serde(rename="channel.goal.begin")]
+    ChannelGoalBegin,
+    #[serde(rename="channel.goal.progress")]
+    ChannelGoalProgress,
+    #[serde(rename="channel.goal.end")]
+    ChannelGoalEnd,
+    #[serde(rename="channel.follow")]
+    ChannelFollow,
+    #[serde(rename="channel.subscribe")]
+    ChannelSubscribe,
+    #[serde(rename="channel.subscription.message")]
+    ChannelSubscriptionMessage,
+    #[serde(rename="channel.subscription.gift")]
+    ChannelSubscriptionGift,
+    #[serde(rename="channel.cheer")]
+    ChannelCheer,
+    #[serde(rename="channel.raid")]
+    ChannelRaid,
+    #[serde(rename="channel.channel_points_custom_reward_redemption.add")]
+    ChannelPointsCustomRewardRedemptionAdd,
+    #[serde(rename="channel.channel_points_custom_reward_redemption.update")]
+    ChannelPointsCustomRewardRedemptionUpdate,
+    #[serde(rename="channel.charity_campaign.start")]
+    CharityCampaignStart,
+    #[serde(rename="channel.charity_campaign.progress")]
+    CharityCampaignProgress,
+    #[serde(rename="channel.charity_campaign.stop")]
+    CharityCampaignStop,
+    #[serde(rename="channel.charity_campaign.donate")]
+    CharityDonation,
+    #[serde(rename="stream.online")]
+    StreamOnline,
+    #[serde(rename="stream.offline")]
+    StreamOffline,
+    #[serde(rename="channel.update")]
+    ChannelUpdate,
+    #[serde(rename="channel.ad_break.begin")]
+    ChannelAdBreakBegin
 }
 
-impl fmt::Display for SubType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl SubType {
+    // payloads in here are modeled after these versions of the types
+    pub fn version(&self) -> &'static str {
+        match self {
+            SubType::ChannelFollow => "2",
+            SubType::ChannelUpdate => "2",
+            _ => "1"
+        }
+    }
+}
+
+impl fmt::Display for SubType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", serde_json::ser::to_string(self).expect("Serialization shouldn't fail"))
     }
 }
@@ -298,6 +907,94 @@ impl EventSubMessage {
                 data: EventSubData::HypeTrainEnd(serde_json::de::from_str(&data.to_string())?),
                 msg_time: msg_time.to_string()
             },
+            SubType::ChannelPollBegin => EventSubMessage {
+                data: EventSubData::ChannelPollBegin(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelPollProgress => EventSubMessage {
+                data: EventSubData::ChannelPollProgress(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelPollEnd => EventSubMessage {
+                data: EventSubData::ChannelPollEnd(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelGoalBegin => EventSubMessage {
+                data: EventSubData::ChannelGoalBegin(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelGoalProgress => EventSubMessage {
+                data: EventSubData::ChannelGoalProgress(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelGoalEnd => EventSubMessage {
+                data: EventSubData::ChannelGoalEnd(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelFollow => EventSubMessage {
+                data: EventSubData::ChannelFollow(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelSubscribe => EventSubMessage {
+                data: EventSubData::ChannelSubscribe(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelSubscriptionMessage => EventSubMessage {
+                data: EventSubData::ChannelSubscriptionMessage(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelSubscriptionGift => EventSubMessage {
+                data: EventSubData::ChannelSubscriptionGift(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelCheer => EventSubMessage {
+                data: EventSubData::ChannelCheer(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelRaid => EventSubMessage {
+                data: EventSubData::ChannelRaid(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelPointsCustomRewardRedemptionAdd => EventSubMessage {
+                data: EventSubData::ChannelPointsCustomRewardRedemptionAdd(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelPointsCustomRewardRedemptionUpdate => EventSubMessage {
+                data: EventSubData::ChannelPointsCustomRewardRedemptionUpdate(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::CharityCampaignStart => EventSubMessage {
+                data: EventSubData::CharityCampaignStart(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::CharityCampaignProgress => EventSubMessage {
+                data: EventSubData::CharityCampaignProgress(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::CharityCampaignStop => EventSubMessage {
+                data: EventSubData::CharityCampaignStop(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::CharityDonation => EventSubMessage {
+                data: EventSubData::CharityDonation(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::StreamOnline => EventSubMessage {
+                data: EventSubData::StreamOnline(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::StreamOffline => EventSubMessage {
+                data: EventSubData::StreamOffline(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelUpdate => EventSubMessage {
+                data: EventSubData::ChannelUpdate(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
+            SubType::ChannelAdBreakBegin => EventSubMessage {
+                data: EventSubData::ChannelAdBreakBegin(serde_json::de::from_str(&data.to_string())?),
+                msg_time: msg_time.to_string()
+            },
         };
 
         Ok(res)
@@ -1130,4 +1827,713 @@ mod tests {
             assert!(res.is_ok(), "Expected `ChannelPredictionEnd` to parse, got {:?}", res);
         }
     }
-}
\ No newline at end of file
+
+    mod channel_poll_begin {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "1243456",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "title": "Aren’t shoes just really hard socks?",
+                "choices": [
+                    {"id": "123", "title": "Yeah!"},
+                    {"id": "124", "title": "No!"},
+                    {"id": "125", "title": "Maybe!"}
+                ],
+                "bits_voting": {
+                    "is_enabled": true,
+                    "amount_per_vote": 10
+                },
+                "channel_points_voting": {
+                    "is_enabled": true,
+                    "amount_per_vote": 10
+                },
+                "started_at": "2020-07-15T17:16:03.17106713Z",
+                "ends_at": "2020-07-15T17:16:08.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelPollBegin, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelPollBegin` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_poll_progress {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "1243456",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "title": "Aren’t shoes just really hard socks?",
+                "choices": [
+                    {"id": "123", "title": "Yeah!", "bits_votes": 5, "channel_points_votes": 7, "votes": 12},
+                    {"id": "124", "title": "No!", "bits_votes": 10, "channel_points_votes": 4, "votes": 14},
+                    {"id": "125", "title": "Maybe!", "bits_votes": 0, "channel_points_votes": 7, "votes": 7}
+                ],
+                "bits_voting": {
+                    "is_enabled": true,
+                    "amount_per_vote": 10
+                },
+                "channel_points_voting": {
+                    "is_enabled": true,
+                    "amount_per_vote": 10
+                },
+                "started_at": "2020-07-15T17:16:03.17106713Z",
+                "ends_at": "2020-07-15T17:16:08.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelPollProgress, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelPollProgress` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_poll_end {
+        use super::*;
+
+        #[test]
+        fn serializes_completed() {
+            let json_val = serde_json::json!({
+                "id": "1243456",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "title": "Aren’t shoes just really hard socks?",
+                "choices": [
+                    {"id": "123", "title": "Blue", "bits_votes": 50, "channel_points_votes": 70, "votes": 120},
+                    {"id": "124", "title": "Yellow", "bits_votes": 100, "channel_points_votes": 40, "votes": 140},
+                    {"id": "125", "title": "Green", "bits_votes": 10, "channel_points_votes": 70, "votes": 80}
+                ],
+                "bits_voting": {
+                    "is_enabled": true,
+                    "amount_per_vote": 10
+                },
+                "channel_points_voting": {
+                    "is_enabled": true,
+                    "amount_per_vote": 10
+                },
+                "status": "completed",
+                "started_at": "2020-07-15T17:16:03.17106713Z",
+                "ended_at": "2020-07-15T17:16:11.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelPollEnd, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelPollEnd` to parse, got {:?}", res);
+        }
+
+        #[test]
+        fn serializes_terminated_no_votes() {
+            let json_val = serde_json::json!({
+                "id": "1243456",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "title": "Aren’t shoes just really hard socks?",
+                "choices": [
+                    {"id": "123", "title": "Blue", "bits_votes": 0, "channel_points_votes": 0, "votes": 0},
+                    {"id": "124", "title": "Yellow", "bits_votes": 0, "channel_points_votes": 0, "votes": 0}
+                ],
+                "bits_voting": {
+                    "is_enabled": false,
+                    "amount_per_vote": 0
+                },
+                "channel_points_voting": {
+                    "is_enabled": false,
+                    "amount_per_vote": 0
+                },
+                "status": "terminated",
+                "started_at": "2020-07-15T17:16:03.17106713Z",
+                "ended_at": "2020-07-15T17:16:11.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelPollEnd, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelPollEnd` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_goal_begin {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "12345-cool-event",
+                "broadcaster_user_id": "141981764",
+                "broadcaster_user_name": "TwitchDev",
+                "broadcaster_user_login": "twitchdev",
+                "type": "subscription",
+                "description": "Help me get partner!",
+                "current_amount": 100,
+                "target_amount": 220,
+                "started_at": "2021-07-15T17:16:03.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelGoalBegin, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelGoalBegin` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_goal_progress {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "12345-cool-event",
+                "broadcaster_user_id": "141981764",
+                "broadcaster_user_name": "TwitchDev",
+                "broadcaster_user_login": "twitchdev",
+                "type": "subscription",
+                "description": "Help me get partner!",
+                "current_amount": 120,
+                "target_amount": 220,
+                "started_at": "2021-07-15T17:16:03.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelGoalProgress, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelGoalProgress` to parse, got {:?}", res);
+        }
+
+        #[test]
+        fn serializes_unknown_type() {
+            let json_val = serde_json::json!({
+                "id": "12345-cool-event",
+                "broadcaster_user_id": "141981764",
+                "broadcaster_user_name": "TwitchDev",
+                "broadcaster_user_login": "twitchdev",
+                "type": "some_new_goal_type",
+                "description": "",
+                "current_amount": 120,
+                "target_amount": 220,
+                "started_at": "2021-07-15T17:16:03.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelGoalProgress, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelGoalProgress` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_goal_end {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "12345-abc-678-defgh",
+                "broadcaster_user_id": "141981764",
+                "broadcaster_user_name": "TwitchDev",
+                "broadcaster_user_login": "twitchdev",
+                "type": "subscription",
+                "description": "Help me get partner!",
+                "is_achieved": false,
+                "current_amount": 180,
+                "target_amount": 220,
+                "started_at": "2021-07-15T17:16:03.17106713Z",
+                "ended_at": "2020-07-16T17:16:03.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelGoalEnd, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelGoalEnd` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_follow {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "user_id": "1234",
+                "user_login": "cool_user",
+                "user_name": "Cool_User",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cooler_user",
+                "broadcaster_user_name": "Cooler_User",
+                "followed_at": "2020-07-15T18:16:11.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelFollow, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelFollow` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_subscribe {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "user_id": "1234",
+                "user_login": "cool_user",
+                "user_name": "Cool_User",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cooler_user",
+                "broadcaster_user_name": "Cooler_User",
+                "tier": "1000",
+                "is_gift": false
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelSubscribe, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelSubscribe` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_subscription_message {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "user_id": "1234",
+                "user_login": "cool_user",
+                "user_name": "Cool_User",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cooler_user",
+                "broadcaster_user_name": "Cooler_User",
+                "tier": "1000",
+                "message": {
+                    "text": "Love the stream! FevziGG",
+                    "emotes": [
+                        {
+                            "begin": 23,
+                            "end": 30,
+                            "id": "302976485"
+                        }
+                    ]
+                },
+                "cumulative_months": 15,
+                "streak_months": 1,
+                "duration_months": 6
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelSubscriptionMessage, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelSubscriptionMessage` to parse, got {:?}", res);
+        }
+
+        #[test]
+        fn serializes_no_streak_no_emotes() {
+            let json_val = serde_json::json!({
+                "user_id": "1234",
+                "user_login": "cool_user",
+                "user_name": "Cool_User",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cooler_user",
+                "broadcaster_user_name": "Cooler_User",
+                "tier": "2000",
+                "message": {
+                    "text": "",
+                    "emotes": null
+                },
+                "cumulative_months": 3,
+                "streak_months": null,
+                "duration_months": 1
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelSubscriptionMessage, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelSubscriptionMessage` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_subscription_gift {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "user_id": "1234",
+                "user_login": "cool_user",
+                "user_name": "Cool_User",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cooler_user",
+                "broadcaster_user_name": "Cooler_User",
+                "total": 2,
+                "tier": "1000",
+                "cumulative_total": 284,
+                "is_anonymous": false
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelSubscriptionGift, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelSubscriptionGift` to parse, got {:?}", res);
+        }
+
+        #[test]
+        fn serializes_anonymous() {
+            let json_val = serde_json::json!({
+                "user_id": null,
+                "user_login": null,
+                "user_name": null,
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cooler_user",
+                "broadcaster_user_name": "Cooler_User",
+                "total": 5,
+                "tier": "1000",
+                "cumulative_total": null,
+                "is_anonymous": true
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelSubscriptionGift, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelSubscriptionGift` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_cheer {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "is_anonymous": false,
+                "user_id": "1234",
+                "user_login": "cool_user",
+                "user_name": "Cool_User",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cooler_user",
+                "broadcaster_user_name": "Cooler_User",
+                "message": "pogchamp",
+                "bits": 1000
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelCheer, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelCheer` to parse, got {:?}", res);
+        }
+
+        #[test]
+        fn serializes_anonymous() {
+            let json_val = serde_json::json!({
+                "is_anonymous": true,
+                "user_id": null,
+                "user_login": null,
+                "user_name": null,
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cooler_user",
+                "broadcaster_user_name": "Cooler_User",
+                "message": "Cheer100",
+                "bits": 100
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelCheer, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelCheer` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_raid {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "from_broadcaster_user_id": "1234",
+                "from_broadcaster_user_login": "cool_user",
+                "from_broadcaster_user_name": "Cool_User",
+                "to_broadcaster_user_id": "1337",
+                "to_broadcaster_user_login": "cooler_user",
+                "to_broadcaster_user_name": "Cooler_User",
+                "viewers": 9001
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelRaid, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelRaid` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_points_custom_reward_redemption_add {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "17fa2df1-ad76-4804-bfa5-a40ef63efe63",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "user_id": "9001",
+                "user_login": "cooler_user",
+                "user_name": "Cooler_User",
+                "user_input": "pogchamp",
+                "status": "unfulfilled",
+                "reward": {
+                    "id": "92af127c-7326-4483-a52b-b0da0be61c01",
+                    "title": "title",
+                    "cost": 100,
+                    "prompt": "reward prompt"
+                },
+                "redeemed_at": "2020-07-15T17:16:03.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelPointsCustomRewardRedemptionAdd, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelPointsCustomRewardRedemptionAdd` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_points_custom_reward_redemption_update {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "17fa2df1-ad76-4804-bfa5-a40ef63efe63",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "user_id": "9001",
+                "user_login": "cooler_user",
+                "user_name": "Cooler_User",
+                "user_input": "",
+                "status": "fulfilled",
+                "reward": {
+                    "id": "92af127c-7326-4483-a52b-b0da0be61c01",
+                    "title": "title",
+                    "cost": 100,
+                    "prompt": "reward prompt"
+                },
+                "redeemed_at": "2020-07-15T17:16:03.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelPointsCustomRewardRedemptionUpdate, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelPointsCustomRewardRedemptionUpdate` to parse, got {:?}", res);
+        }
+    }
+
+    mod charity_campaign_start {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "123-abc-456-def",
+                "broadcaster_id": "123456",
+                "broadcaster_name": "SunnySideUp",
+                "broadcaster_login": "sunnysideup",
+                "charity_name": "Example name",
+                "charity_description": "Example description",
+                "charity_logo": "https://abc.cloudfront.net/ppgf/1000/100.png",
+                "charity_website": "https://www.example.com",
+                "current_amount": {
+                    "value": 260000,
+                    "decimal_places": 2,
+                    "currency": "USD"
+                },
+                "target_amount": {
+                    "value": 1500000,
+                    "decimal_places": 2,
+                    "currency": "USD"
+                },
+                "started_at": "2022-07-26T17:00:03.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::CharityCampaignStart, "", json_val);
+            assert!(res.is_ok(), "Expected `CharityCampaignStart` to parse, got {:?}", res);
+        }
+    }
+
+    mod charity_campaign_progress {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "123-abc-456-def",
+                "broadcaster_id": "123456",
+                "broadcaster_name": "SunnySideUp",
+                "broadcaster_login": "sunnysideup",
+                "charity_name": "Example name",
+                "charity_description": "Example description",
+                "charity_logo": "https://abc.cloudfront.net/ppgf/1000/100.png",
+                "charity_website": "https://www.example.com",
+                "current_amount": {
+                    "value": 260000,
+                    "decimal_places": 2,
+                    "currency": "USD"
+                },
+                "target_amount": {
+                    "value": 1500000,
+                    "decimal_places": 2,
+                    "currency": "USD"
+                }
+            });
+
+            let res = EventSubMessage::new(&SubType::CharityCampaignProgress, "", json_val);
+            assert!(res.is_ok(), "Expected `CharityCampaignProgress` to parse, got {:?}", res);
+        }
+    }
+
+    mod charity_campaign_stop {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "123-abc-456-def",
+                "broadcaster_id": "123456",
+                "broadcaster_name": "SunnySideUp",
+                "broadcaster_login": "sunnysideup",
+                "charity_name": "Example name",
+                "charity_description": "Example description",
+                "charity_logo": "https://abc.cloudfront.net/ppgf/1000/100.png",
+                "charity_website": "https://www.example.com",
+                "current_amount": {
+                    "value": 260000,
+                    "decimal_places": 2,
+                    "currency": "USD"
+                },
+                "target_amount": {
+                    "value": 1500000,
+                    "decimal_places": 2,
+                    "currency": "USD"
+                },
+                "stopped_at": "2022-07-26T22:00:03.17106713Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::CharityCampaignStop, "", json_val);
+            assert!(res.is_ok(), "Expected `CharityCampaignStop` to parse, got {:?}", res);
+        }
+    }
+
+    mod charity_donation {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "a1b2c3-aabb-4455-d1e2f3",
+                "campaign_id": "123-abc-456-def",
+                "broadcaster_user_id": "123456",
+                "broadcaster_user_name": "SunnySideUp",
+                "broadcaster_user_login": "sunnysideup",
+                "user_id": "654321",
+                "user_login": "generoususer1",
+                "user_name": "GenerousUser1",
+                "charity_name": "example name",
+                "charity_description": "example description",
+                "charity_logo": "https://abc.cloudfront.net/ppgf/1000/100.png",
+                "charity_website": "https://www.example.com",
+                "amount": {
+                    "value": 10000,
+                    "decimal_places": 2,
+                    "currency": "USD"
+                }
+            });
+
+            let res = EventSubMessage::new(&SubType::CharityDonation, "", json_val);
+            assert!(res.is_ok(), "Expected `CharityDonation` to parse, got {:?}", res);
+        }
+    }
+
+    mod charity_amount {
+        use super::super::CharityAmount;
+
+        #[test]
+        fn formats() {
+            let amount = CharityAmount { value: 260050, decimal_places: 2, currency: "USD".to_string() };
+            assert_eq!(amount.formatted(), "$2600.50");
+
+            let amount = CharityAmount { value: 1500, decimal_places: 0, currency: "JPY".to_string() };
+            assert_eq!(amount.formatted(), "¥1500");
+
+            let amount = CharityAmount { value: 1005, decimal_places: 2, currency: "PLN".to_string() };
+            assert_eq!(amount.formatted(), "10.05 PLN");
+        }
+    }
+
+    mod stream_online {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "id": "9001",
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "type": "live",
+                "started_at": "2020-10-11T10:11:12.123Z"
+            });
+
+            let res = EventSubMessage::new(&SubType::StreamOnline, "", json_val);
+            assert!(res.is_ok(), "Expected `StreamOnline` to parse, got {:?}", res);
+        }
+    }
+
+    mod stream_offline {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User"
+            });
+
+            let res = EventSubMessage::new(&SubType::StreamOffline, "", json_val);
+            assert!(res.is_ok(), "Expected `StreamOffline` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_update {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "title": "Best Stream Ever",
+                "language": "en",
+                "category_id": "12453",
+                "category_name": "Grand Theft Auto",
+                "content_classification_labels": [ "MatureGame" ]
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelUpdate, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelUpdate` to parse, got {:?}", res);
+        }
+    }
+
+    mod channel_ad_break_begin {
+        use super::*;
+
+        #[test]
+        fn serializes() {
+            let json_val = serde_json::json!({
+                "duration_seconds": "60",
+                "started_at": "2019-11-16T10:11:12.634234626Z",
+                "is_automatic": false,
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "requester_user_id": "1337",
+                "requester_user_login": "cool_user",
+                "requester_user_name": "Cool_User"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelAdBreakBegin, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelAdBreakBegin` to parse, got {:?}", res);
+        }
+
+        #[test]
+        fn serializes_numeric_duration() {
+            let json_val = serde_json::json!({
+                "duration_seconds": 90,
+                "started_at": "2019-11-16T10:11:12.634234626Z",
+                "is_automatic": true,
+                "broadcaster_user_id": "1337",
+                "broadcaster_user_login": "cool_user",
+                "broadcaster_user_name": "Cool_User",
+                "requester_user_id": "1337",
+                "requester_user_login": "cool_user",
+                "requester_user_name": "Cool_User"
+            });
+
+            let res = EventSubMessage::new(&SubType::ChannelAdBreakBegin, "", json_val);
+            assert!(res.is_ok(), "Expected `ChannelAdBreakBegin` to parse, got {:?}", res);
+        }
+    }
+}
@@ -1 +1,2 @@
-pub mod eventsub_msgs;
\ No newline at end of file
+pub mod eventsub_msgs;
+pub mod envelope;
\ No newline at end of file
@@ -0,0 +1,82 @@
+use chrono::Utc;
+use yew::{function_component, html, Html, UseStateHandle, use_effect_with_deps, classes, use_state_eq};
+use yew_style_in_rs::style;
+
+use crate::front_common::{scalable_wrapper, SourceColor, enclose, use_action_list};
+use crate::front_common::components::CountdownTimer;
+use crate::front_common::ad_break::{AdBreakProps, AdBreakState};
+
+#[function_component(AdBreakCountdown)]
+pub fn ad_break_countdown(props: &AdBreakProps) -> Html {
+    let shown = use_state_eq(|| false);
+    let actions = use_action_list();
+
+    use_effect_with_deps(enclose! {
+        (shown)
+        move |last_msg: &UseStateHandle<AdBreakState>| {
+            if let Some(ends_at) = last_msg.ends_at {
+                actions.stop();
+
+                if ends_at > Utc::now() {
+                    shown.set(true);
+
+                    actions.start(vec![
+                        (
+                            Box::new(move || (ends_at - Utc::now()).num_milliseconds().max(0).try_into().unwrap()),
+                            Box::new(enclose! { (shown) move || {
+                                shown.set(false);
+                            }})
+                        ),
+                    ]);
+                } else {
+                    shown.set(false);
+                }
+            }
+
+            || ()
+        }
+    }, props.last_message.clone());
+
+    let outline_color = if props.settings.color == SourceColor::White { "white" } else { "black" };
+    let outline_color_opposite = if props.settings.color == SourceColor::White { "black" } else { "white" };
+
+    style! {
+        let css = dyn css! {r#"
+            --outlines: ${outline_color};
+            --outlines-opposite: ${outline_color_opposite};
+            color: var(--outlines);
+            -webkit-text-stroke: 0.6px var(--outlines-opposite);
+            transition: opacity 400ms ease-out;
+
+            &.hide {
+                opacity: 0;
+            }
+
+            &.show {
+                opacity: 1;
+            }
+
+            & .message {
+                font-size: 30px;
+                font-weight: bold;
+            }
+
+            & .timer {
+                font-size: 26px;
+                font-weight: 500;
+            }
+        "#};
+    }
+
+    let ends_at = props.last_message.ends_at;
+
+    scalable_wrapper(html! {
+        <div class={classes!(css, "text-center", {if *shown {"show"} else {"hide"}})}>
+            <div class="message">{ props.settings.message() }</div>
+            <div class="timer">
+                // the timer only picks up the end time once, so every ad break needs a new timer
+                <CountdownTimer key={ends_at.map(|ends_at| ends_at.to_rfc3339()).unwrap_or_default()} target_time={ends_at} />
+            </div>
+        </div>
+    })
+}
@@ -0,0 +1,3 @@
+mod countdown;
+
+pub use countdown::AdBreakCountdown;
\ No newline at end of file
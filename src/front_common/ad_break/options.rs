@@ -0,0 +1,54 @@
+use serde::{Serialize, Deserialize};
+use yew::{Properties, UseStateHandle};
+
+use crate::{front_common::{IntoWithLogin, SourceColor}, util::is_default};
+
+use super::AdBreakState;
+
+const DEFAULT_MESSAGE: &str = "Ad break, we'll be right back!";
+
+#[derive(Serialize)]
+pub struct AdBreakSourceOptionsLogin {
+    #[serde(flatten)]
+    pub data: AdBreakSourceOptions,
+    pub login_token: String,
+}
+
+// an empty message falls back to the default one
+#[derive(Default, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AdBreakSourceOptions {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub color: SourceColor,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub message: String,
+}
+
+impl IntoWithLogin for AdBreakSourceOptions {
+    type WithLogin = AdBreakSourceOptionsLogin;
+
+    fn with_login(&self, token: &str) -> Self::WithLogin {
+        Self::WithLogin{ data: self.clone(), login_token: token.to_string() }
+    }
+}
+
+impl AdBreakSourceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn default_message() -> &'static str {
+        DEFAULT_MESSAGE
+    }
+
+    pub fn message(&self) -> &str {
+        if self.message.is_empty() { DEFAULT_MESSAGE } else { &self.message }
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct AdBreakProps {
+    pub settings: AdBreakSourceOptions,
+    pub last_message: UseStateHandle<AdBreakState>,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc, ParseError, Duration};
+use thiserror::Error;
+
+use crate::common_data::eventsub_msgs::{EventSubMessage, EventSubData, SubType};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AdBreakState {
+    // `None` until the first ad break arrives
+    pub ends_at: Option<DateTime<Utc>>,
+    pub duration_seconds: i64,
+}
+
+impl TryFrom<EventSubMessage> for AdBreakState {
+    type Error = AdBreakFromEventSubMessageError;
+
+    fn try_from(value: EventSubMessage) -> Result<Self, Self::Error> {
+        match value.data {
+            EventSubData::ChannelAdBreakBegin(data) => {
+                let started_at = DateTime::parse_from_rfc3339(&data.started_at)?.with_timezone(&Utc);
+
+                Ok(Self {
+                    ends_at: Some(started_at + Duration::seconds(data.duration_seconds)),
+                    duration_seconds: data.duration_seconds,
+                })
+            },
+            wrong_sub_data => Err(AdBreakFromEventSubMessageError::WrongTypeSubData(wrong_sub_data.sub_type()))
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AdBreakFromEventSubMessageError {
+    #[error("Wrong type of sub data received: {0}")]
+    WrongTypeSubData(SubType),
+    #[error("Start time parsing failed: {0}")]
+    ParseError(#[from] ParseError),
+}
@@ -0,0 +1,121 @@
+use std::{rc::Rc, cell::RefCell, collections::VecDeque};
+
+use yew::{function_component, html, Html, UseStateHandle, use_effect_with_deps, use_state, use_state_eq, use_mut_ref, classes};
+use uuid::Uuid;
+use yew_style_in_rs::style;
+
+use crate::front_common::{scalable_wrapper, SourceColor, enclose, use_action_list};
+use crate::front_common::action_list::ActionList;
+use crate::front_common::alerts::{AlertsProps, AlertsSourceOptions, AlertEvent};
+
+type AlertQueue = Rc<RefCell<VecDeque<AlertEvent>>>;
+
+fn play_next(
+    queue: &AlertQueue,
+    playing: &Rc<RefCell<bool>>,
+    current: &UseStateHandle<Option<AlertEvent>>,
+    shown: &UseStateHandle<bool>,
+    actions: &ActionList,
+    settings: &AlertsSourceOptions,
+) {
+    // state handles are stale until the next render, so the flag is what guards against double starts
+    if *playing.borrow() {
+        return;
+    }
+
+    let alert = if let Some(alert) = queue.borrow_mut().pop_front() {
+        alert
+    } else {
+        return;
+    };
+
+    let duration_ms = settings.duration_seconds(alert.alert_type()) * 1000;
+
+    playing.replace(true);
+    current.set(Some(alert));
+    shown.set(true);
+
+    // clearing `current` is what lets the next alert in the queue play
+    actions.start(vec![
+        (Box::new(move || duration_ms), Box::new(enclose! { (shown) move || {
+            shown.set(false);
+        }})),
+        (Box::new(|| 500), Box::new(enclose! { (playing, current) move || {
+            playing.replace(false);
+            current.set(None);
+        }})),
+    ]);
+}
+
+#[function_component(AlertsQueue)]
+pub fn alerts_queue(props: &AlertsProps) -> Html {
+    let queue: AlertQueue = use_mut_ref(VecDeque::new);
+    let playing = use_mut_ref(|| false);
+    let current: UseStateHandle<Option<AlertEvent>> = use_state(|| None);
+    let shown = use_state_eq(|| false);
+    let actions = use_action_list();
+
+    let settings = props.settings.clone();
+    use_effect_with_deps(enclose! {
+        (queue, playing, current, shown, actions, settings)
+        move |last_msg: &UseStateHandle<Option<AlertEvent>>| {
+            if let Some(alert) = &**last_msg {
+                if settings.should_show(alert) {
+                    queue.borrow_mut().push_back(alert.clone());
+                    play_next(&queue, &playing, &current, &shown, &actions, &settings);
+                }
+            }
+
+            || ()
+        }
+    }, props.last_message.clone());
+
+    let settings = props.settings.clone();
+    use_effect_with_deps(enclose! {
+        (queue, playing, current, shown, actions)
+        move |current_id: &Option<Uuid>| {
+            if current_id.is_none() {
+                play_next(&queue, &playing, &current, &shown, &actions, &settings);
+            }
+
+            || ()
+        }
+    }, current.as_ref().map(|alert| alert.id));
+
+    let outline_color = if props.settings.color == SourceColor::White { "white" } else { "black" };
+    let outline_color_opposite = if props.settings.color == SourceColor::White { "black" } else { "white" };
+
+    style! {
+        let css = dyn css! {r#"
+            --outlines: ${outline_color};
+            --outlines-opposite: ${outline_color_opposite};
+            color: var(--outlines);
+            -webkit-text-stroke: 0.6px var(--outlines-opposite);
+            transition: opacity 400ms ease-out;
+
+            &.hide {
+                opacity: 0;
+            }
+
+            &.show {
+                opacity: 1;
+            }
+
+            & .alert-text {
+                font-size: 30px;
+                font-weight: bold;
+            }
+        "#};
+    }
+
+    let alert_text = current
+        .as_ref()
+        .map(|alert| alert.render(props.settings.template(alert.alert_type())))
+        .unwrap_or_default();
+
+    scalable_wrapper(html! {
+        <div class={classes!(css, "text-center", {if *shown {"show"} else {"hide"}})}>
+            <div class="alert-text">{ alert_text }</div>
+        </div>
+    })
+}
@@ -0,0 +1,6 @@
+mod options;
+mod state;
+pub mod components;
+
+pub use state::{AlertEvent, AlertKind, AlertType};
+pub use options::{AlertsSourceOptions, AlertsProps};
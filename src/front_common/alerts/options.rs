@@ -0,0 +1,133 @@
+use serde::{Serialize, Deserialize};
+use yew::{Properties, UseStateHandle};
+
+use crate::{front_common::{IntoWithLogin, SourceColor}, util::is_default};
+
+use super::{AlertEvent, AlertKind, AlertType};
+
+const DEFAULT_DURATION_SECONDS: u32 = 5;
+
+#[derive(Serialize)]
+pub struct AlertsSourceOptionsLogin {
+    #[serde(flatten)]
+    pub data: AlertsSourceOptions,
+    pub login_token: String,
+}
+
+// durations are in seconds, zero durations and empty templates fall back to the defaults
+#[derive(Default, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AlertsSourceOptions {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub color: SourceColor,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub follow_duration: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub sub_duration: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub resub_duration: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub gift_duration: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub cheer_duration: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub raid_duration: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub min_bits: i64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub min_raid_viewers: i64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub follow_template: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub sub_template: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub resub_template: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub gift_template: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub cheer_template: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub raid_template: String,
+}
+
+impl IntoWithLogin for AlertsSourceOptions {
+    type WithLogin = AlertsSourceOptionsLogin;
+
+    fn with_login(&self, token: &str) -> Self::WithLogin {
+        Self::WithLogin{ data: self.clone(), login_token: token.to_string() }
+    }
+}
+
+impl AlertsSourceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn default_template(alert_type: AlertType) -> &'static str {
+        match alert_type {
+            AlertType::Follow => "{user} just followed!",
+            AlertType::Sub => "{user} subscribed at tier {tier}!",
+            AlertType::Resub => "{user} resubscribed for {months} months!",
+            AlertType::Gift => "{user} gifted {amount} subs!",
+            AlertType::Cheer => "{user} cheered {amount} bits!",
+            AlertType::Raid => "{user} is raiding with {viewers} viewers!",
+        }
+    }
+
+    pub fn duration_seconds(&self, alert_type: AlertType) -> u32 {
+        let duration = match alert_type {
+            AlertType::Follow => self.follow_duration,
+            AlertType::Sub => self.sub_duration,
+            AlertType::Resub => self.resub_duration,
+            AlertType::Gift => self.gift_duration,
+            AlertType::Cheer => self.cheer_duration,
+            AlertType::Raid => self.raid_duration,
+        };
+
+        if duration == 0 { DEFAULT_DURATION_SECONDS } else { duration }
+    }
+
+    pub fn template(&self, alert_type: AlertType) -> &str {
+        let template = match alert_type {
+            AlertType::Follow => &self.follow_template,
+            AlertType::Sub => &self.sub_template,
+            AlertType::Resub => &self.resub_template,
+            AlertType::Gift => &self.gift_template,
+            AlertType::Cheer => &self.cheer_template,
+            AlertType::Raid => &self.raid_template,
+        };
+
+        if template.is_empty() { Self::default_template(alert_type) } else { template }
+    }
+
+    /// Whether the alert should be played at all
+    pub fn should_show(&self, alert: &AlertEvent) -> bool {
+        match &alert.kind {
+            // gifted subs are already covered by the gift alert itself
+            AlertKind::Sub { is_gift, .. } => !is_gift,
+            AlertKind::Cheer { bits, .. } => *bits >= self.min_bits,
+            AlertKind::Raid { viewers, .. } => *viewers >= self.min_raid_viewers,
+            _ => true
+        }
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct AlertsProps {
+    pub settings: AlertsSourceOptions,
+    pub last_message: UseStateHandle<Option<AlertEvent>>,
+}
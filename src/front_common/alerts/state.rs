@@ -0,0 +1,136 @@
+use strum::{EnumIter, Display};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::common_data::eventsub_msgs::{EventSubMessage, EventSubData, SubType};
+
+const ANONYMOUS_NAME: &str = "Anonymous";
+
+#[derive(Clone, Copy, Debug, PartialEq, EnumIter, Display)]
+#[strum(serialize_all="snake_case")]
+pub enum AlertType {
+    Follow,
+    Sub,
+    Resub,
+    Gift,
+    Cheer,
+    Raid
+}
+
+impl AlertType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertType::Follow => "Follows",
+            AlertType::Sub => "Subs",
+            AlertType::Resub => "Resubs",
+            AlertType::Gift => "Gift subs",
+            AlertType::Cheer => "Cheers",
+            AlertType::Raid => "Raids",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlertKind {
+    Follow { user_name: String },
+    Sub { user_name: String, tier: String, is_gift: bool },
+    Resub { user_name: String, tier: String, months: i64, message: String },
+    Gift { user_name: String, tier: String, total: i64 },
+    Cheer { user_name: String, bits: i64, message: String },
+    Raid { user_name: String, viewers: i64 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlertEvent {
+    // every alert is unique, even if the data is the same
+    pub id: Uuid,
+    pub kind: AlertKind,
+}
+
+// "1000" -> "1", "2000" -> "2" and so on
+fn tier_name(tier: &str) -> String {
+    tier.trim_end_matches('0').to_string()
+}
+
+impl AlertEvent {
+    pub fn new(kind: AlertKind) -> Self {
+        Self { id: Uuid::new_v4(), kind }
+    }
+
+    pub fn alert_type(&self) -> AlertType {
+        match self.kind {
+            AlertKind::Follow { .. } => AlertType::Follow,
+            AlertKind::Sub { .. } => AlertType::Sub,
+            AlertKind::Resub { .. } => AlertType::Resub,
+            AlertKind::Gift { .. } => AlertType::Gift,
+            AlertKind::Cheer { .. } => AlertType::Cheer,
+            AlertKind::Raid { .. } => AlertType::Raid,
+        }
+    }
+
+    /// Fills `{user}`, `{amount}`, `{tier}`, `{months}`, `{viewers}` and `{message}` in the template
+    pub fn render(&self, template: &str) -> String {
+        let (user_name, amount, tier, message) = match &self.kind {
+            AlertKind::Follow { user_name } => (user_name, 0, "", ""),
+            AlertKind::Sub { user_name, tier, .. } => (user_name, 0, tier.as_str(), ""),
+            AlertKind::Resub { user_name, tier, months, message } => (user_name, *months, tier.as_str(), message.as_str()),
+            AlertKind::Gift { user_name, tier, total } => (user_name, *total, tier.as_str(), ""),
+            AlertKind::Cheer { user_name, bits, message } => (user_name, *bits, "", message.as_str()),
+            AlertKind::Raid { user_name, viewers } => (user_name, *viewers, "", ""),
+        };
+
+        template
+            .replace("{user}", user_name)
+            .replace("{amount}", &amount.to_string())
+            .replace("{months}", &amount.to_string())
+            .replace("{viewers}", &amount.to_string())
+            .replace("{tier}", &tier_name(tier))
+            .replace("{message}", message)
+    }
+}
+
+impl TryFrom<EventSubMessage> for AlertEvent {
+    type Error = AlertFromEventSubMessageError;
+
+    fn try_from(value: EventSubMessage) -> Result<Self, Self::Error> {
+        let kind = match value.data {
+            EventSubData::ChannelFollow(data) => AlertKind::Follow {
+                user_name: data.user_name
+            },
+            EventSubData::ChannelSubscribe(data) => AlertKind::Sub {
+                user_name: data.user_name,
+                tier: data.tier,
+                is_gift: data.is_gift
+            },
+            EventSubData::ChannelSubscriptionMessage(data) => AlertKind::Resub {
+                user_name: data.user_name,
+                tier: data.tier,
+                months: data.cumulative_months,
+                message: data.message.text
+            },
+            EventSubData::ChannelSubscriptionGift(data) => AlertKind::Gift {
+                user_name: data.user_name.unwrap_or_else(|| ANONYMOUS_NAME.to_string()),
+                tier: data.tier,
+                total: data.total
+            },
+            EventSubData::ChannelCheer(data) => AlertKind::Cheer {
+                user_name: data.user_name.unwrap_or_else(|| ANONYMOUS_NAME.to_string()),
+                bits: data.bits,
+                message: data.message
+            },
+            EventSubData::ChannelRaid(data) => AlertKind::Raid {
+                user_name: data.from_broadcaster_user_name,
+                viewers: data.viewers
+            },
+            wrong_sub_data => return Err(AlertFromEventSubMessageError::WrongTypeSubData(wrong_sub_data.sub_type()))
+        };
+
+        Ok(Self::new(kind))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AlertFromEventSubMessageError {
+    #[error("Wrong type of sub data received: {0}")]
+    WrongTypeSubData(SubType),
+}
@@ -0,0 +1,3 @@
+mod tracker;
+
+pub use tracker::CharityTracker;
\ No newline at end of file
@@ -0,0 +1,184 @@
+use std::{rc::Rc, cell::RefCell};
+
+use gloo_render::{AnimationFrame, request_animation_frame};
+use yew::{function_component, html, Html, UseStateHandle, use_effect_with_deps, classes, use_state_eq, use_mut_ref, Properties};
+use yew_style_in_rs::style;
+
+use crate::common_data::eventsub_msgs::CharityAmount;
+use crate::front_common::{scalable_wrapper, SourceColor, enclose};
+use crate::front_common::animated_values::use_animated_f64;
+use crate::front_common::transition_funcs::ease_in_out_formula;
+use crate::front_common::charity::{CharityProps, CharityCampaignState, CharityDonationState};
+
+#[derive(PartialEq, Properties)]
+struct DonationsTickerProps {
+    last_donation: UseStateHandle<Option<CharityDonationState>>,
+    shown_donations: usize,
+}
+
+#[function_component(DonationsTicker)]
+fn donations_ticker(props: &DonationsTickerProps) -> Html {
+    let donations: UseStateHandle<Vec<(i64, CharityDonationState)>> = use_state_eq(Vec::new);
+    let next_frame_change_order: Rc<RefCell<Option<AnimationFrame>>> = use_mut_ref(|| None);
+
+    let shown_donations = props.shown_donations;
+    use_effect_with_deps(enclose! { (donations, next_frame_change_order) move |last_donation: &UseStateHandle<Option<CharityDonationState>>| {
+        if let Some(donation) = &**last_donation {
+            let latest: Vec<CharityDonationState> = Some(donation.clone())
+                .into_iter()
+                .chain((*donations).clone().into_iter().map(|(_, donation)| donation))
+                // one extra, so the last one can scroll out of view
+                .take(shown_donations + 1)
+                .collect();
+
+            // new one starts above the ticker, and slides in on the next frame
+            donations.set(latest.iter().cloned().enumerate().map(|(idx, donation)| (idx as i64 - 1, donation)).collect());
+
+            let donations = donations.clone();
+            next_frame_change_order.replace(Some(request_animation_frame(move |_| {
+                donations.set(latest.into_iter().enumerate().map(|(idx, donation)| (idx as i64, donation)).collect());
+            })));
+        }
+
+        || ()
+    }}, props.last_donation.clone());
+
+    style! {
+        let css = dyn css! {r#"
+            & .hidden-item {
+                opacity: 0;
+                font-size: 18px;
+            }
+
+            & .item {
+                position: absolute;
+                top: 0;
+                font-size: 18px;
+                font-weight: 500;
+
+                transition: transform 0.3s ease;
+                transform: translateY(calc(var(--order) * 100%));
+            }
+        "#};
+    }
+
+    html! {
+        <div class={classes!(css, "position-relative", "overflow-hidden")}>
+            {
+                (0..props.shown_donations).map(|_| {
+                    html! {
+                        <div class="hidden-item">
+                            {"Placeholder"}
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+            {
+                (*donations).clone().into_iter().map(|(idx, donation)| {
+                    html! {
+                        <div class="item w-100 text-truncate" style={format!("--order: {}", idx)} key={donation.id.to_string()}>
+                            { format!("{} donated {}", donation.user_name, donation.amount.formatted()) }
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
+
+#[function_component(CharityTracker)]
+pub fn charity_tracker(props: &CharityProps) -> Html {
+    let shown = use_state_eq(|| false);
+    let amount_state = use_animated_f64(1.0, ease_in_out_formula);
+
+    use_effect_with_deps(enclose! {
+        (shown, amount_state)
+        move |last_msg: &UseStateHandle<CharityCampaignState>| {
+            if !last_msg.id.is_empty() {
+                shown.set(true);
+                amount_state.set_value(last_msg.current_amount.as_f64());
+            }
+
+            || ()
+        }
+    }, props.last_message.clone());
+
+    let outline_color = if props.settings.color == SourceColor::White { "white" } else { "black" };
+    let outline_color_opposite = if props.settings.color == SourceColor::White { "black" } else { "white" };
+
+    // the amount is animated as a float, so it has to be turned back into the twitch representation
+    let current_amount = CharityAmount {
+        value: (*amount_state * 10f64.powi(props.last_message.current_amount.decimal_places as i32)).round() as i64,
+        ..props.last_message.current_amount.clone()
+    };
+    let progress = CharityCampaignState { current_amount: current_amount.clone(), ..(*props.last_message).clone() }.progress();
+
+    let bar_color = if props.last_message.ended { "#00bf00" } else { "#9146ff" };
+
+    style! {
+        let css = dyn css! {r#"
+            --outlines: ${outline_color};
+            --outlines-opposite: ${outline_color_opposite};
+            color: var(--outlines);
+            -webkit-text-stroke: 0.4px var(--outlines-opposite);
+            transition: opacity 400ms ease-out;
+            width: 500px;
+
+            &.hide {
+                opacity: 0;
+            }
+
+            &.show {
+                opacity: 1;
+            }
+
+            & .charity-name {
+                font-size: 24px;
+                font-weight: bold;
+            }
+
+            & .bar {
+                height: 40px;
+                border: 3px solid var(--outlines);
+            }
+
+            & .bar-fill {
+                height: 100%;
+                background-color: var(--bar-color);
+                transition: background-color 400ms ease-out;
+            }
+
+            & .amount {
+                font-size: 20px;
+                font-weight: 500;
+            }
+        "#};
+    }
+
+    scalable_wrapper(html! {
+        <div class={classes!(css, "text-center", {if *shown {"show"} else {"hide"}})}>
+            <div class="charity-name">{ &props.last_message.charity_name }</div>
+            <div class="bar text-start">
+                <div
+                    class="bar-fill"
+                    style={format!("width: {}%; --bar-color: {};", progress * 100.0, bar_color)}
+                ></div>
+            </div>
+            <div class="amount">
+                { format!("{} / {}", current_amount.formatted(), props.last_message.target_amount.formatted()) }
+            </div>
+            {
+                if props.settings.hide_donations {
+                    html! {}
+                } else {
+                    html! {
+                        <DonationsTicker
+                            last_donation={props.last_donation.clone()}
+                            shown_donations={props.settings.shown_donations_count()}
+                        />
+                    }
+                }
+            }
+        </div>
+    })
+}
@@ -0,0 +1,6 @@
+mod options;
+mod state;
+pub mod components;
+
+pub use state::{CharityEvent, CharityCampaignState, CharityDonationState};
+pub use options::{CharitySourceOptions, CharityProps};
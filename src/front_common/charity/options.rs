@@ -0,0 +1,53 @@
+use serde::{Serialize, Deserialize};
+use yew::{Properties, UseStateHandle};
+
+use crate::{front_common::{IntoWithLogin, SourceColor}, util::is_default};
+
+use super::{CharityCampaignState, CharityDonationState};
+
+const DEFAULT_SHOWN_DONATIONS: u32 = 3;
+
+#[derive(Serialize)]
+pub struct CharitySourceOptionsLogin {
+    #[serde(flatten)]
+    pub data: CharitySourceOptions,
+    pub login_token: String,
+}
+
+#[derive(Default, Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CharitySourceOptions {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub color: SourceColor,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub shown_donations: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub hide_donations: bool,
+}
+
+impl IntoWithLogin for CharitySourceOptions {
+    type WithLogin = CharitySourceOptionsLogin;
+
+    fn with_login(&self, token: &str) -> Self::WithLogin {
+        Self::WithLogin{ data: self.clone(), login_token: token.to_string() }
+    }
+}
+
+impl CharitySourceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn shown_donations_count(&self) -> usize {
+        if self.shown_donations == 0 { DEFAULT_SHOWN_DONATIONS as usize } else { self.shown_donations as usize }
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct CharityProps {
+    pub settings: CharitySourceOptions,
+    pub last_message: UseStateHandle<CharityCampaignState>,
+    pub last_donation: UseStateHandle<Option<CharityDonationState>>,
+}
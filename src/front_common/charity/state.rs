@@ -0,0 +1,83 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::common_data::eventsub_msgs::{EventSubMessage, EventSubData, SubType, CharityAmount};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharityCampaignState {
+    pub id: String,
+    pub charity_name: String,
+    pub current_amount: CharityAmount,
+    pub target_amount: CharityAmount,
+    pub ended: bool,
+}
+
+impl CharityCampaignState {
+    pub fn progress(&self) -> f64 {
+        let target = self.target_amount.as_f64();
+
+        if target <= 0.0 { 0.0 } else { (self.current_amount.as_f64() / target).clamp(0.0, 1.0) }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharityDonationState {
+    // every donation is unique, even if the data is the same
+    pub id: Uuid,
+    pub user_name: String,
+    pub amount: CharityAmount,
+}
+
+impl CharityDonationState {
+    pub fn new(user_name: String, amount: CharityAmount) -> Self {
+        Self { id: Uuid::new_v4(), user_name, amount }
+    }
+}
+
+// campaign updates and donations are shown separately, so they're kept apart
+#[derive(Clone, Debug, PartialEq)]
+pub enum CharityEvent {
+    Campaign(CharityCampaignState),
+    Donation(CharityDonationState),
+}
+
+impl TryFrom<EventSubMessage> for CharityEvent {
+    type Error = CharityFromEventSubMessageError;
+
+    fn try_from(value: EventSubMessage) -> Result<Self, Self::Error> {
+        match value.data {
+            EventSubData::CharityCampaignStart(data) => Ok(Self::Campaign(CharityCampaignState {
+                id: data.id,
+                charity_name: data.charity_name,
+                current_amount: data.current_amount,
+                target_amount: data.target_amount,
+                ended: false,
+            })),
+            EventSubData::CharityCampaignProgress(data) => Ok(Self::Campaign(CharityCampaignState {
+                id: data.id,
+                charity_name: data.charity_name,
+                current_amount: data.current_amount,
+                target_amount: data.target_amount,
+                ended: false,
+            })),
+            EventSubData::CharityCampaignStop(data) => Ok(Self::Campaign(CharityCampaignState {
+                id: data.id,
+                charity_name: data.charity_name,
+                current_amount: data.current_amount,
+                target_amount: data.target_amount,
+                ended: true,
+            })),
+            EventSubData::CharityDonation(data) => Ok(Self::Donation(CharityDonationState::new(
+                data.user_name,
+                data.amount,
+            ))),
+            wrong_sub_data => Err(CharityFromEventSubMessageError::WrongTypeSubData(wrong_sub_data.sub_type()))
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CharityFromEventSubMessageError {
+    #[error("Wrong type of sub data received: {0}")]
+    WrongTypeSubData(SubType),
+}
@@ -7,30 +7,46 @@ use yew::{Properties, function_component, use_mut_ref, use_state, html};
 
 #[derive(PartialEq, Properties)]
 pub struct CountdownTimerProps {
-    pub target_time: Option<DateTime<Utc>>
+    pub target_time: Option<DateTime<Utc>>,
+    // counts the time passed since `target_time` instead, never stops
+    #[prop_or_default]
+    pub count_up: bool,
 }
 
-fn get_time_left_seconds(target_time: Option<DateTime<Utc>>) -> Option<i64> {
+fn get_time_seconds(target_time: Option<DateTime<Utc>>, count_up: bool) -> Option<i64> {
     let time_now = Utc::now();
 
-    target_time.map(|target_time| (target_time - time_now).num_seconds().max(0))
+    target_time.map(|target_time| {
+        let time = if count_up { time_now - target_time } else { target_time - time_now };
+        time.num_seconds().max(0)
+    })
 }
 
-fn get_timer_string(time_left: Option<i64>) -> String {
-    if let Some(time_left) = time_left {
-        let minutes = time_left.checked_div(60).unwrap_or_default();
-        let seconds = time_left.checked_rem(60).unwrap_or_default();
-    
-        format!("{minutes:0>2}:{seconds:0>2}")
-    } else {
-        "--:--".to_string()
+fn get_timer_string(time: Option<i64>, with_hours: bool) -> String {
+    match time {
+        Some(time) if with_hours => {
+            let hours = time.checked_div(3600).unwrap_or_default();
+            let minutes = time.checked_rem(3600).unwrap_or_default() / 60;
+            let seconds = time.checked_rem(60).unwrap_or_default();
+
+            format!("{hours}:{minutes:0>2}:{seconds:0>2}")
+        },
+        Some(time) => {
+            let minutes = time.checked_div(60).unwrap_or_default();
+            let seconds = time.checked_rem(60).unwrap_or_default();
+
+            format!("{minutes:0>2}:{seconds:0>2}")
+        },
+        None if with_hours => "-:--:--".to_string(),
+        None => "--:--".to_string(),
     }
 }
 
 #[function_component(CountdownTimer)]
 pub fn countdown_timer(props: &CountdownTimerProps) -> Html {
     let timer_animation: Rc<RefCell<Option<Interval>>> = use_mut_ref(|| None);
-    let timer = use_state(|| get_timer_string(get_time_left_seconds(props.target_time)));
+    let count_up = props.count_up;
+    let timer = use_state(|| get_timer_string(get_time_seconds(props.target_time, count_up), count_up));
     log_1(&format!("{:?}", props.target_time).into());
 
     let timer_not_running = timer_animation.borrow().is_none();
@@ -40,10 +56,10 @@ pub fn countdown_timer(props: &CountdownTimerProps) -> Html {
         let timer = timer.clone();
         let timer_animation_clone = timer_animation.clone();
         timer_animation.replace(Some(Interval::new(1000, move || {
-            let time_left = get_time_left_seconds(target_time);
-            timer.set(get_timer_string(time_left));
+            let time = get_time_seconds(target_time, count_up);
+            timer.set(get_timer_string(time, count_up));
 
-            if time_left.map_or(true, |time_left| time_left <= 0) {
+            if time.map_or(true, |time| !count_up && time <= 0) {
                 if let Some(timer_animation) = timer_animation_clone.take() {
                     timer_animation.cancel();
                 }
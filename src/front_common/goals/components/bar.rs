@@ -0,0 +1,116 @@
+use yew::{function_component, html, Html, UseStateHandle, use_effect_with_deps, classes, use_state_eq};
+use yew_style_in_rs::style;
+
+use crate::front_common::{scalable_wrapper, SourceColor, enclose, use_action_list};
+use crate::front_common::animated_values::use_animated_f64;
+use crate::front_common::transition_funcs::ease_in_out_formula;
+use crate::front_common::goals::{GoalProps, GoalState};
+
+fn status_text(last_message: &GoalState) -> String {
+    if !last_message.ended {
+        return format!("{} / {} {}", last_message.current_amount, last_message.target_amount, last_message.type_.name());
+    }
+
+    if last_message.is_achieved {
+        "Goal achieved!".to_string()
+    } else {
+        "Goal ended".to_string()
+    }
+}
+
+#[function_component(GoalBar)]
+pub fn goal_bar(props: &GoalProps) -> Html {
+    let shown = use_state_eq(|| false);
+    let amount_state = use_animated_f64(1.0, ease_in_out_formula);
+    let actions = use_action_list();
+
+    let hide_after_end = props.settings.hide_after_end;
+    use_effect_with_deps(enclose! {
+        (shown, amount_state)
+        move |last_msg: &UseStateHandle<GoalState>| {
+            if !last_msg.id.is_empty() {
+                actions.stop();
+                shown.set(true);
+                amount_state.set_value(last_msg.current_amount as _);
+
+                if last_msg.ended && hide_after_end {
+                    actions.start(vec![
+                        (Box::new(|| 10_000), Box::new(enclose! { (shown) move || {
+                            shown.set(false);
+                        }})),
+                    ])
+                }
+            }
+
+            || ()
+        }
+    }, props.last_message.clone());
+
+    let outline_color = if props.settings.color == SourceColor::White { "white" } else { "black" };
+    let outline_color_opposite = if props.settings.color == SourceColor::White { "black" } else { "white" };
+
+    let target_amount = props.last_message.target_amount.max(1) as f64;
+    let progress = (*amount_state / target_amount).clamp(0.0, 1.0);
+
+    let bar_color = if props.last_message.ended && props.last_message.is_achieved { "#00bf00" } else { "#9146ff" };
+
+    style! {
+        let css = dyn css! {r#"
+            --outlines: ${outline_color};
+            --outlines-opposite: ${outline_color_opposite};
+            color: var(--outlines);
+            -webkit-text-stroke: 0.4px var(--outlines-opposite);
+            transition: opacity 400ms ease-out;
+            width: 500px;
+
+            &.hide {
+                opacity: 0;
+            }
+
+            &.show {
+                opacity: 1;
+            }
+
+            & .description {
+                font-size: 24px;
+                font-weight: bold;
+            }
+
+            & .bar {
+                height: 40px;
+                border: 3px solid var(--outlines);
+            }
+
+            & .bar-fill {
+                height: 100%;
+                background-color: var(--bar-color);
+                transition: background-color 400ms ease-out;
+            }
+
+            & .amount {
+                font-size: 20px;
+                font-weight: 500;
+            }
+        "#};
+    }
+
+    scalable_wrapper(html! {
+        <div class={classes!(css, "text-center", {if *shown {"show"} else {"hide"}})}>
+            <div class="description">{ &props.last_message.description }</div>
+            <div class="bar text-start">
+                <div
+                    class="bar-fill"
+                    style={format!("width: {}%; --bar-color: {};", progress * 100.0, bar_color)}
+                ></div>
+            </div>
+            <div class="amount">
+                {
+                    status_text(&GoalState {
+                        current_amount: *amount_state as _,
+                        ..(*props.last_message).clone()
+                    })
+                }
+            </div>
+        </div>
+    })
+}
@@ -0,0 +1,3 @@
+mod bar;
+
+pub use bar::GoalBar;
@@ -0,0 +1,43 @@
+use serde::{Serialize, Deserialize};
+use yew::{Properties, UseStateHandle};
+
+use crate::{front_common::{IntoWithLogin, SourceColor}, util::is_default};
+
+use super::GoalState;
+
+#[derive(Serialize)]
+pub struct GoalSourceOptionsLogin {
+    #[serde(flatten)]
+    pub data: GoalSourceOptions,
+    pub login_token: String,
+}
+
+#[derive(Default, Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct GoalSourceOptions {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub color: SourceColor,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub hide_after_end: bool,
+}
+
+impl IntoWithLogin for GoalSourceOptions {
+    type WithLogin = GoalSourceOptionsLogin;
+
+    fn with_login(&self, token: &str) -> Self::WithLogin {
+        Self::WithLogin{ data: self.clone(), login_token: token.to_string() }
+    }
+}
+
+impl GoalSourceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct GoalProps {
+    pub settings: GoalSourceOptions,
+    pub last_message: UseStateHandle<GoalState>,
+}
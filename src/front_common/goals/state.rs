@@ -0,0 +1,93 @@
+use thiserror::Error;
+
+use crate::common_data::eventsub_msgs::{EventSubMessage, EventSubData, SubType, GoalType};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoalState {
+    pub id: String,
+    pub type_: GoalType,
+    pub description: String,
+    pub current_amount: i64,
+    pub target_amount: i64,
+    pub ended: bool,
+    pub is_achieved: bool,
+}
+
+impl Default for GoalState {
+    fn default() -> Self {
+        Self {
+            id: Default::default(),
+            type_: GoalType::Other,
+            description: Default::default(),
+            current_amount: 0,
+            target_amount: 1,
+            ended: false,
+            is_achieved: false,
+        }
+    }
+}
+
+impl GoalState {
+    pub fn new(
+        id: String,
+        type_: GoalType,
+        description: String,
+        current_amount: i64,
+        target_amount: i64,
+        ended: bool,
+        is_achieved: bool,
+    ) -> Self {
+        Self {
+            id,
+            type_,
+            description,
+            current_amount,
+            target_amount,
+            ended,
+            is_achieved,
+        }
+    }
+}
+
+impl TryFrom<EventSubMessage> for GoalState {
+    type Error = GoalFromEventSubMessageError;
+
+    fn try_from(value: EventSubMessage) -> Result<Self, Self::Error> {
+        match value.data {
+            EventSubData::ChannelGoalBegin(data) => Ok(Self {
+                id: data.id,
+                type_: data.type_,
+                description: data.description,
+                current_amount: data.current_amount,
+                target_amount: data.target_amount,
+                ended: false,
+                is_achieved: false,
+            }),
+            EventSubData::ChannelGoalProgress(data) => Ok(Self {
+                id: data.id,
+                type_: data.type_,
+                description: data.description,
+                current_amount: data.current_amount,
+                target_amount: data.target_amount,
+                ended: false,
+                is_achieved: false,
+            }),
+            EventSubData::ChannelGoalEnd(data) => Ok(Self {
+                id: data.id,
+                type_: data.type_,
+                description: data.description,
+                current_amount: data.current_amount,
+                target_amount: data.target_amount,
+                ended: true,
+                is_achieved: data.is_achieved,
+            }),
+            wrong_sub_data => Err(GoalFromEventSubMessageError::WrongTypeSubData(wrong_sub_data.sub_type()))
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GoalFromEventSubMessageError {
+    #[error("Wrong type of sub data received: {0}")]
+    WrongTypeSubData(SubType),
+}
@@ -1,9 +1,17 @@
 mod scalable_wrapper;
 mod options_util;
 mod action_list;
+mod source_socket;
 
 pub mod predictions;
 pub mod hypetrain;
+pub mod polls;
+pub mod goals;
+pub mod alerts;
+pub mod redemptions;
+pub mod charity;
+pub mod stream_status;
+pub mod ad_break;
 pub mod animated_values;
 pub(crate) mod components;
 
@@ -14,6 +22,7 @@ pub use common_params::SourceColor;
 pub use scalable_wrapper::scalable_wrapper;
 pub use options_util::IntoWithLogin;
 pub use action_list::use_action_list;
+pub use source_socket::use_source_socket;
 
 #[macro_export]
 macro_rules! enclose {
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use yew::prelude::*;
+use yew_style_in_rs::style;
+
+use super::super::state::{PollState, PollStatus};
+use crate::front_common::{scalable_wrapper, polls::PollProps, SourceColor, components::CountdownTimer};
+
+fn title(title: &str) -> Html {
+    html! {
+        <span class="mx-auto title">{ title }</span>
+    }
+}
+
+fn status(status: &PollStatus, poll_id: &str, end_time: &DateTime<Utc>) -> Html {
+    match status {
+        // keyed by poll id so a new poll remounts the timer with its own end time
+        PollStatus::InProgress => html!{
+            <span class="timer">
+                <CountdownTimer key={poll_id.to_string()} target_time={Some(*end_time)} />
+            </span>
+        },
+        PollStatus::Finished => html!{ <span class="timer">{ "Poll ended" }</span> },
+    }
+}
+
+fn choices_list(state: &PollState, show_votes: bool) -> Html {
+    let winner_idx = state.get_winner_idx();
+
+    html! {
+        <div class="choices-wrapper">
+            {
+                state.choices.iter().enumerate().map(
+                    |(idx, choice)| html! {
+                        <div class={classes!("choice", "d-grid", (winner_idx == Some(idx)).then_some("winner"))}>
+                            <div class="choice-title vh-align">{ &choice.title }</div>
+                            <div class="choice-percents vh-align">{ state.get_choice_percents(&choice.id) }{"%"}</div>
+                            if show_votes {
+                                <div class="choice-votes vh-align">{ choice.votes }</div>
+                            }
+                        </div>
+                    }
+                ).collect::<Html>()
+            }
+        </div>
+    }
+}
+
+#[function_component(PollList)]
+pub fn poll_list(props: &PollProps) -> Html {
+    let outline_color = if props.color == SourceColor::White { "white" } else { "black" };
+    let outline_color_opposite = if props.color == SourceColor::White { "black" } else { "white" };
+    let grid_columns = if props.show_votes { "3fr 1fr 1fr" } else { "3fr 1fr" };
+
+    style! {
+        let css = dyn css! {r#"
+            --outlines: ${outline_color};
+            --outlines-opposite: ${outline_color_opposite};
+
+            & .element {
+                width: min-content;
+                transition: opacity 400ms ease-out;
+            }
+
+            & .element.hide {
+                opacity: 0;
+            }
+
+            & .element.show {
+                opacity: 1;
+            }
+
+            & .status {
+                transition: opacity 200ms ease-out;
+            }
+
+            & .status.hide {
+                opacity: 0;
+            }
+
+            & .status.show {
+                opacity: 1;
+            }
+
+            & .title {
+                max-width: fit-content;
+                color: var(--outlines);
+                -webkit-text-stroke: 0.6px var(--outlines-opposite);
+                font-size: 20px;
+                font-weight: bold;
+            }
+
+            & .timer {
+                color: var(--outlines);
+                font-size: 16px;
+                font-weight: bold;
+                -webkit-text-stroke: 0.6px var(--outlines-opposite);
+            }
+
+            & .choices-wrapper {
+                width: max-content;
+            }
+
+            & .choice {
+                transition: color 400ms ease-out;
+                color: var(--outlines);
+                -webkit-text-stroke: 0.6px var(--outlines-opposite);
+                font-size: 16px;
+                font-weight: bold;
+                grid-template-columns: ${grid_columns};
+                vertical-align: middle;
+            }
+
+            & .choice.winner {
+                color: #C38000;
+            }
+
+            & .choice-title {
+                max-width: 200px;
+            }
+
+            & .choice-percents {
+                font-size: 20px;
+            }
+
+            & .choice-votes {
+                text-align: left;
+            }
+
+            & .vh-align {
+                display: flex;
+                justify-content: center;
+                align-items: center;
+            }
+        "#};
+    }
+
+    scalable_wrapper(html! {
+        <div class={classes!(css, "text-center")}>
+            <div class={if *props.show_element_state {"element show"} else {"element hide"}}>
+                { title(&props.state.title) }
+                <div class={if *props.show_status_state {"status show"} else {"status hide"}}>
+                    { status(&props.status_state, &props.state.id, &props.state.end_time) }
+                </div>
+                { choices_list(&props.state, props.show_votes) }
+            </div>
+        </div>
+    })
+}
@@ -0,0 +1,6 @@
+mod options;
+mod state;
+pub mod components;
+
+pub use options::{PollSourceOptions, PollProps};
+pub use state::{PollStateAnimator, PollState, PollChoiceState, PollStatus};
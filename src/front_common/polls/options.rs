@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+use yew::{Properties, UseStateHandle};
+
+use crate::{front_common::{SourceColor, options_util::IntoWithLogin}, util::is_default};
+
+use super::{PollState, PollStatus};
+
+#[derive(Serialize)]
+pub struct PollSourceOptionsLogin {
+    #[serde(flatten)]
+    pub data: PollSourceOptions,
+    pub login_token: String
+}
+
+#[derive(Default, Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PollSourceOptions {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub color: SourceColor,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub show_votes: bool
+}
+
+impl IntoWithLogin for PollSourceOptions {
+    type WithLogin = PollSourceOptionsLogin;
+
+    fn with_login(&self, token: &str) -> Self::WithLogin {
+        Self::WithLogin { data: self.clone(), login_token: token.to_string() }
+    }
+}
+
+impl PollSourceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct PollProps {
+    pub color: SourceColor,
+    pub show_votes: bool,
+    pub state: UseStateHandle<PollState>,
+    pub show_element_state: UseStateHandle<bool>,
+    pub show_status_state: UseStateHandle<bool>,
+    pub status_state: UseStateHandle<PollStatus>,
+}
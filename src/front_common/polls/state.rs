@@ -0,0 +1,274 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use chrono::{DateTime, Utc, ParseError};
+use thiserror::Error;
+
+use yew::UseStateSetter;
+use gloo_timers::callback::{Interval, Timeout};
+
+use crate::{FPS, GLOBAL_DELAY_VALUE, GLOBAL_DELAY_VALUE_SECONDS, common_data::eventsub_msgs::{EventSubData, EventSubMessage, PollChoice, SubType}};
+use super::super::transition_funcs::ease_in_out_formula;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PollChoiceState {
+    pub id: String,
+    pub title: String,
+    pub votes: i64,
+}
+
+impl From<PollChoice> for PollChoiceState {
+    fn from(choice: PollChoice) -> Self {
+        Self {
+            id: choice.id,
+            title: choice.title,
+            votes: choice.votes,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum PollStatus {
+    InProgress,
+    #[default]
+    Finished
+}
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct PollState {
+    pub id: String,
+    pub title: String,
+    pub choices: Vec<PollChoiceState>,
+    pub end_time: DateTime<Utc>,
+    pub status: PollStatus,
+}
+
+impl PollState {
+    pub fn new(
+        id: String,
+        title: String,
+        choices: Vec<PollChoiceState>,
+        end_time: DateTime<Utc>,
+        status: PollStatus
+    ) -> Self {
+        Self {
+            id,
+            title,
+            choices,
+            end_time,
+            status,
+        }
+    }
+
+    pub fn clone_with_empty_choices(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            choices: Default::default(),
+            end_time: self.end_time,
+            status: self.status.clone(),
+        }
+    }
+
+    pub fn total_votes(&self) -> i64 {
+        self.choices.iter().fold(0, |curr, val| curr + val.votes)
+    }
+
+    pub fn get_choice_percents(&self, choice_id: &str) -> i64 {
+        let sum = self.total_votes();
+
+        if sum == 0 {
+            return 0;
+        }
+
+        // choice_id should always come from the vec itself, otherwise something wrong is going on
+        let choice = self.choices.iter().find(|v| v.id == choice_id).unwrap();
+
+        (((choice.votes as f64) / (sum as f64)) * 100.0).round() as _
+    }
+
+    /// Index of the choice with the most votes, only known after the poll has finished
+    /// and only if anyone has voted at all
+    pub fn get_winner_idx(&self) -> Option<usize> {
+        if self.status != PollStatus::Finished || self.total_votes() == 0 {
+            return None;
+        }
+
+        self.choices
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, choice)| choice.votes)
+            .map(|(idx, _)| idx)
+    }
+}
+
+impl TryFrom<EventSubMessage> for PollState {
+    type Error = PollFromEventSubMessageError;
+
+    fn try_from(value: EventSubMessage) -> Result<Self, Self::Error> {
+        match value.data {
+            EventSubData::ChannelPollBegin(data) => Ok(Self {
+                id: data.id,
+                title: data.title,
+                choices: data.choices.into_iter().map(Into::into).collect(),
+                end_time: DateTime::parse_from_rfc3339(&data.ends_at)?.with_timezone(&Utc),
+                status: PollStatus::InProgress,
+            }),
+            EventSubData::ChannelPollProgress(data) => Ok(Self {
+                id: data.id,
+                title: data.title,
+                choices: data.choices.into_iter().map(Into::into).collect(),
+                end_time: DateTime::parse_from_rfc3339(&data.ends_at)?.with_timezone(&Utc),
+                status: PollStatus::InProgress,
+            }),
+            EventSubData::ChannelPollEnd(data) => Ok(Self {
+                id: data.id,
+                title: data.title,
+                choices: data.choices.into_iter().map(Into::into).collect(),
+                end_time: DateTime::parse_from_rfc3339(&data.ended_at)?.with_timezone(&Utc),
+                status: PollStatus::Finished,
+            }),
+            wrong_sub_data => Err(PollFromEventSubMessageError::WrongTypeSubData(wrong_sub_data.sub_type()))
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PollFromEventSubMessageError {
+    #[error("Wrong type of sub data received: {0}")]
+    WrongTypeSubData(SubType),
+    #[error("End time parsing failed: {0}")]
+    ParseError(#[from] ParseError),
+}
+
+pub struct PollStateAnimator {
+    prev_state: PollState,
+    next_state: PollState,
+
+    pub state_setter: UseStateSetter<PollState>,
+    pub show_element_setter: UseStateSetter<bool>,
+    pub show_status_setter: UseStateSetter<bool>,
+    pub status_setter: UseStateSetter<PollStatus>,
+
+    timer: Rc<RefCell<f64>>,
+
+    animation_handle: Rc<RefCell<Option<Interval>>>,
+    hide_element_handle: Rc<RefCell<Option<Timeout>>>,
+    show_status_handle: Rc<RefCell<Option<Timeout>>>
+}
+
+impl PollStateAnimator {
+    pub fn new(
+        state_setter: UseStateSetter<PollState>,
+        curr_state: &PollState,
+        show_element_setter: UseStateSetter<bool>,
+        show_status_setter: UseStateSetter<bool>,
+        status_setter: UseStateSetter<PollStatus>,
+    ) -> Self {
+        Self {
+            prev_state: curr_state.clone(),
+            next_state: Default::default(),
+            state_setter,
+            show_element_setter,
+            show_status_setter,
+            status_setter,
+            timer: Default::default(),
+            animation_handle: Default::default(),
+            hide_element_handle: Default::default(),
+            show_status_handle: Default::default()
+        }
+    }
+
+    // same as with predictions - UseState value is stale here, so the caller passes the current one
+    pub fn set_state(&mut self, new_state: PollState, curr_state: &PollState) {
+        // a new poll has nothing to animate from, start from zero votes
+        if new_state.id != curr_state.id {
+            self.prev_state = new_state.clone();
+            self.prev_state.status = curr_state.status.clone();
+            self.prev_state.choices.iter_mut().for_each(|choice| choice.votes = 0);
+        } else {
+            self.prev_state = curr_state.clone();
+        }
+
+        self.next_state = new_state;
+
+        *(self.timer.borrow_mut()) = 0.0;
+
+        if let Some(anim_handle) = self.animation_handle.take() {
+            anim_handle.cancel();
+        };
+
+        if let Some(element_handle) = self.hide_element_handle.take() {
+            element_handle.cancel();
+        }
+
+        self.show_element_setter.set(true);
+
+        if self.prev_state.status != self.next_state.status {
+            if let Some(status_handle) = self.show_status_handle.take() {
+                status_handle.cancel();
+            }
+
+            self.show_status_setter.set(false);
+
+            let show_status_setter = self.show_status_setter.clone();
+            let status_setter = self.status_setter.clone();
+            let next_status = self.next_state.status.clone();
+
+            self.show_status_handle.replace(Some(Timeout::new(500, move || {
+                show_status_setter.set(true);
+                status_setter.set(next_status);
+            })));
+        }
+
+        // results stay on screen for a bit after the poll is done
+        if self.next_state.status == PollStatus::Finished {
+            let show_element_setter = self.show_element_setter.clone();
+
+            self.hide_element_handle.replace(Some(Timeout::new(10_000, move || {
+                show_element_setter.set(false);
+            })));
+        }
+
+        let state_setter = self.state_setter.clone();
+        let timer_ref = self.timer.clone();
+        let anim_handle_ref = self.animation_handle.clone();
+        let prev_state = self.prev_state.clone();
+        let next_state = self.next_state.clone();
+
+        self.animation_handle.replace(Some(Interval::new(GLOBAL_DELAY_VALUE / FPS, move || {
+            let mut timer_borrow = timer_ref.borrow_mut();
+            *timer_borrow += (GLOBAL_DELAY_VALUE_SECONDS as f64) / (FPS as f64);
+
+            // if time over the animation time - we're done
+            if *timer_borrow > (GLOBAL_DELAY_VALUE_SECONDS as f64) {
+                state_setter.set(next_state.clone());
+                if let Some(anim_handle) = anim_handle_ref.take() {
+                    anim_handle.cancel();
+                };
+                return;
+            }
+
+            let mut intermediate_state = next_state.clone_with_empty_choices();
+
+            for choice in next_state.choices.iter() {
+                let prev_votes = prev_state.choices
+                    .iter()
+                    .find(|elem| elem.id == choice.id)
+                    .map_or(0, |elem| elem.votes);
+
+                intermediate_state.choices.push(PollChoiceState {
+                    id: choice.id.clone(),
+                    title: choice.title.clone(),
+                    votes: ease_in_out_formula(
+                        *timer_borrow,
+                        prev_votes as _,
+                        (choice.votes - prev_votes) as _,
+                        GLOBAL_DELAY_VALUE_SECONDS as _
+                    ) as _,
+                });
+            }
+
+            state_setter.set(intermediate_state);
+        })));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{rc::Rc, cell::RefCell};
+
+use gloo_render::{AnimationFrame, request_animation_frame};
+use yew::{function_component, html, Html, UseStateHandle, use_effect_with_deps, use_state_eq, use_mut_ref, classes};
+use yew_style_in_rs::style;
+
+use crate::common_data::eventsub_msgs::RedemptionStatus;
+use crate::front_common::{scalable_wrapper, SourceColor, enclose};
+use crate::front_common::redemptions::{RedemptionsProps, RedemptionEvent};
+
+fn with_order(redemptions: Vec<RedemptionEvent>, first_idx: i64) -> Vec<(i64, RedemptionEvent)> {
+    redemptions
+        .into_iter()
+        .enumerate()
+        .map(|(idx, redemption)| (idx as i64 + first_idx, redemption))
+        .collect()
+}
+
+#[function_component(RedemptionsFeed)]
+pub fn redemptions_feed(props: &RedemptionsProps) -> Html {
+    let feed: UseStateHandle<Vec<(i64, RedemptionEvent)>> = use_state_eq(Vec::new);
+    let next_frame_change_order: Rc<RefCell<Option<AnimationFrame>>> = use_mut_ref(|| None);
+
+    let settings = props.settings.clone();
+    use_effect_with_deps(enclose! { (feed, next_frame_change_order) move |last_msg: &UseStateHandle<Option<RedemptionEvent>>| {
+        if let Some(redemption) = &**last_msg {
+            let mut redemptions: Vec<RedemptionEvent> = (*feed)
+                .clone()
+                .into_iter()
+                .map(|(_, redemption)| redemption)
+                .collect();
+
+            let existing = redemptions.iter().position(|shown| shown.redemption_id == redemption.redemption_id);
+
+            if redemption.is_update {
+                // canceled redemptions are refunded, so they shouldn't stay in the feed
+                if let (Some(idx), RedemptionStatus::Canceled) = (existing, &redemption.status) {
+                    redemptions.remove(idx);
+                    feed.set(with_order(redemptions, 0));
+                }
+            } else if existing.is_none() && settings.should_show(redemption) {
+                redemptions.insert(0, redemption.clone());
+                // one extra, so the last one can scroll out of view
+                redemptions.truncate(settings.shown_count() + 1);
+
+                // new one starts above the feed, and slides in on the next frame
+                feed.set(with_order(redemptions.clone(), -1));
+
+                let feed = feed.clone();
+                next_frame_change_order.replace(Some(request_animation_frame(move |_| {
+                    feed.set(with_order(redemptions, 0));
+                })));
+            }
+        }
+
+        || ()
+    }}, props.last_message.clone());
+
+    let outline_color = if props.settings.color == SourceColor::White { "white" } else { "black" };
+    let outline_color_opposite = if props.settings.color == SourceColor::White { "black" } else { "white" };
+
+    style! {
+        let css = dyn css! {r#"
+            --outlines: ${outline_color};
+            --outlines-opposite: ${outline_color_opposite};
+            color: var(--outlines);
+            -webkit-text-stroke: 0.4px var(--outlines-opposite);
+            width: 500px;
+
+            & .hidden-item {
+                opacity: 0;
+                font-size: 20px;
+            }
+
+            & .item {
+                position: absolute;
+                top: 0;
+                font-size: 20px;
+                font-weight: 500;
+
+                transition: transform 0.3s ease;
+                transform: translateY(calc(var(--order) * 100%));
+            }
+
+            & .user-input {
+                font-weight: normal;
+                font-style: italic;
+            }
+        "#};
+    }
+
+    scalable_wrapper(html! {
+        <div class={classes!(css, "position-relative", "overflow-hidden", "text-center")}>
+            {
+                (0..props.settings.shown_count()).map(|_| {
+                    html! {
+                        <div class="hidden-item">
+                            {"Placeholder"}
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+            {
+                (*feed).clone().into_iter().map(|(idx, redemption)| {
+                    html! {
+                        <div class="item w-100 text-truncate" style={format!("--order: {}", idx)} key={redemption.id.to_string()}>
+                            { format!("{} redeemed {} ({})", redemption.user_name, redemption.reward_title, redemption.cost) }
+                            {
+                                if redemption.user_input.is_empty() {
+                                    html! {}
+                                } else {
+                                    html! {
+                                        <span class="user-input">{ format!(": {}", redemption.user_input) }</span>
+                                    }
+                                }
+                            }
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    })
+}
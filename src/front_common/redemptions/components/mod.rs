@@ -0,0 +1,3 @@
+mod feed;
+
+pub use feed::RedemptionsFeed;
\ No newline at end of file
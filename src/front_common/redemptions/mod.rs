@@ -0,0 +1,6 @@
+mod options;
+mod state;
+pub mod components;
+
+pub use state::RedemptionEvent;
+pub use options::{RedemptionsSourceOptions, RedemptionsProps};
@@ -0,0 +1,62 @@
+use serde::{Serialize, Deserialize};
+use yew::{Properties, UseStateHandle};
+
+use crate::{front_common::{IntoWithLogin, SourceColor}, util::is_default};
+
+use super::RedemptionEvent;
+
+const DEFAULT_MAX_SHOWN: u32 = 5;
+
+#[derive(Serialize)]
+pub struct RedemptionsSourceOptionsLogin {
+    #[serde(flatten)]
+    pub data: RedemptionsSourceOptions,
+    pub login_token: String,
+}
+
+// `reward_ids` is comma-separated, since the options live in a query string,
+// an empty one shows every reward
+#[derive(Default, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct RedemptionsSourceOptions {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub color: SourceColor,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub max_shown: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub reward_ids: String,
+}
+
+impl IntoWithLogin for RedemptionsSourceOptions {
+    type WithLogin = RedemptionsSourceOptionsLogin;
+
+    fn with_login(&self, token: &str) -> Self::WithLogin {
+        Self::WithLogin{ data: self.clone(), login_token: token.to_string() }
+    }
+}
+
+impl RedemptionsSourceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn shown_count(&self) -> usize {
+        if self.max_shown == 0 { DEFAULT_MAX_SHOWN as usize } else { self.max_shown as usize }
+    }
+
+    pub fn reward_ids(&self) -> impl Iterator<Item = &str> {
+        self.reward_ids.split(',').filter(|id| !id.is_empty())
+    }
+
+    pub fn should_show(&self, redemption: &RedemptionEvent) -> bool {
+        self.reward_ids.is_empty() || self.reward_ids().any(|id| id == redemption.reward_id)
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct RedemptionsProps {
+    pub settings: RedemptionsSourceOptions,
+    pub last_message: UseStateHandle<Option<RedemptionEvent>>,
+}
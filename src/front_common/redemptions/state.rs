@@ -0,0 +1,59 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::common_data::eventsub_msgs::{EventSubMessage, EventSubData, SubType, RedemptionStatus};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedemptionEvent {
+    // every message is unique, even if the data is the same
+    pub id: Uuid,
+    pub redemption_id: String,
+    pub reward_id: String,
+    pub reward_title: String,
+    pub cost: i64,
+    pub user_name: String,
+    pub user_input: String,
+    pub status: RedemptionStatus,
+    // updates change an existing redemption, they never add a new one to the feed
+    pub is_update: bool,
+}
+
+impl TryFrom<EventSubMessage> for RedemptionEvent {
+    type Error = RedemptionFromEventSubMessageError;
+
+    fn try_from(value: EventSubMessage) -> Result<Self, Self::Error> {
+        let res = match value.data {
+            EventSubData::ChannelPointsCustomRewardRedemptionAdd(data) => Self {
+                id: Uuid::new_v4(),
+                redemption_id: data.id,
+                reward_id: data.reward.id,
+                reward_title: data.reward.title,
+                cost: data.reward.cost,
+                user_name: data.user_name,
+                user_input: data.user_input,
+                status: data.status,
+                is_update: false,
+            },
+            EventSubData::ChannelPointsCustomRewardRedemptionUpdate(data) => Self {
+                id: Uuid::new_v4(),
+                redemption_id: data.id,
+                reward_id: data.reward.id,
+                reward_title: data.reward.title,
+                cost: data.reward.cost,
+                user_name: data.user_name,
+                user_input: data.user_input,
+                status: data.status,
+                is_update: true,
+            },
+            wrong_sub_data => return Err(RedemptionFromEventSubMessageError::WrongTypeSubData(wrong_sub_data.sub_type()))
+        };
+
+        Ok(res)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RedemptionFromEventSubMessageError {
+    #[error("Wrong type of sub data received: {0}")]
+    WrongTypeSubData(SubType),
+}
@@ -0,0 +1,66 @@
+use serde::Deserialize;
+use yew::{use_effect_with_deps, use_mut_ref, use_state_eq};
+use yew_hooks::{use_web_socket_with_options, UseWebSocketHandle, UseWebSocketOptions, UseWebSocketReadyState};
+
+use crate::common_data::envelope::PROTOCOL_VERSION;
+
+// only the number is needed here, the source parses the whole envelope itself
+#[derive(Deserialize)]
+struct EnvelopeSeq {
+    seq: Option<u64>,
+}
+
+fn source_url(topic: &str, since: Option<u64>) -> String {
+    let location = web_sys::window().expect("Gotta have a window object").location();
+    let host = location.host().expect("Gotta have a location origin");
+    let protocol = location.protocol().expect("Gotta have a location protocol");
+
+    let ws_protocol = if protocol.contains("https") { "wss" } else { "ws" };
+    let url = format!("{}://{}/ws/sources/{}?protocol={}", ws_protocol, host, topic, PROTOCOL_VERSION);
+
+    match since {
+        Some(since) => format!("{}&since={}", url, since),
+        None => url,
+    }
+}
+
+/// Socket of the source for `topic`, reconnecting when the connection drops. Keeps the `seq` of the
+/// last event it got, so it comes back with `since` and gets what it missed instead of a snapshot.
+pub fn use_source_socket(topic: &str) -> UseWebSocketHandle {
+    let last_seq = use_mut_ref(|| None::<u64>);
+    // changed only once the socket is closed, the new url is what makes it connect again
+    let since = use_state_eq(|| None::<u64>);
+
+    let ws = use_web_socket_with_options(
+        source_url(topic, *since),
+        UseWebSocketOptions { reconnect_limit: Some(1000), reconnect_interval: Some(10000), ..Default::default() }
+    );
+
+    {
+        let last_seq = last_seq.clone();
+
+        use_effect_with_deps(move |message| {
+            let seq = message.as_deref()
+                .and_then(|message| serde_json::from_str::<EnvelopeSeq>(message).ok())
+                .and_then(|envelope| envelope.seq);
+
+            if let Some(seq) = seq {
+                last_seq.replace(Some(seq));
+            }
+            || ()
+        }, ws.message.clone());
+    }
+
+    {
+        let since = since.clone();
+
+        use_effect_with_deps(move |ready_state| {
+            if matches!(**ready_state, UseWebSocketReadyState::Closed) {
+                since.set(*last_seq.borrow());
+            }
+            || ()
+        }, ws.ready_state.clone());
+    }
+
+    ws
+}
\ No newline at end of file
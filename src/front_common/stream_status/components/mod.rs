@@ -0,0 +1,3 @@
+mod uptime;
+
+pub use uptime::StreamUptime;
\ No newline at end of file
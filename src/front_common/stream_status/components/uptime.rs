@@ -0,0 +1,69 @@
+use yew::{function_component, html, Html, classes};
+use yew_style_in_rs::style;
+
+use crate::front_common::{scalable_wrapper, SourceColor};
+use crate::front_common::components::CountdownTimer;
+use crate::front_common::stream_status::{StreamStatusProps, StreamStatus};
+
+#[function_component(StreamUptime)]
+pub fn stream_uptime(props: &StreamStatusProps) -> Html {
+    let outline_color = if props.settings.color == SourceColor::White { "white" } else { "black" };
+    let outline_color_opposite = if props.settings.color == SourceColor::White { "black" } else { "white" };
+
+    style! {
+        let css = dyn css! {r#"
+            --outlines: ${outline_color};
+            --outlines-opposite: ${outline_color_opposite};
+            color: var(--outlines);
+            -webkit-text-stroke: 0.4px var(--outlines-opposite);
+            width: 500px;
+
+            & .status {
+                font-size: 30px;
+                font-weight: bold;
+            }
+
+            & .title {
+                font-size: 22px;
+                font-weight: 500;
+            }
+
+            & .category {
+                font-size: 18px;
+                font-weight: 500;
+            }
+        "#};
+    }
+
+    let status = match &*props.status {
+        StreamStatus::StartingSoon => html! { { "Starting soon" } },
+        StreamStatus::Live { started_at } => html! {
+            <>
+                { "Live for " }
+                // the timer only picks up the start time once, so a new stream needs a new timer
+                <CountdownTimer key={started_at.to_rfc3339()} target_time={Some(*started_at)} count_up={true} />
+            </>
+        },
+        StreamStatus::Offline => html! { { "Offline" } },
+    };
+
+    scalable_wrapper(html! {
+        <div class={classes!(css, "text-center")}>
+            <div class="status">{ status }</div>
+            {
+                if props.settings.hide_title || props.info.title.is_empty() {
+                    html! {}
+                } else {
+                    html! { <div class="title text-truncate">{ &props.info.title }</div> }
+                }
+            }
+            {
+                if props.settings.hide_category || props.info.category_name.is_empty() {
+                    html! {}
+                } else {
+                    html! { <div class="category text-truncate">{ &props.info.category_name }</div> }
+                }
+            }
+        </div>
+    })
+}
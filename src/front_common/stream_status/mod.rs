@@ -0,0 +1,6 @@
+mod options;
+mod state;
+pub mod components;
+
+pub use state::{StreamStatusEvent, StreamStatus, ChannelInfo};
+pub use options::{StreamStatusSourceOptions, StreamStatusProps};
@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+use yew::{Properties, UseStateHandle};
+
+use crate::{front_common::{IntoWithLogin, SourceColor}, util::is_default};
+
+use super::{StreamStatus, ChannelInfo};
+
+#[derive(Serialize)]
+pub struct StreamStatusSourceOptionsLogin {
+    #[serde(flatten)]
+    pub data: StreamStatusSourceOptions,
+    pub login_token: String,
+}
+
+#[derive(Default, Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct StreamStatusSourceOptions {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub color: SourceColor,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub hide_title: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub hide_category: bool,
+}
+
+impl IntoWithLogin for StreamStatusSourceOptions {
+    type WithLogin = StreamStatusSourceOptionsLogin;
+
+    fn with_login(&self, token: &str) -> Self::WithLogin {
+        Self::WithLogin{ data: self.clone(), login_token: token.to_string() }
+    }
+}
+
+impl StreamStatusSourceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct StreamStatusProps {
+    pub settings: StreamStatusSourceOptions,
+    pub status: UseStateHandle<StreamStatus>,
+    pub info: UseStateHandle<ChannelInfo>,
+}
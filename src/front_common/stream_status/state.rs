@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc, ParseError};
+use thiserror::Error;
+
+use crate::common_data::eventsub_msgs::{EventSubMessage, EventSubData, SubType};
+
+// nothing is known until the stream goes live, so that's assumed to be soon
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum StreamStatus {
+    #[default]
+    StartingSoon,
+    Live { started_at: DateTime<Utc> },
+    Offline,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChannelInfo {
+    pub title: String,
+    pub category_name: String,
+}
+
+// status and channel info come from different events, so they're kept apart
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamStatusEvent {
+    Status(StreamStatus),
+    Info(ChannelInfo),
+}
+
+impl TryFrom<EventSubMessage> for StreamStatusEvent {
+    type Error = StreamStatusFromEventSubMessageError;
+
+    fn try_from(value: EventSubMessage) -> Result<Self, Self::Error> {
+        match value.data {
+            EventSubData::StreamOnline(data) => Ok(Self::Status(StreamStatus::Live {
+                started_at: DateTime::parse_from_rfc3339(&data.started_at)?.with_timezone(&Utc)
+            })),
+            EventSubData::StreamOffline(_) => Ok(Self::Status(StreamStatus::Offline)),
+            EventSubData::ChannelUpdate(data) => Ok(Self::Info(ChannelInfo {
+                title: data.title,
+                category_name: data.category_name,
+            })),
+            wrong_sub_data => Err(StreamStatusFromEventSubMessageError::WrongTypeSubData(wrong_sub_data.sub_type()))
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StreamStatusFromEventSubMessageError {
+    #[error("Wrong type of sub data received: {0}")]
+    WrongTypeSubData(SubType),
+    #[error("Start time parsing failed: {0}")]
+    ParseError(#[from] ParseError),
+}
@@ -1,4 +1,11 @@
 start cmd /k "cargo watch -w server -w src -x "run""
 start cmd /k "cd app && trunk watch"
 start cmd /k "cd predictions-list && trunk watch"
-start cmd /k "cd hypetrain-normal && trunk watch"
\ No newline at end of file
+start cmd /k "cd hypetrain-normal && trunk watch"
+start cmd /k "cd polls-list && trunk watch"
+start cmd /k "cd goals-bar && trunk watch"
+start cmd /k "cd alerts-box && trunk watch"
+start cmd /k "cd redemptions-feed && trunk watch"
+start cmd /k "cd charity-tracker && trunk watch"
+start cmd /k "cd stream-uptime && trunk watch"
+start cmd /k "cd ad-break-countdown && trunk watch"
\ No newline at end of file
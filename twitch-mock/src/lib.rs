@@ -0,0 +1,161 @@
+//! A local stand-in for the parts of Twitch the server talks to: the auth endpoints,
+//! `/helix/users`, `/helix/eventsub/subscriptions`, `/helix/predictions` and
+//! `/helix/hypetrain/events`. It can also send signed EventSub webhooks to whatever
+//! callback a subscription was made with.
+
+mod routes;
+mod state;
+mod webhook;
+
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use actix_web::{web, App, HttpServer};
+use actix_web::dev::ServerHandle;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+pub use state::{MockUser, MockSubscription, MockTransport};
+pub use webhook::{DeliveryError, WebhookMessage};
+
+use routes::State;
+use state::{MockState, new_id};
+
+pub struct MockTwitch {
+    addr: SocketAddr,
+    state: State,
+    server: ServerHandle,
+    client: reqwest::Client,
+}
+
+impl MockTwitch {
+    /// Starts the mock on a random local port. Needs a running actix system,
+    /// like the one `#[actix_web::test]` gives.
+    pub async fn start(client_id: &str, client_secret: &str) -> std::io::Result<Self> {
+        let state: State = web::Data::new(Mutex::new(MockState::new(client_id, client_secret)));
+
+        let server = {
+            let state = state.clone();
+
+            HttpServer::new(move || App::new().app_data(state.clone()).configure(routes::configure))
+                .workers(1)
+                .disable_signals()
+                .bind(("127.0.0.1", 0))?
+        };
+
+        let addr = server.addrs()[0];
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        Ok(Self { addr, state, server: handle, client: reqwest::Client::new() })
+    }
+
+    pub async fn stop(self) {
+        self.server.stop(false).await;
+    }
+
+    /// Base for the auth endpoints, in place of `https://id.twitch.tv`
+    pub fn auth_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Base for helix, in place of `https://api.twitch.tv/helix`
+    pub fn api_url(&self) -> String {
+        format!("http://{}/helix", self.addr)
+    }
+
+    pub fn add_user(&self, login: &str) -> MockUser {
+        let mut state = self.lock();
+
+        let user = MockUser {
+            id: state.next_user_id.to_string(),
+            login: login.to_lowercase(),
+            display_name: login.to_string(),
+            broadcaster_type: "affiliate".to_string(),
+        };
+
+        state.next_user_id += 1;
+        state.users.insert(user.id.clone(), user.clone());
+
+        user
+    }
+
+    /// Gives a code the server can exchange for a user token, same as Twitch
+    /// does by redirecting back after the user accepts the scopes.
+    pub fn authorize(&self, user_id: &str, scopes: &[&str]) -> String {
+        let code = new_id();
+        let scopes = scopes.iter().map(|scope| scope.to_string()).collect();
+
+        self.lock().auth_codes.insert(code.clone(), (user_id.to_string(), scopes));
+
+        code
+    }
+
+    /// Makes every token given out so far invalid, like they all expired.
+    pub fn expire_tokens(&self) {
+        self.lock().access_tokens.clear();
+    }
+
+    pub fn set_max_total_cost(&self, max_total_cost: i64) {
+        self.lock().max_total_cost = max_total_cost;
+    }
+
+    /// Makes it the broadcaster's latest prediction on `GET /helix/predictions`,
+    /// `prediction` is the object as helix returns it.
+    pub fn add_prediction(&self, broadcaster_id: &str, prediction: Value) {
+        self.lock().predictions.entry(broadcaster_id.to_string()).or_default().insert(0, prediction);
+    }
+
+    /// Makes it the broadcaster's latest event on `GET /helix/hypetrain/events`,
+    /// `event` is the object as helix returns it.
+    pub fn add_hype_train_event(&self, broadcaster_id: &str, event: Value) {
+        self.lock().hype_train_events.entry(broadcaster_id.to_string()).or_default().insert(0, event);
+    }
+
+    pub fn subscriptions(&self) -> Vec<MockSubscription> {
+        self.lock().subscriptions.clone()
+    }
+
+    pub fn subscription(&self, sub_id: &str) -> Option<MockSubscription> {
+        self.lock().subscriptions.iter().find(|sub| sub.id == sub_id).cloned()
+    }
+
+    /// First subscription of `sub_type`, e.g. `channel.follow`
+    pub fn find_subscription(&self, sub_type: &str) -> Option<MockSubscription> {
+        self.lock().subscriptions.iter().find(|sub| sub.type_ == sub_type).cloned()
+    }
+
+    /// Sends an event to the subscription's callback. Returns the status and body it got back.
+    pub async fn send_notification(&self, sub_id: &str, event: Value) -> Result<(StatusCode, String), DeliveryError> {
+        let subscription = self.get_subscription(sub_id)?;
+
+        self.deliver(sub_id, &WebhookMessage::notification(&subscription, event)).await
+    }
+
+    /// Removes the subscription and tells its callback about it, like Twitch does
+    /// when a user revokes the authorization.
+    pub async fn revoke_subscription(&self, sub_id: &str) -> Result<(StatusCode, String), DeliveryError> {
+        let mut subscription = self.get_subscription(sub_id)?;
+
+        self.lock().subscriptions.retain(|sub| sub.id != sub_id);
+        subscription.status = "authorization_revoked".to_string();
+
+        webhook::deliver(&self.client, &subscription, &WebhookMessage::revocation(&subscription)).await
+    }
+
+    /// Sends a prepared message, signed with the subscription's secret.
+    pub async fn deliver(&self, sub_id: &str, message: &WebhookMessage) -> Result<(StatusCode, String), DeliveryError> {
+        let subscription = self.get_subscription(sub_id)?;
+
+        webhook::deliver(&self.client, &subscription, message).await
+    }
+
+    fn get_subscription(&self, sub_id: &str) -> Result<MockSubscription, DeliveryError> {
+        self.subscription(sub_id).ok_or_else(|| DeliveryError::UnknownSubscription(sub_id.to_string()))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().expect("Mock state lock poisoned")
+    }
+}
\ No newline at end of file
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web::http::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::state::{MockState, MockSubscription, MockTransport, TokenOwner, TOKEN_LIFETIME, new_id, now_rfc3339};
+
+const PAGE_SIZE: usize = 100;
+
+pub(crate) type State = web::Data<Mutex<MockState>>;
+
+pub(crate) fn configure(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/oauth2/token", web::post().to(token))
+        .route("/oauth2/validate", web::get().to(validate))
+        .service(
+            web::scope("/helix")
+                .route("/users", web::get().to(users))
+                .route("/eventsub/subscriptions", web::post().to(create_subscription))
+                .route("/eventsub/subscriptions", web::get().to(list_subscriptions))
+                .route("/eventsub/subscriptions", web::delete().to(delete_subscription))
+                .route("/predictions", web::get().to(predictions))
+                .route("/hypetrain/events", web::get().to(hype_train_events))
+        );
+}
+
+// same shape as the errors helix sends
+fn helix_error(status: StatusCode, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(json!({
+        "error": status.canonical_reason().unwrap_or_default(),
+        "status": status.as_u16(),
+        "message": message
+    }))
+}
+
+fn auth_token<'a>(request: &'a HttpRequest, scheme: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .get("Authorization")?
+        .to_str()
+        .ok()?
+        .strip_prefix(scheme)
+        .map(str::trim)
+}
+
+// helix calls need the client id next to a token twitch knows
+fn check_helix_auth(request: &HttpRequest, state: &MockState) -> Result<TokenOwner, HttpResponse> {
+    let client_id = request
+        .headers()
+        .get("Client-Id")
+        .and_then(|val| val.to_str().ok());
+
+    if client_id != Some(state.client_id.as_str()) {
+        return Err(helix_error(StatusCode::UNAUTHORIZED, "Client-Id header is missing or invalid"));
+    }
+
+    auth_token(request, "Bearer")
+        .and_then(|token| state.access_tokens.get(token))
+        .cloned()
+        .ok_or_else(|| helix_error(StatusCode::UNAUTHORIZED, "Invalid OAuth token"))
+}
+
+#[derive(Deserialize)]
+struct TokenRequest {
+    client_id: String,
+    client_secret: String,
+    grant_type: String,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+async fn token(request: HttpRequest, body: web::Bytes, state: State) -> HttpResponse {
+    // twitch takes both json and form bodies here
+    let is_json = request
+        .headers()
+        .get("Content-Type")
+        .and_then(|val| val.to_str().ok())
+        .map_or(false, |val| val.starts_with("application/json"));
+
+    let token_request = if is_json {
+        serde_json::from_slice::<TokenRequest>(&body).ok()
+    } else {
+        serde_urlencoded::from_bytes::<TokenRequest>(&body).ok()
+    };
+
+    let token_request = match token_request {
+        Some(token_request) => token_request,
+        None => return helix_error(StatusCode::BAD_REQUEST, "missing or malformed parameters"),
+    };
+
+    let mut state = state.lock().expect("Mock state lock poisoned");
+
+    if token_request.client_id != state.client_id {
+        return helix_error(StatusCode::BAD_REQUEST, "invalid client");
+    }
+
+    if token_request.client_secret != state.client_secret {
+        return helix_error(StatusCode::FORBIDDEN, "invalid client secret");
+    }
+
+    match token_request.grant_type.as_str() {
+        "client_credentials" => {
+            let access_token = state.issue_app_token();
+
+            HttpResponse::Ok().json(json!({
+                "access_token": access_token,
+                "expires_in": TOKEN_LIFETIME,
+                "token_type": "bearer"
+            }))
+        },
+        "authorization_code" => {
+            let grant = token_request.code.and_then(|code| state.auth_codes.remove(&code));
+            let (user_id, scopes) = match grant {
+                Some(grant) => grant,
+                None => return helix_error(StatusCode::BAD_REQUEST, "Invalid authorization code"),
+            };
+
+            user_tokens_response(&mut state, &user_id, scopes)
+        },
+        "refresh_token" => {
+            let grant = token_request.refresh_token.and_then(|token| state.refresh_tokens.remove(&token));
+            let (user_id, scopes) = match grant {
+                Some(grant) => grant,
+                None => return helix_error(StatusCode::BAD_REQUEST, "Invalid refresh token"),
+            };
+
+            user_tokens_response(&mut state, &user_id, scopes)
+        },
+        _ => helix_error(StatusCode::BAD_REQUEST, "unsupported grant type"),
+    }
+}
+
+fn user_tokens_response(state: &mut MockState, user_id: &str, scopes: Vec<String>) -> HttpResponse {
+    let (access_token, refresh_token) = state.issue_user_tokens(user_id, &scopes);
+
+    HttpResponse::Ok().json(json!({
+        "access_token": access_token,
+        "expires_in": TOKEN_LIFETIME,
+        "refresh_token": refresh_token,
+        "scope": scopes,
+        "token_type": "bearer"
+    }))
+}
+
+async fn validate(request: HttpRequest, state: State) -> HttpResponse {
+    let state = state.lock().expect("Mock state lock poisoned");
+
+    let owner = auth_token(&request, "OAuth").and_then(|token| state.access_tokens.get(token));
+
+    match owner {
+        Some(TokenOwner::App) => HttpResponse::Ok().json(json!({
+            "client_id": state.client_id,
+            "scopes": [],
+            "expires_in": TOKEN_LIFETIME
+        })),
+        Some(TokenOwner::User { user_id, scopes }) => HttpResponse::Ok().json(json!({
+            "client_id": state.client_id,
+            "login": state.users.get(user_id).map(|user| user.login.clone()),
+            "user_id": user_id,
+            "scopes": scopes,
+            "expires_in": TOKEN_LIFETIME
+        })),
+        None => helix_error(StatusCode::UNAUTHORIZED, "invalid access token"),
+    }
+}
+
+async fn users(request: HttpRequest, state: State) -> HttpResponse {
+    let state = state.lock().expect("Mock state lock poisoned");
+
+    let owner = match check_helix_auth(&request, &state) {
+        Ok(owner) => owner,
+        Err(response) => return response,
+    };
+
+    // `id` can be given more than once, which serde's query structs can't take
+    let query: Vec<(String, String)> = serde_urlencoded::from_str(request.query_string()).unwrap_or_default();
+    let mut user_ids: Vec<String> = query.into_iter()
+        .filter(|(key, _)| key == "id")
+        .map(|(_, user_id)| user_id)
+        .collect();
+
+    // without ids it's the owner of the token, which an app token doesn't have
+    if user_ids.is_empty() {
+        match owner {
+            TokenOwner::User { user_id, .. } => user_ids.push(user_id),
+            TokenOwner::App => return helix_error(StatusCode::BAD_REQUEST, "Must provide an ID, Login or OAuth Token"),
+        }
+    }
+
+    HttpResponse::Ok().json(json!({
+        "data": user_ids.iter().filter_map(|user_id| state.users.get(user_id)).collect::<Vec<_>>()
+    }))
+}
+
+#[derive(Deserialize)]
+struct CreateSubRequest {
+    #[serde(rename = "type")]
+    type_: String,
+    version: String,
+    condition: Value,
+    transport: MockTransport,
+}
+
+async fn create_subscription(request: HttpRequest, body: web::Json<CreateSubRequest>, state: State) -> HttpResponse {
+    let mut state = state.lock().expect("Mock state lock poisoned");
+
+    let owner = match check_helix_auth(&request, &state) {
+        Ok(owner) => owner,
+        Err(response) => return response,
+    };
+
+    let body = body.into_inner();
+
+    // webhooks need the app token, websockets need a user one
+    match (body.transport.method.as_str(), &owner) {
+        ("webhook", TokenOwner::App) => {
+            let secret_len = body.transport.secret.as_ref().map_or(0, String::len);
+
+            if body.transport.callback.is_none() || !(10..=100).contains(&secret_len) {
+                return helix_error(StatusCode::BAD_REQUEST, "webhook transport needs a callback and a 10 to 100 characters secret");
+            }
+        },
+        ("websocket", TokenOwner::User { .. }) => {
+            if body.transport.session_id.is_none() {
+                return helix_error(StatusCode::BAD_REQUEST, "websocket transport needs a session_id");
+            }
+        },
+        ("webhook", _) | ("websocket", _) => return helix_error(StatusCode::UNAUTHORIZED, "wrong token type for the transport"),
+        _ => return helix_error(StatusCode::BAD_REQUEST, "unknown transport method"),
+    }
+
+    let exists = state.subscriptions.iter().any(|sub|
+        sub.type_ == body.type_
+            && sub.condition == body.condition
+            && sub.transport.method == body.transport.method
+    );
+
+    if exists {
+        return helix_error(StatusCode::CONFLICT, "subscription already exists");
+    }
+
+    let cost = state.subscription_cost(&body.condition);
+    if state.total_cost() + cost > state.max_total_cost {
+        return helix_error(StatusCode::TOO_MANY_REQUESTS, "subscription cost exceeds max_total_cost");
+    }
+
+    let subscription = MockSubscription {
+        id: new_id(),
+        // the callback verification is skipped, it's the webhook handler that's under test
+        status: "enabled".to_string(),
+        type_: body.type_,
+        version: body.version,
+        cost,
+        condition: body.condition,
+        transport: body.transport,
+        created_at: now_rfc3339(),
+    };
+
+    state.subscriptions.push(subscription.clone());
+
+    HttpResponse::Accepted().json(json!({
+        "data": [subscription],
+        "total": state.subscriptions.len(),
+        "total_cost": state.total_cost(),
+        "max_total_cost": state.max_total_cost
+    }))
+}
+
+#[derive(Deserialize)]
+struct ListSubQuery {
+    #[serde(default)]
+    after: Option<String>,
+}
+
+async fn list_subscriptions(request: HttpRequest, query: web::Query<ListSubQuery>, state: State) -> HttpResponse {
+    let state = state.lock().expect("Mock state lock poisoned");
+
+    match check_helix_auth(&request, &state) {
+        Ok(TokenOwner::App) => {},
+        Ok(TokenOwner::User { .. }) => return helix_error(StatusCode::UNAUTHORIZED, "app access token is required"),
+        Err(response) => return response,
+    }
+
+    // cursor is just the offset of the next page
+    let start = match query.after.as_deref().map(str::parse::<usize>) {
+        None => 0,
+        Some(Ok(start)) => start,
+        Some(Err(_)) => return helix_error(StatusCode::BAD_REQUEST, "invalid cursor"),
+    };
+
+    let page: Vec<_> = state.subscriptions.iter().skip(start).take(PAGE_SIZE).collect();
+    let next = start + page.len();
+
+    let pagination = if next < state.subscriptions.len() {
+        json!({ "cursor": next.to_string() })
+    } else {
+        json!({})
+    };
+
+    HttpResponse::Ok().json(json!({
+        "data": page,
+        "total": state.subscriptions.len(),
+        "total_cost": state.total_cost(),
+        "max_total_cost": state.max_total_cost,
+        "pagination": pagination
+    }))
+}
+
+#[derive(Deserialize)]
+struct DeleteSubQuery {
+    id: String,
+}
+
+async fn delete_subscription(request: HttpRequest, query: web::Query<DeleteSubQuery>, state: State) -> HttpResponse {
+    let mut state = state.lock().expect("Mock state lock poisoned");
+
+    match check_helix_auth(&request, &state) {
+        Ok(TokenOwner::App) => {},
+        Ok(TokenOwner::User { .. }) => return helix_error(StatusCode::UNAUTHORIZED, "app access token is required"),
+        Err(response) => return response,
+    }
+
+    let before = state.subscriptions.len();
+    state.subscriptions.retain(|sub| sub.id != query.id);
+
+    if state.subscriptions.len() == before {
+        return helix_error(StatusCode::NOT_FOUND, "subscription not found");
+    }
+
+    HttpResponse::NoContent().finish()
+}
+
+#[derive(Deserialize)]
+struct BroadcasterQuery {
+    broadcaster_id: String,
+    #[serde(default)]
+    first: Option<usize>,
+}
+
+// only the broadcaster's own token can read these
+fn broadcaster_events(
+    request: &HttpRequest,
+    query: &BroadcasterQuery,
+    state: &MockState,
+    scope: &str,
+    events: &HashMap<String, Vec<Value>>
+) -> HttpResponse {
+    match check_helix_auth(request, state) {
+        Ok(TokenOwner::User { user_id, scopes }) if user_id == query.broadcaster_id => {
+            if !scopes.iter().any(|token_scope| token_scope == scope) {
+                return helix_error(StatusCode::UNAUTHORIZED, &format!("Missing scope: {}", scope));
+            }
+        },
+        Ok(_) => return helix_error(StatusCode::UNAUTHORIZED, "The ID in broadcaster_id must match the user ID in the access token"),
+        Err(response) => return response,
+    }
+
+    let page: Vec<_> = events
+        .get(&query.broadcaster_id)
+        .into_iter()
+        .flatten()
+        .take(query.first.unwrap_or(20))
+        .collect();
+
+    HttpResponse::Ok().json(json!({
+        "data": page,
+        "pagination": {}
+    }))
+}
+
+async fn predictions(request: HttpRequest, query: web::Query<BroadcasterQuery>, state: State) -> HttpResponse {
+    let state = state.lock().expect("Mock state lock poisoned");
+
+    broadcaster_events(&request, &query, &state, "channel:read:predictions", &state.predictions)
+}
+
+async fn hype_train_events(request: HttpRequest, query: web::Query<BroadcasterQuery>, state: State) -> HttpResponse {
+    let state = state.lock().expect("Mock state lock poisoned");
+
+    broadcaster_events(&request, &query, &state, "channel:read:hype_train", &state.hype_train_events)
+}
\ No newline at end of file
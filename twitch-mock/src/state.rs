@@ -0,0 +1,143 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+// same as twitch gives to new apps
+const DEFAULT_MAX_TOTAL_COST: i64 = 10000;
+pub(crate) const TOKEN_LIFETIME: i64 = 4 * 60 * 60;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MockUser {
+    pub id: String,
+    pub login: String,
+    pub display_name: String,
+    pub broadcaster_type: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MockTransport {
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback: Option<String>,
+    // twitch never gives the secret back
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MockSubscription {
+    pub id: String,
+    pub status: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub version: String,
+    pub cost: i64,
+    pub condition: Value,
+    pub transport: MockTransport,
+    pub created_at: String,
+}
+
+// what a token was given out for
+#[derive(Clone)]
+pub(crate) enum TokenOwner {
+    App,
+    User { user_id: String, scopes: Vec<String> },
+}
+
+pub(crate) struct MockState {
+    pub client_id: String,
+    pub client_secret: String,
+    pub max_total_cost: i64,
+    pub next_user_id: u64,
+    pub users: HashMap<String, MockUser>,
+    pub access_tokens: HashMap<String, TokenOwner>,
+    // refresh token -> user id and scopes
+    pub refresh_tokens: HashMap<String, (String, Vec<String>)>,
+    // authorization code -> user id and scopes
+    pub auth_codes: HashMap<String, (String, Vec<String>)>,
+    pub subscriptions: Vec<MockSubscription>,
+    // broadcaster id -> helix objects, newest first
+    pub predictions: HashMap<String, Vec<Value>>,
+    pub hype_train_events: HashMap<String, Vec<Value>>,
+}
+
+impl MockState {
+    pub fn new(client_id: &str, client_secret: &str) -> Self {
+        Self {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            max_total_cost: DEFAULT_MAX_TOTAL_COST,
+            next_user_id: 10000,
+            users: HashMap::new(),
+            access_tokens: HashMap::new(),
+            refresh_tokens: HashMap::new(),
+            auth_codes: HashMap::new(),
+            subscriptions: Vec::new(),
+            predictions: HashMap::new(),
+            hype_train_events: HashMap::new(),
+        }
+    }
+
+    pub fn issue_app_token(&mut self) -> String {
+        let token = new_id();
+        self.access_tokens.insert(token.clone(), TokenOwner::App);
+
+        token
+    }
+
+    // gives out a new access and refresh token pair
+    pub fn issue_user_tokens(&mut self, user_id: &str, scopes: &[String]) -> (String, String) {
+        let access_token = new_id();
+        let refresh_token = new_id();
+
+        self.access_tokens.insert(access_token.clone(), TokenOwner::User {
+            user_id: user_id.to_string(),
+            scopes: scopes.to_vec()
+        });
+        self.refresh_tokens.insert(refresh_token.clone(), (user_id.to_string(), scopes.to_vec()));
+
+        (access_token, refresh_token)
+    }
+
+    pub fn total_cost(&self) -> i64 {
+        self.subscriptions.iter().map(|sub| sub.cost).sum()
+    }
+
+    // subscriptions for users that authorized the app are free, like on twitch
+    pub fn subscription_cost(&self, condition: &Value) -> i64 {
+        let authorized_users: HashSet<&str> = self.access_tokens
+            .values()
+            .filter_map(|owner| match owner {
+                TokenOwner::User { user_id, .. } => Some(user_id.as_str()),
+                TokenOwner::App => None,
+            })
+            .collect();
+
+        let condition_users = ["broadcaster_user_id", "to_broadcaster_user_id", "user_id"]
+            .into_iter()
+            .filter_map(|field| condition.get(field).and_then(Value::as_str));
+
+        let mut has_user = false;
+        for user_id in condition_users {
+            if !authorized_users.contains(user_id) {
+                return 1;
+            }
+
+            has_user = true;
+        }
+
+        if has_user { 0 } else { 1 }
+    }
+}
+
+pub(crate) fn new_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+pub(crate) fn now_rfc3339() -> String {
+    OffsetDateTime::now_utc().format(&Rfc3339).expect("Current time is always formattable")
+}
\ No newline at end of file
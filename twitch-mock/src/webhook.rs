@@ -0,0 +1,98 @@
+use hmac::{Hmac, Mac};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::state::{MockSubscription, new_id, now_rfc3339};
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Error)]
+pub enum DeliveryError {
+    #[error("No subscription with id {0}")]
+    UnknownSubscription(String),
+    #[error("Subscription {0} doesn't use the webhook transport")]
+    NotWebhook(String),
+    #[error("Sending the webhook failed: {0}")]
+    HttpError(#[from] reqwest::Error),
+}
+
+/// A webhook message as Twitch would send it. Keep the same message to deliver it again,
+/// or change `message_id`/`timestamp`/`signing_secret` to check how those are handled.
+#[derive(Clone, Debug)]
+pub struct WebhookMessage {
+    pub message_id: String,
+    pub message_type: String,
+    pub timestamp: String,
+    pub body: Value,
+    /// Signs with this in place of the subscription's secret, to forge a message.
+    pub signing_secret: Option<String>,
+}
+
+impl WebhookMessage {
+    pub fn notification(subscription: &MockSubscription, event: Value) -> Self {
+        Self::new("notification", json!({ "subscription": subscription, "event": event }))
+    }
+
+    pub fn revocation(subscription: &MockSubscription) -> Self {
+        Self::new("revocation", json!({ "subscription": subscription }))
+    }
+
+    pub fn challenge(subscription: &MockSubscription, challenge: &str) -> Self {
+        Self::new("webhook_callback_verification", json!({ "subscription": subscription, "challenge": challenge }))
+    }
+
+    fn new(message_type: &str, body: Value) -> Self {
+        Self {
+            message_id: new_id(),
+            message_type: message_type.to_string(),
+            timestamp: now_rfc3339(),
+            body,
+            signing_secret: None,
+        }
+    }
+}
+
+// signed the same way twitch does, over id + timestamp + body
+pub(crate) fn sign(secret: &str, message_id: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut hasher = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    hasher.update(message_id.as_bytes());
+    hasher.update(timestamp.as_bytes());
+    hasher.update(body);
+
+    "sha256=".to_string() + &hex::encode(hasher.finalize().into_bytes())
+}
+
+pub(crate) async fn deliver(
+    client: &reqwest::Client,
+    subscription: &MockSubscription,
+    message: &WebhookMessage
+) -> Result<(StatusCode, String), DeliveryError> {
+    let (callback, secret) = match (&subscription.transport.callback, &subscription.transport.secret) {
+        (Some(callback), Some(secret)) if subscription.transport.method == "webhook" => (callback, secret),
+        _ => return Err(DeliveryError::NotWebhook(subscription.id.clone())),
+    };
+
+    let body = serde_json::to_vec(&message.body).expect("JSON values always serialize");
+    let secret = message.signing_secret.as_ref().unwrap_or(secret);
+    let signature = sign(secret, &message.message_id, &message.timestamp, &body);
+
+    let response = client.post(callback)
+        .header("Content-Type", "application/json")
+        .header("Twitch-Eventsub-Message-Id", &message.message_id)
+        .header("Twitch-Eventsub-Message-Retry", "0")
+        .header("Twitch-Eventsub-Message-Type", &message.message_type)
+        .header("Twitch-Eventsub-Message-Signature", signature)
+        .header("Twitch-Eventsub-Message-Timestamp", &message.timestamp)
+        .header("Twitch-Eventsub-Subscription-Type", &subscription.type_)
+        .header("Twitch-Eventsub-Subscription-Version", &subscription.version)
+        .body(body)
+        .send()
+        .await?;
+
+    let status = response.status();
+    let text = response.text().await?;
+
+    Ok((status, text))
+}
\ No newline at end of file
@@ -0,0 +1,37 @@
+/*
+ * 
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 
+ * 
+ * Generated by: https://openapi-generator.tech
+ */
+
+
+
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct CustomRewardInfo {
+    #[serde(rename = "cost")]
+    pub cost: i64,
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "is_enabled")]
+    pub is_enabled: bool,
+    #[serde(rename = "title")]
+    pub title: String,
+}
+
+impl CustomRewardInfo {
+    pub fn new(cost: i64, id: String, is_enabled: bool, title: String) -> CustomRewardInfo {
+        CustomRewardInfo {
+            cost,
+            id,
+            is_enabled,
+            title,
+        }
+    }
+}
+
+
@@ -1,3 +1,5 @@
+pub mod custom_reward_info;
+pub use self::custom_reward_info::CustomRewardInfo;
 pub mod login_token_response;
 pub use self::login_token_response::LoginTokenResponse;
 pub mod login_url_response;
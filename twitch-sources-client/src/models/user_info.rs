@@ -13,13 +13,16 @@
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct UserInfo {
+    #[serde(rename = "relogin_needed")]
+    pub relogin_needed: bool,
     #[serde(rename = "username")]
     pub username: String,
 }
 
 impl UserInfo {
-    pub fn new(username: String) -> UserInfo {
+    pub fn new(relogin_needed: bool, username: String) -> UserInfo {
         UserInfo {
+            relogin_needed,
             username,
         }
     }